@@ -199,10 +199,15 @@ Examples:
 // newMergeCmd creates the merge command group.
 func newMergeCmd() *cobra.Command {
 	var (
-		tables       string
-		strategy     string
-		dryRun       bool
-		remoteServer string
+		tables               string
+		strategy             string
+		dryRun               bool
+		remoteServer         string
+		allowVersionMismatch bool
+		maxAuditRows         int64
+		logLevel             string
+		maxConflicts         int64
+		maxConflictRatio     float64
 	)
 
 	cmd := &cobra.Command{
@@ -278,6 +283,7 @@ Examples:
 
 			// Create merger
 			merger := replinit.NewMerger(localPool, remotePool, nil)
+			merger.SetAllowVersionMismatch(allowVersionMismatch)
 
 			// Get table info with PKs
 			var tablesToMerge []replinit.MergeTableInfo
@@ -398,6 +404,10 @@ Examples:
 				RemoteServer:     remoteServer,
 				QuiesceTimeoutMs: 30000, // 30 second timeout for quiesce
 				DryRun:           false,
+				MaxAuditRows:     maxAuditRows,
+				LogLevel:         replinit.MergeLogLevel(logLevel),
+				MaxConflicts:     maxConflicts,
+				MaxConflictRatio: maxConflictRatio,
 			}
 
 			result, err := merger.ExecuteMerge(ctx, mergeConfig)
@@ -414,6 +424,9 @@ Examples:
 			fmt.Printf("Resolved:          %d\n", result.ConflictsResolved)
 			fmt.Printf("Transferred A→B:   %d\n", result.RowsTransferredAToB)
 			fmt.Printf("Transferred B→A:   %d\n", result.RowsTransferredBToA)
+			if result.AuditTruncated {
+				fmt.Printf("Audit log truncated after %d rows (--max-audit-rows)\n", result.AuditRowsWritten)
+			}
 
 			if len(result.Errors) > 0 {
 				fmt.Println("\nWarnings/Errors:")
@@ -435,6 +448,11 @@ Examples:
 	cmd.Flags().StringVar(&strategy, "strategy", "prefer-node-a", "Conflict resolution strategy")
 	cmd.Flags().BoolVar(&dryRun, "dry-run", false, "Preview changes without applying")
 	cmd.Flags().StringVar(&remoteServer, "remote-server", "node_b_fdw", "Name of postgres_fdw foreign server")
+	cmd.Flags().BoolVar(&allowVersionMismatch, "allow-version-mismatch", false, "Warn instead of failing when nodes run different steep_repl major versions")
+	cmd.Flags().Int64Var(&maxAuditRows, "max-audit-rows", 0, "Stop writing merge audit rows after this many (0 = unlimited)")
+	cmd.Flags().Int64Var(&maxConflicts, "max-conflicts", 0, "Abort before applying changes if more conflicts are found (0 = unlimited)")
+	cmd.Flags().Float64Var(&maxConflictRatio, "max-conflict-ratio", 0, "Abort if conflicts exceed this fraction of all rows, e.g. 0.05 (0 = unlimited)")
+	cmd.Flags().StringVar(&logLevel, "log-level", "changes_only", "Rows written to the merge audit log: all, conflicts_only, changes_only")
 
 	cmd.MarkFlagRequired("tables")
 
@@ -0,0 +1,33 @@
+package main
+
+import "testing"
+
+func TestMergeCmd_AllowVersionMismatchFlag(t *testing.T) {
+	cmd := newMergeCmd()
+	if err := cmd.ParseFlags([]string{"--tables", "users", "--allow-version-mismatch"}); err != nil {
+		t.Fatalf("parse flags: %v", err)
+	}
+
+	allow, err := cmd.Flags().GetBool("allow-version-mismatch")
+	if err != nil {
+		t.Fatalf("get flag: %v", err)
+	}
+	if !allow {
+		t.Error("--allow-version-mismatch should be set on merge")
+	}
+}
+
+func TestMergeCmd_MaxAuditRowsFlag(t *testing.T) {
+	cmd := newMergeCmd()
+	if err := cmd.ParseFlags([]string{"--tables", "users", "--max-audit-rows", "250"}); err != nil {
+		t.Fatalf("parse flags: %v", err)
+	}
+
+	limit, err := cmd.Flags().GetInt64("max-audit-rows")
+	if err != nil {
+		t.Fatalf("get flag: %v", err)
+	}
+	if limit != 250 {
+		t.Errorf("max-audit-rows = %d, want 250", limit)
+	}
+}
@@ -1,7 +1,10 @@
 //! Audit log table for steep_repl extension.
 //!
 //! This module creates the audit_log table for an immutable record
-//! of system activity with full before/after state capture.
+//! of system activity with full before/after state capture, plus an
+//! NDJSON export (optionally compressed) for shipping entries to external
+//! log collectors, a polling feed for clients that cannot LISTEN, and
+//! a NOTIFY queue health check for those that can.
 
 use pgrx::prelude::*;
 
@@ -41,6 +44,146 @@ CREATE INDEX idx_audit_log_actor ON steep_repl.audit_log(actor);
 CREATE INDEX idx_audit_log_action ON steep_repl.audit_log(action);
 CREATE INDEX idx_audit_log_target ON steep_repl.audit_log(target_type, target_id)
     WHERE target_type IS NOT NULL;
+
+-- Export audit log entries as newline-delimited JSON
+-- Severity is derived from success: failed actions are 'error', others 'info'
+CREATE FUNCTION steep_repl.export_audit_ndjson(
+    p_since TIMESTAMPTZ DEFAULT NULL,
+    p_min_severity TEXT DEFAULT 'info'
+)
+RETURNS SETOF TEXT AS $$
+DECLARE
+    v_min_rank INTEGER;
+BEGIN
+    v_min_rank := CASE p_min_severity
+        WHEN 'info' THEN 0
+        WHEN 'error' THEN 1
+    END;
+
+    IF v_min_rank IS NULL THEN
+        RAISE EXCEPTION 'Unknown severity: %', p_min_severity
+            USING HINT = 'Valid severities are info, error';
+    END IF;
+
+    RETURN QUERY
+    SELECT jsonb_build_object(
+        'id', a.id,
+        'occurred_at', a.occurred_at,
+        'severity', CASE WHEN a.success THEN 'info' ELSE 'error' END,
+        'action', a.action,
+        'actor', a.actor,
+        'target_type', a.target_type,
+        'target_id', a.target_id,
+        'old_value', a.old_value,
+        'new_value', a.new_value,
+        'client_ip', host(a.client_ip),
+        'success', a.success,
+        'error_message', a.error_message
+    )::TEXT
+    FROM steep_repl.audit_log a
+    WHERE (p_since IS NULL OR a.occurred_at >= p_since)
+      AND (CASE WHEN a.success THEN 0 ELSE 1 END) >= v_min_rank
+    ORDER BY a.id;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.export_audit_ndjson(TIMESTAMPTZ, TEXT) IS
+    'Export audit log entries as one JSON object per row, filtered by time and minimum severity';
+
+-- Compressed NDJSON export, returned as bytea chunks of up to 1MB
+-- Uses the same command-line codecs as snapshot data files (gzip, lz4, zstd);
+-- 'none' returns the raw NDJSON bytes. Concatenating the chunks in order
+-- yields one compressed stream. Each call compresses into its own temp file,
+-- which is removed on success and on error.
+CREATE FUNCTION steep_repl.export_audit_ndjson(
+    p_since TIMESTAMPTZ,
+    p_min_severity TEXT,
+    p_compression TEXT
+)
+RETURNS SETOF BYTEA AS $$
+DECLARE
+    v_codec TEXT;
+    v_path TEXT := '/tmp/steep_audit_' || pg_backend_pid() || '_' || gen_random_uuid() || '.ndjson';
+    v_chunk BYTEA;
+    v_offset BIGINT := 0;
+BEGIN
+    v_codec := CASE p_compression
+        WHEN 'none' THEN 'cat'
+        WHEN 'gzip' THEN 'gzip -nc'
+        WHEN 'lz4' THEN 'lz4 -qc'
+        WHEN 'zstd' THEN 'zstd -qc'
+    END;
+
+    IF v_codec IS NULL THEN
+        RAISE EXCEPTION 'Unknown compression type: %', p_compression;
+    END IF;
+
+    BEGIN
+        -- Stream one line per row into the codec. CSV with quote and delimiter
+        -- characters that never occur in JSON text passes each line through
+        -- unescaped, and nothing is aggregated in memory.
+        EXECUTE format(
+            'COPY (SELECT line FROM steep_repl.export_audit_ndjson(%L, %L) line)
+             TO PROGRAM %L WITH (FORMAT csv, QUOTE E''\x01'', DELIMITER E''\x02'', ENCODING ''UTF8'')',
+            p_since, p_min_severity,
+            v_codec || ' > ' || quote_literal(v_path)
+        );
+
+        LOOP
+            v_chunk := pg_read_binary_file(v_path, v_offset, 1048576);
+            EXIT WHEN length(v_chunk) = 0;
+            RETURN NEXT v_chunk;
+            v_offset := v_offset + length(v_chunk);
+        END LOOP;
+    EXCEPTION WHEN OTHERS THEN
+        EXECUTE format('COPY (SELECT 1) TO PROGRAM %L', 'rm -f ' || quote_literal(v_path));
+        RAISE;
+    END;
+
+    EXECUTE format('COPY (SELECT 1) TO PROGRAM %L', 'rm -f ' || quote_literal(v_path));
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.export_audit_ndjson(TIMESTAMPTZ, TEXT, TEXT) IS
+    'Export audit log NDJSON as compressed bytea chunks (none, gzip, lz4, zstd)';
+
+-- Poll audit events past a watermark, for clients that cannot hold a LISTEN
+-- connection (e.g. behind transaction-pooling pgbouncer). Clients pass the
+-- highest id they have seen and advance it from the returned rows.
+CREATE FUNCTION steep_repl.poll_events(p_after_id BIGINT DEFAULT 0, p_limit INTEGER DEFAULT 100)
+RETURNS TABLE (
+    id BIGINT,
+    occurred_at TIMESTAMPTZ,
+    action TEXT,
+    actor TEXT,
+    target_type TEXT,
+    target_id TEXT,
+    success BOOLEAN,
+    new_value JSONB
+) AS $$
+    SELECT a.id, a.occurred_at, a.action, a.actor, a.target_type, a.target_id, a.success, a.new_value
+    FROM steep_repl.audit_log a
+    WHERE a.id > COALESCE(p_after_id, 0)
+    ORDER BY a.id
+    LIMIT p_limit;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.poll_events IS
+    'Return audit events with id greater than the watermark, in id order, for polling clients';
+
+-- NOTIFY queue fill level, to catch a stuck listener before the queue fills
+-- A full queue makes every committing transaction that issued NOTIFY fail.
+CREATE FUNCTION steep_repl.notify_queue_health(p_warn_threshold DOUBLE PRECISION DEFAULT 0.5)
+RETURNS TABLE (
+    usage DOUBLE PRECISION,
+    status TEXT  -- ok, warning
+) AS $$
+    SELECT u, CASE WHEN u >= p_warn_threshold THEN 'warning' ELSE 'ok' END
+    FROM pg_notification_queue_usage() u;
+$$ LANGUAGE sql VOLATILE;
+
+COMMENT ON FUNCTION steep_repl.notify_queue_health IS
+    'NOTIFY queue usage fraction (0-1) with ok/warning status against a threshold';
 "#,
     name = "create_audit_log_table",
     requires = ["create_schema"],
@@ -49,6 +192,7 @@ CREATE INDEX idx_audit_log_target ON steep_repl.audit_log(target_type, target_id
 #[cfg(any(test, feature = "pg_test"))]
 #[pg_schema]
 mod tests {
+    use crate::test_support::ScratchDir;
     use pgrx::prelude::*;
 
     #[pg_test]
@@ -107,4 +251,167 @@ mod tests {
         Spi::run("DELETE FROM steep_repl.audit_log WHERE actor = 'steep_repl@localhost'")
             .expect("cleanup should succeed");
     }
+
+    #[pg_test]
+    fn test_export_audit_ndjson() {
+        Spi::run(
+            "INSERT INTO steep_repl.audit_log (occurred_at, action, actor, target_type, target_id, success, error_message)
+             VALUES
+                (now() - interval '2 days', 'node.registered', 'ndjson@localhost', 'node', 'node-a', true, NULL),
+                (now(), 'node.registered', 'ndjson@localhost', 'node', 'node-b', true, NULL),
+                (now(), 'node.removed', 'ndjson@localhost', 'node', 'node-c', false, 'node busy')"
+        ).expect("audit log insert should succeed");
+
+        // Every line parses as JSON
+        let parsed = Spi::get_one::<i64>(
+            "SELECT count(line::jsonb) FROM steep_repl.export_audit_ndjson() line
+             WHERE line::jsonb->>'actor' = 'ndjson@localhost'"
+        );
+        assert_eq!(parsed, Ok(Some(3)));
+
+        let recent = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.export_audit_ndjson(now() - interval '1 day') line
+             WHERE line::jsonb->>'actor' = 'ndjson@localhost'"
+        );
+        assert_eq!(recent, Ok(Some(2)), "since filter should drop the old entry");
+
+        let errors = Spi::get_one::<String>(
+            "SELECT string_agg(line::jsonb->>'target_id', ',')
+             FROM steep_repl.export_audit_ndjson(NULL, 'error') line
+             WHERE line::jsonb->>'actor' = 'ndjson@localhost'"
+        );
+        assert_eq!(errors, Ok(Some("node-c".to_string())), "severity filter should keep only failures");
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE actor = 'ndjson@localhost'")
+            .expect("cleanup should succeed");
+    }
+
+    #[pg_test]
+    fn test_export_audit_ndjson_compressed_roundtrip() {
+        Spi::run(
+            r#"INSERT INTO steep_repl.audit_log (action, actor, target_id, new_value, success)
+               VALUES ('node.registered', 'gzip@localhost', 'node-a', '{"note": "quote \" and \\ slash"}', true),
+                      ('node.removed', 'gzip@localhost', 'node-b', NULL, false)"#
+        ).expect("audit log insert should succeed");
+
+        let dir = ScratchDir::new("audit_gzip");
+        let output = dir.file("audit.ndjson");
+        Spi::run(&format!(
+            "COPY (SELECT translate(encode(string_agg(chunk, ''::bytea ORDER BY n), 'base64'), E'\\n', '')
+             FROM steep_repl.export_audit_ndjson(NULL, 'info', 'gzip') WITH ORDINALITY AS c(chunk, n))
+             TO PROGRAM 'base64 -d | gzip -dc > {}'",
+            output
+        )).expect("decompress export");
+
+        let matches = Spi::get_one::<bool>(&format!(
+            "SELECT pg_read_file('{}')
+                  = (SELECT string_agg(line || E'\\n', '') FROM steep_repl.export_audit_ndjson() line)",
+            output
+        ));
+        assert_eq!(matches, Ok(Some(true)), "gzip export should decompress to the plain NDJSON");
+
+        let uncompressed = Spi::get_one::<bool>(
+            "SELECT (SELECT convert_from(string_agg(chunk, ''::bytea ORDER BY n), 'UTF8')
+                     FROM steep_repl.export_audit_ndjson(NULL, 'info', 'none') WITH ORDINALITY AS c(chunk, n))
+                  = (SELECT string_agg(line || E'\\n', '') FROM steep_repl.export_audit_ndjson() line)"
+        );
+        assert_eq!(uncompressed, Ok(Some(true)), "'none' should return the NDJSON bytes unchanged");
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE actor = 'gzip@localhost'")
+            .expect("cleanup should succeed");
+    }
+
+    #[pg_test]
+    fn test_export_audit_ndjson_compressed_empty_window() {
+        // Nothing can match a window starting in the future
+        let chunks = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.export_audit_ndjson(now() + interval '1 day', 'info', 'none')"
+        );
+        assert_eq!(chunks, Ok(Some(0)), "'none' should return no chunks for an empty window");
+
+        let dir = ScratchDir::new("audit_empty");
+        let output = dir.file("audit.ndjson");
+        Spi::run(&format!(
+            "COPY (SELECT translate(encode(COALESCE(string_agg(chunk, ''::bytea ORDER BY n), ''), 'base64'), E'\\n', '')
+             FROM steep_repl.export_audit_ndjson(now() + interval '1 day', 'info', 'gzip') WITH ORDINALITY AS c(chunk, n))
+             TO PROGRAM 'base64 -d | gzip -dc > {}'",
+            output
+        )).expect("empty gzip export should decompress");
+
+        let size = Spi::get_one::<i64>(&format!("SELECT size FROM pg_stat_file('{}')", output));
+        assert_eq!(size, Ok(Some(0)), "an empty window should decompress to nothing");
+    }
+
+    #[pg_test]
+    fn test_export_audit_ndjson_compressed_removes_temp_file_on_error() {
+        Spi::run(
+            "DO $$
+            BEGIN
+                PERFORM steep_repl.export_audit_ndjson(NULL, 'bogus', 'gzip');
+            EXCEPTION WHEN OTHERS THEN
+                NULL;
+            END $$"
+        ).expect("failed export should be caught");
+
+        let leftover = Spi::get_one::<i64>(
+            "SELECT count(*) FROM pg_ls_dir('/tmp') f
+             WHERE f LIKE 'steep\\_audit\\_' || pg_backend_pid() || '\\_%'"
+        );
+        assert_eq!(leftover, Ok(Some(0)), "a failed export should not leave its temp file behind");
+    }
+
+    // No entry is ever classified as a warning, so it is not a valid filter
+    #[pg_test(error = "Unknown severity: warning")]
+    fn test_export_audit_ndjson_rejects_warning_severity() {
+        Spi::run("SELECT steep_repl.export_audit_ndjson(NULL, 'warning')")
+            .expect("warning severity should be rejected");
+    }
+
+    #[pg_test(error = "Unknown compression type: brotli")]
+    fn test_export_audit_ndjson_unknown_compression() {
+        Spi::run("SELECT steep_repl.export_audit_ndjson(NULL, 'info', 'brotli')")
+            .expect("unknown codec should be rejected");
+    }
+
+    #[pg_test]
+    fn test_poll_events_after_watermark() {
+        let watermark = Spi::get_one::<i64>("SELECT COALESCE(max(id), 0) FROM steep_repl.audit_log")
+            .expect("watermark query should succeed")
+            .unwrap();
+
+        Spi::run(
+            "INSERT INTO steep_repl.audit_log (action, actor)
+             VALUES ('poll.first', 'poll@localhost'), ('poll.second', 'poll@localhost'), ('poll.third', 'poll@localhost')"
+        ).expect("audit log insert should succeed");
+
+        let actions = Spi::get_one::<String>(&format!(
+            "SELECT string_agg(action, ',' ORDER BY id) FROM steep_repl.poll_events({})",
+            watermark
+        ));
+        assert_eq!(actions, Ok(Some("poll.first,poll.second,poll.third".to_string())));
+
+        // Advancing the watermark past the first event skips it
+        let next = Spi::get_one::<String>(
+            "SELECT string_agg(action, ',') FROM steep_repl.poll_events(
+                (SELECT id FROM steep_repl.audit_log WHERE action = 'poll.first'), 1)"
+        );
+        assert_eq!(next, Ok(Some("poll.second".to_string())), "limit should cap the batch");
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE actor = 'poll@localhost'")
+            .expect("cleanup should succeed");
+    }
+
+    #[pg_test]
+    fn test_notify_queue_health() {
+        let usage = Spi::get_one::<bool>(
+            "SELECT usage >= 0 AND usage <= 1 FROM steep_repl.notify_queue_health()"
+        );
+        assert_eq!(usage, Ok(Some(true)), "usage should be a fraction in [0,1]");
+
+        let status = Spi::get_one::<String>("SELECT status FROM steep_repl.notify_queue_health(0)");
+        assert_eq!(status, Ok(Some("warning".to_string())), "a zero threshold should always warn");
+    }
 }
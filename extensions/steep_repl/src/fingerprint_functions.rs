@@ -55,7 +55,7 @@ $$ LANGUAGE sql;
 
 COMMENT ON FUNCTION steep_repl.capture_fingerprint(TEXT, TEXT, TEXT) IS 'Capture and store schema fingerprint for a table with node_id';
 
--- Capture all user tables for a specific node
+-- Capture all eligible user tables for a specific node (see steep_repl.eligible_tables)
 CREATE FUNCTION steep_repl.capture_all_fingerprints(p_node_id TEXT)
 RETURNS INTEGER AS $$
 DECLARE
@@ -63,18 +63,17 @@ DECLARE
     rec RECORD;
 BEGIN
     FOR rec IN
-        SELECT schemaname, tablename
-        FROM pg_tables
-        WHERE schemaname NOT IN ('pg_catalog', 'information_schema', 'steep_repl')
+        SELECT table_schema, table_name
+        FROM steep_repl.eligible_tables()
     LOOP
-        PERFORM steep_repl.capture_fingerprint(p_node_id, rec.schemaname, rec.tablename);
+        PERFORM steep_repl.capture_fingerprint(p_node_id, rec.table_schema, rec.table_name);
         v_count := v_count + 1;
     END LOOP;
     RETURN v_count;
 END;
 $$ LANGUAGE plpgsql;
 
-COMMENT ON FUNCTION steep_repl.capture_all_fingerprints(TEXT) IS 'Capture fingerprints for all user tables for a specific node';
+COMMENT ON FUNCTION steep_repl.capture_all_fingerprints(TEXT) IS 'Capture fingerprints for all eligible user tables for a specific node';
 
 -- Compare fingerprints with a peer node via dblink
 -- Returns a table of comparison results
@@ -160,7 +159,9 @@ BEGIN
     FROM steep_repl.schema_fingerprints l
     FULL OUTER JOIN _remote_fps r
         ON l.table_schema = r.table_schema AND l.table_name = r.table_name
-    WHERE l.node_id = p_local_node OR l.node_id IS NULL
+    WHERE (l.node_id = p_local_node OR l.node_id IS NULL)
+      AND (steep_repl.included_schemas() IS NULL
+           OR COALESCE(l.table_schema, r.table_schema) = ANY(steep_repl.included_schemas()))
     ORDER BY table_schema, table_name;
 
 END;
@@ -270,9 +271,72 @@ END;
 $function$ LANGUAGE plpgsql;
 
 COMMENT ON FUNCTION steep_repl.get_column_diff(TEXT, TEXT, TEXT) IS 'Get detailed column differences between local and remote table';
+
+-- Compare stored fingerprints across every pair of healthy nodes
+-- Uses fingerprints already in schema_fingerprints (captured locally or synced
+-- from peers); nodes with no stored fingerprints are left out.
+-- Only drift is returned unless p_include_matches is set.
+CREATE FUNCTION steep_repl.cluster_drift(p_include_matches BOOLEAN DEFAULT false)
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT,
+    node_a TEXT,
+    node_b TEXT,
+    status TEXT  -- MATCH, MISMATCH, A_ONLY, B_ONLY
+) AS $$
+    WITH cluster_nodes AS (
+        SELECT n.node_id
+        FROM steep_repl.nodes n
+        WHERE n.status = 'healthy'
+          AND EXISTS (SELECT 1 FROM steep_repl.schema_fingerprints f WHERE f.node_id = n.node_id)
+    ),
+    pairs AS (
+        SELECT a.node_id AS node_a, b.node_id AS node_b
+        FROM cluster_nodes a
+        JOIN cluster_nodes b ON a.node_id < b.node_id
+    ),
+    fps AS (
+        SELECT f.node_id, f.table_schema, f.table_name, f.fingerprint
+        FROM steep_repl.schema_fingerprints f
+        WHERE steep_repl.included_schemas() IS NULL
+           OR f.table_schema = ANY(steep_repl.included_schemas())
+    ),
+    cluster_tables AS (
+        SELECT DISTINCT fps.table_schema, fps.table_name
+        FROM fps
+        JOIN cluster_nodes c ON c.node_id = fps.node_id
+    ),
+    compared AS (
+        SELECT
+            t.table_schema,
+            t.table_name,
+            p.node_a,
+            p.node_b,
+            CASE
+                WHEN fa.fingerprint IS NULL AND fb.fingerprint IS NULL THEN NULL
+                WHEN fb.fingerprint IS NULL THEN 'A_ONLY'
+                WHEN fa.fingerprint IS NULL THEN 'B_ONLY'
+                WHEN fa.fingerprint = fb.fingerprint THEN 'MATCH'
+                ELSE 'MISMATCH'
+            END AS status
+        FROM cluster_tables t
+        CROSS JOIN pairs p
+        LEFT JOIN fps fa ON fa.node_id = p.node_a
+            AND fa.table_schema = t.table_schema AND fa.table_name = t.table_name
+        LEFT JOIN fps fb ON fb.node_id = p.node_b
+            AND fb.table_schema = t.table_schema AND fb.table_name = t.table_name
+    )
+    SELECT c.table_schema, c.table_name, c.node_a, c.node_b, c.status
+    FROM compared c
+    WHERE c.status IS NOT NULL
+      AND (p_include_matches OR c.status <> 'MATCH')
+    ORDER BY 1, 2, 3, 4;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.cluster_drift(BOOLEAN) IS 'Pairwise schema fingerprint comparison across all healthy nodes, using stored fingerprints';
 "#,
     name = "create_fingerprint_functions",
-    requires = ["create_schema_fingerprints_table"],
+    requires = ["create_schema_fingerprints_table", "create_table_functions", "create_nodes_table"],
 );
 
 #[cfg(any(test, feature = "pg_test"))]
@@ -491,4 +555,44 @@ mod tests {
         // Cleanup
         Spi::run("DROP TABLE IF EXISTS public.test_changes").expect("cleanup test table");
     }
+
+    #[pg_test]
+    fn test_cluster_drift_flags_divergent_node() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('drift-a', 'A', 'host-a', 5432, 50, 'healthy'),
+                    ('drift-b', 'B', 'host-b', 5432, 50, 'healthy'),
+                    ('drift-c', 'C', 'host-c', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "INSERT INTO steep_repl.schema_fingerprints (node_id, table_schema, table_name, fingerprint, column_count)
+             VALUES ('drift-a', 'drift', 'orders', 'fp1', 3),
+                    ('drift-b', 'drift', 'orders', 'fp1', 3),
+                    ('drift-c', 'drift', 'orders', 'fp2', 4),
+                    ('drift-a', 'drift', 'users', 'fp3', 2),
+                    ('drift-b', 'drift', 'users', 'fp3', 2),
+                    ('drift-c', 'drift', 'users', 'fp3', 2)"
+        ).expect("fingerprint insert should succeed");
+
+        let drift = Spi::get_one::<String>(
+            "SELECT string_agg(table_name || ':' || node_a || '/' || node_b || '=' || status, ',')
+             FROM steep_repl.cluster_drift() WHERE table_schema = 'drift'"
+        );
+        assert_eq!(
+            drift,
+            Ok(Some("orders:drift-a/drift-c=MISMATCH,orders:drift-b/drift-c=MISMATCH".to_string())),
+            "only pairs involving the divergent node should be flagged"
+        );
+
+        let all_pairs = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.cluster_drift(true) WHERE table_schema = 'drift'"
+        );
+        assert_eq!(all_pairs, Ok(Some(6)), "two tables across three pairs");
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.schema_fingerprints WHERE node_id LIKE 'drift-%'")
+            .expect("cleanup fingerprints should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'drift-%'")
+            .expect("cleanup nodes should succeed");
+    }
 }
@@ -0,0 +1,116 @@
+//! Configuration parameters (GUCs) for steep_repl extension.
+//!
+//! This module defines the steep_repl.* settings and registers them
+//! with PostgreSQL at extension load time.
+
+use pgrx::guc::{GucContext, GucFlags, GucRegistry, GucSetting};
+use pgrx::prelude::*;
+use std::ffi::CString;
+
+/// steep_repl.node_id: identity of this server in steep_repl.nodes.
+pub static NODE_ID: GucSetting<Option<CString>> = GucSetting::<Option<CString>>::new(None);
+
+/// steep_repl.included_schemas: comma-separated schemas eligible for snapshots and merges.
+pub static INCLUDED_SCHEMAS: GucSetting<Option<CString>> = GucSetting::<Option<CString>>::new(None);
+
+/// steep_repl.modified_columns: candidate last-modified column names, in priority order.
+pub static MODIFIED_COLUMNS: GucSetting<Option<CString>> =
+    GucSetting::<Option<CString>>::new(Some(c"updated_at,modified_at,last_modified,timestamp"));
+
+/// Register all steep_repl GUCs. Called from _PG_init.
+pub fn init() {
+    GucRegistry::define_string_guc(
+        c"steep_repl.node_id",
+        c"Node ID of the local server.",
+        c"Identity of this server in steep_repl.nodes, used as the source node for local operations.",
+        &NODE_ID,
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
+    GucRegistry::define_string_guc(
+        c"steep_repl.included_schemas",
+        c"Schemas eligible for snapshots and merges.",
+        c"Comma-separated schema list. Empty means all non-system schemas except steep_repl.",
+        &INCLUDED_SCHEMAS,
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
+    GucRegistry::define_string_guc(
+        c"steep_repl.modified_columns",
+        c"Candidate last-modified columns for last-modified merges.",
+        c"Comma-separated column names probed in order when a merge does not specify modified_column.",
+        &MODIFIED_COLUMNS,
+        GucContext::Userset,
+        GucFlags::default(),
+    );
+}
+
+extension_sql!(
+    r#"
+-- Effective value of every steep_repl.* setting, for a one-stop config dump
+CREATE FUNCTION steep_repl.config()
+RETURNS TABLE (
+    name TEXT,
+    value TEXT,
+    "default" TEXT,
+    source TEXT
+) AS $$
+    SELECT s.name, s.setting, s.boot_val, s.source
+    FROM pg_settings s
+    WHERE s.name LIKE 'steep\_repl.%'
+    ORDER BY s.name;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.config IS
+    'Effective steep_repl.* settings with their defaults and where each value came from';
+"#,
+    name = "create_config_function",
+    requires = ["create_schema"],
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn test_config_lists_known_gucs() {
+        for guc in [
+            "steep_repl.node_id",
+            "steep_repl.included_schemas",
+            "steep_repl.modified_columns",
+        ] {
+            let found = Spi::get_one::<bool>(&format!(
+                "SELECT EXISTS(SELECT 1 FROM steep_repl.config() WHERE name = '{guc}')"
+            ));
+            assert_eq!(found, Ok(Some(true)), "{guc} should be listed");
+        }
+    }
+
+    #[pg_test]
+    fn test_config_reports_current_value() {
+        Spi::run("SET steep_repl.modified_columns = 'changed_at'").expect("set GUC");
+
+        let value = Spi::get_one::<String>(
+            "SELECT value FROM steep_repl.config() WHERE name = 'steep_repl.modified_columns'",
+        );
+        assert_eq!(value, Ok(Some("changed_at".to_string())), "config() should show the session override");
+
+        let source = Spi::get_one::<String>(
+            "SELECT source FROM steep_repl.config() WHERE name = 'steep_repl.modified_columns'",
+        );
+        assert_eq!(source, Ok(Some("session".to_string())));
+
+        let default = Spi::get_one::<String>(
+            "SELECT \"default\" FROM steep_repl.config() WHERE name = 'steep_repl.modified_columns'",
+        );
+        assert_eq!(
+            default,
+            Ok(Some("updated_at,modified_at,last_modified,timestamp".to_string())),
+        );
+
+        Spi::run("RESET steep_repl.modified_columns").expect("reset GUC");
+    }
+}
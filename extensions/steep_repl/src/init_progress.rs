@@ -1,7 +1,8 @@
 //! Init progress table for steep_repl extension.
 //!
 //! This module creates the init_progress table for real-time
-//! initialization progress tracking with throughput metrics.
+//! initialization progress tracking with throughput metrics, and a
+//! stall check for initializations whose progress stopped advancing.
 
 use pgrx::prelude::*;
 
@@ -50,6 +51,28 @@ COMMENT ON COLUMN steep_repl.init_progress.throughput_rows_sec IS 'Current throu
 COMMENT ON COLUMN steep_repl.init_progress.eta_seconds IS 'Estimated seconds remaining';
 COMMENT ON COLUMN steep_repl.init_progress.parallel_workers IS 'Active parallel workers';
 COMMENT ON COLUMN steep_repl.init_progress.error_message IS 'Last error if any';
+
+-- Report in-flight initializations whose progress has not advanced recently
+-- Every progress write bumps updated_at, so a running phase with an old
+-- updated_at indicates a silent hang rather than a crash.
+CREATE FUNCTION steep_repl.stalled_jobs(p_idle_secs INTEGER DEFAULT 120)
+RETURNS TABLE (
+    node_id TEXT,
+    phase TEXT,
+    overall_percent REAL,
+    current_table TEXT,
+    last_progress_at TIMESTAMPTZ,
+    idle_seconds INTEGER
+) AS $$
+    SELECT p.node_id, p.phase, p.overall_percent, p.current_table, p.updated_at,
+           extract(epoch FROM now() - p.updated_at)::INTEGER
+    FROM steep_repl.init_progress p
+    WHERE p.phase IN ('preparing', 'copying', 'catching_up')
+      AND p.updated_at < now() - make_interval(secs => p_idle_secs)
+    ORDER BY p.updated_at;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.stalled_jobs(INTEGER) IS 'In-progress initializations with no progress update within the idle window';
 "#,
     name = "create_init_progress_table",
     requires = ["create_nodes_table"],
@@ -123,4 +146,39 @@ mod tests {
         );
         assert_eq!(result, Ok(Some(true)), "percent check constraint should exist");
     }
+
+    #[pg_test]
+    fn test_stalled_jobs_reports_stale_progress() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('stall-a', 'A', 'host-a', 5432, 50, 'healthy'),
+                    ('stall-b', 'B', 'host-b', 5432, 50, 'healthy'),
+                    ('stall-c', 'C', 'host-c', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "INSERT INTO steep_repl.init_progress (node_id, phase, overall_percent, updated_at)
+             VALUES ('stall-a', 'copying', 40, now() - interval '10 minutes'),
+                    ('stall-b', 'copying', 60, now()),
+                    ('stall-c', 'failed', 10, now() - interval '10 minutes')"
+        ).expect("progress insert should succeed");
+
+        let stalled = Spi::get_one::<String>(
+            "SELECT string_agg(node_id || ':' || (idle_seconds >= 600), ',')
+             FROM steep_repl.stalled_jobs() WHERE node_id LIKE 'stall-%'"
+        );
+        assert_eq!(
+            stalled,
+            Ok(Some("stall-a:true".to_string())),
+            "only the running job with stale progress should be reported"
+        );
+
+        let wide_window = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.stalled_jobs(3600) WHERE node_id LIKE 'stall-%'"
+        );
+        assert_eq!(wide_window, Ok(Some(0)), "a longer idle window should not flag it");
+
+        // Cleanup (cascade will delete progress)
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'stall-%'")
+            .expect("cleanup should succeed");
+    }
 }
@@ -22,16 +22,25 @@ use pgrx::prelude::*;
 
 mod schema;
 mod nodes;
+mod node_functions;
 mod coordinator_state;
 mod audit_log;
 mod init_progress;
 mod schema_fingerprints;
 mod init_slots;
 mod snapshots;
+mod snapshot_functions;
+mod table_functions;
 mod fingerprint_functions;
 mod merge;
 mod merge_audit_log;
+mod schema_repair;
+mod schema_ddl;
 mod utils;
+mod guc;
+
+#[cfg(any(test, feature = "pg_test"))]
+mod test_support;
 
 // Re-export utility functions for SQL access
 pub use utils::{steep_repl_version, steep_repl_min_pg_version};
@@ -41,7 +50,7 @@ pub use utils::{steep_repl_version, steep_repl_min_pg_version};
 // =============================================================================
 
 /// Check that we're running on PostgreSQL 18 or later.
-/// This is enforced at extension load time.
+/// This is enforced at extension load time, before registering GUCs.
 #[pg_guard]
 pub extern "C-unwind" fn _PG_init() {
     // PostgreSQL version is checked at compile time via pgrx features.
@@ -53,6 +62,8 @@ pub extern "C-unwind" fn _PG_init() {
             version
         );
     }
+
+    guc::init();
 }
 
 // =============================================================================
@@ -3,7 +3,10 @@
 //! This module provides SQL functions for:
 //! - row_hash: Fast row hashing for comparison (T067a)
 //! - compare_tables: Hash-based table comparison via postgres_fdw (T067b)
+//! - merge_preview: Per-table overlap counts against a peer connection string
 //! - quiesce_writes: Block writes during merge operations (T067d)
+//! - resolve_conflict: Conflict strategy resolver, with preview_resolution
+//! - resolve_table_conflict: Resolver with last-modified column auto-detection
 
 use pgrx::prelude::*;
 
@@ -232,6 +235,222 @@ $function$ LANGUAGE sql;
 COMMENT ON FUNCTION steep_repl.compare_table_summary(TEXT, TEXT, TEXT, TEXT, TEXT, TEXT[]) IS
     'Get overlap analysis summary for table comparison. Returns counts of matches, conflicts, local_only, remote_only.';
 
+-- Pre-merge risk assessment against a peer connection string
+-- Per table, only PK + 8-byte hash pairs cross the wire and only counts come
+-- back, so this is much cheaper than a row-by-row dry run. Tables may be
+-- schema-qualified; unqualified names default to public. The primary key is
+-- taken from the local table. The peer does not need steep_repl installed.
+CREATE FUNCTION steep_repl.merge_preview(p_peer_connstr TEXT, p_tables TEXT[])
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT,
+    local_only BIGINT,
+    remote_only BIGINT,
+    matches BIGINT,
+    conflicts BIGINT
+) AS $function$
+DECLARE
+    v_table TEXT;
+    v_ident TEXT[];
+    v_pk_json TEXT;
+BEGIN
+    CREATE EXTENSION IF NOT EXISTS dblink;
+
+    FOREACH v_table IN ARRAY p_tables LOOP
+        v_ident := parse_ident(v_table);
+        IF array_length(v_ident, 1) = 1 THEN
+            v_ident := ARRAY['public'] || v_ident;
+        END IF;
+        table_schema := v_ident[1];
+        table_name := v_ident[2];
+
+        SELECT string_agg(format('%L, t.%I', a.attname, a.attname), ', ' ORDER BY k.ord)
+        INTO v_pk_json
+        FROM pg_index i
+        CROSS JOIN LATERAL unnest(i.indkey) WITH ORDINALITY AS k(attnum, ord)
+        JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum
+        WHERE i.indrelid = format('%I.%I', table_schema, table_name)::regclass
+          AND i.indisprimary;
+
+        IF v_pk_json IS NULL THEN
+            RAISE EXCEPTION 'Table %.% has no primary key', table_schema, table_name;
+        END IF;
+
+        EXECUTE format($q$
+            WITH local_hashes AS (
+                SELECT jsonb_build_object(%1$s) AS pk_json, steep_repl.row_hash(t.*) AS row_hash
+                FROM %2$I.%3$I t
+            ),
+            remote_hashes AS (
+                SELECT * FROM dblink(%4$L, %5$L) AS r(pk_json JSONB, row_hash BIGINT)
+            )
+            SELECT
+                count(*) FILTER (WHERE r.pk_json IS NULL),
+                count(*) FILTER (WHERE l.pk_json IS NULL),
+                count(*) FILTER (WHERE l.row_hash = r.row_hash),
+                count(*) FILTER (WHERE l.row_hash <> r.row_hash)
+            FROM local_hashes l
+            FULL OUTER JOIN remote_hashes r ON l.pk_json = r.pk_json
+        $q$,
+            v_pk_json, table_schema, table_name,
+            p_peer_connstr,
+            format('SELECT jsonb_build_object(%s), hashtextextended(t::text, 0) FROM %I.%I t',
+                   v_pk_json, table_schema, table_name)
+        )
+        INTO local_only, remote_only, matches, conflicts;
+
+        RETURN NEXT;
+    END LOOP;
+END;
+$function$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.merge_preview(TEXT, TEXT[]) IS
+    'Per-table counts of local_only, remote_only, matching, and conflicting rows against a peer, without a full diff.';
+
+-- Compatibility series of a steep_repl version: the major version, or
+-- major.minor while the major version is 0 (pre-1.0 minors may break)
+CREATE FUNCTION steep_repl.version_series(p_version TEXT)
+RETURNS TEXT AS $function$
+    SELECT CASE split_part(p_version, '.', 1)
+        WHEN '0' THEN '0.' || split_part(p_version, '.', 2)
+        ELSE split_part(p_version, '.', 1)
+    END;
+$function$ LANGUAGE sql IMMUTABLE STRICT;
+
+COMMENT ON FUNCTION steep_repl.version_series(TEXT) IS
+    'Compatibility series of a version: major, or major.minor for 0.x releases.';
+
+-- Go/no-go validation before a merge against a peer connection string
+-- Bundles the peer reachability, extension version, and per-table existence,
+-- column, and primary key checks into one call. Tables may be
+-- schema-qualified; unqualified names default to public. The final row is
+-- the overall verdict.
+CREATE FUNCTION steep_repl.validate_merge(p_peer_connstr TEXT, p_tables TEXT[])
+RETURNS TABLE (
+    check_name TEXT,
+    ok BOOLEAN,
+    detail TEXT
+) AS $function$
+DECLARE
+    v_table TEXT;
+    v_ident TEXT[];
+    v_qualified TEXT;
+    v_signature_sql TEXT;
+    v_local_version TEXT := steep_repl_version();
+    v_peer_version TEXT;
+    v_local_exists BOOLEAN;
+    v_local_columns TEXT;
+    v_local_pk TEXT;
+    v_peer_exists BOOLEAN;
+    v_peer_columns TEXT;
+    v_peer_pk TEXT;
+    v_valid BOOLEAN := true;
+BEGIN
+    CREATE EXTENSION IF NOT EXISTS dblink;
+
+    check_name := 'peer_reachable';
+    BEGIN
+        SELECT r.v INTO v_peer_version
+        FROM dblink(p_peer_connstr,
+                    'SELECT (SELECT extversion FROM pg_extension WHERE extname = ''steep_repl'')')
+             AS r(v TEXT);
+        ok := true;
+        detail := 'connected to peer';
+    EXCEPTION WHEN OTHERS THEN
+        ok := false;
+        detail := SQLERRM;
+    END;
+    RETURN NEXT;
+
+    IF NOT ok THEN
+        check_name := 'overall';
+        detail := 'merge blocked';
+        RETURN NEXT;
+        RETURN;
+    END IF;
+
+    check_name := 'extension_version';
+    ok := v_peer_version IS NOT NULL
+          AND steep_repl.version_series(v_peer_version) = steep_repl.version_series(v_local_version);
+    detail := CASE
+        WHEN v_peer_version IS NULL THEN 'steep_repl is not installed on the peer'
+        ELSE format('local %s, peer %s', v_local_version, v_peer_version)
+    END;
+    v_valid := v_valid AND ok;
+    RETURN NEXT;
+
+    FOREACH v_table IN ARRAY COALESCE(p_tables, '{}') LOOP
+        v_ident := parse_ident(v_table);
+        IF array_length(v_ident, 1) = 1 THEN
+            v_ident := ARRAY['public'] || v_ident;
+        END IF;
+        v_qualified := format('%I.%I', v_ident[1], v_ident[2]);
+
+        -- The same catalog query runs on both sides: existence, ordered
+        -- column signature, and primary key column list
+        v_signature_sql := format($q$
+            SELECT c.oid IS NOT NULL,
+                   (SELECT string_agg(a.attname || ' ' || format_type(a.atttypid, a.atttypmod), ', ' ORDER BY a.attnum)
+                    FROM pg_attribute a
+                    WHERE a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped),
+                   (SELECT string_agg(a.attname::text, ', ' ORDER BY k.ord)
+                    FROM pg_index i
+                    CROSS JOIN LATERAL unnest(i.indkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum
+                    WHERE i.indrelid = c.oid AND i.indisprimary)
+            FROM (SELECT to_regclass(%L)::oid AS oid) c
+        $q$, v_qualified);
+
+        EXECUTE v_signature_sql INTO v_local_exists, v_local_columns, v_local_pk;
+
+        SELECT r.table_exists, r.table_columns, r.table_pk
+        INTO v_peer_exists, v_peer_columns, v_peer_pk
+        FROM dblink(p_peer_connstr, v_signature_sql)
+             AS r(table_exists BOOLEAN, table_columns TEXT, table_pk TEXT);
+
+        check_name := 'table_exists';
+        ok := v_local_exists AND v_peer_exists;
+        detail := v_qualified || CASE
+            WHEN ok THEN ' exists on both nodes'
+            WHEN v_local_exists THEN ' is missing on the peer'
+            WHEN v_peer_exists THEN ' is missing locally'
+            ELSE ' is missing on both nodes'
+        END;
+        v_valid := v_valid AND ok;
+        RETURN NEXT;
+
+        CONTINUE WHEN NOT ok;
+
+        check_name := 'columns';
+        ok := v_local_columns IS NOT DISTINCT FROM v_peer_columns;
+        detail := CASE
+            WHEN ok THEN v_qualified || ' columns match'
+            ELSE format('%s columns differ: local (%s), peer (%s)', v_qualified, v_local_columns, v_peer_columns)
+        END;
+        v_valid := v_valid AND ok;
+        RETURN NEXT;
+
+        check_name := 'primary_key';
+        ok := v_local_pk IS NOT NULL AND v_local_pk IS NOT DISTINCT FROM v_peer_pk;
+        detail := CASE
+            WHEN v_local_pk IS NULL THEN v_qualified || ' has no primary key'
+            WHEN ok THEN format('%s primary key (%s)', v_qualified, v_local_pk)
+            ELSE format('%s primary key differs: local (%s), peer (%s)', v_qualified, v_local_pk, COALESCE(v_peer_pk, 'none'))
+        END;
+        v_valid := v_valid AND ok;
+        RETURN NEXT;
+    END LOOP;
+
+    check_name := 'overall';
+    ok := v_valid;
+    detail := CASE WHEN v_valid THEN 'ready to merge' ELSE 'merge blocked' END;
+    RETURN NEXT;
+END;
+$function$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.validate_merge(TEXT, TEXT[]) IS
+    'Go/no-go checks (peer reachable, extension version, tables, columns, primary keys) before merging with a peer.';
+
 -- =============================================================================
 -- T067d: Quiesce Writes Function
 -- =============================================================================
@@ -310,6 +529,174 @@ $function$ LANGUAGE plpgsql;
 
 COMMENT ON FUNCTION steep_repl.release_quiesce(TEXT, TEXT) IS
     'Release quiesce lock on a table after merge completion.';
+
+-- =============================================================================
+-- Conflict Resolution
+-- =============================================================================
+-- Mirrors the daemon's merge strategies (prefer-node-a, prefer-node-b,
+-- last-modified, manual). Decisions are kept_a, kept_b, or skipped.
+
+-- Candidate last-modified column names from steep_repl.modified_columns
+-- Falls back to the daemon's defaults when the setting is unavailable
+CREATE FUNCTION steep_repl.modified_column_candidates()
+RETURNS TEXT[] AS $function$
+    SELECT COALESCE(array(
+        SELECT btrim(c)
+        FROM unnest(string_to_array(
+            COALESCE(current_setting('steep_repl.modified_columns', true),
+                     'updated_at,modified_at,last_modified,timestamp'),
+            ',')) c
+        WHERE btrim(c) <> ''
+    ), '{}');
+$function$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.modified_column_candidates() IS
+    'Candidate last-modified column names, in priority order, from steep_repl.modified_columns.';
+
+-- Detect the last-modified column of a table
+-- Returns the first candidate column that exists with a date/time type, or NULL
+CREATE FUNCTION steep_repl.detect_modified_column(p_schema TEXT, p_table TEXT)
+RETURNS TEXT AS $function$
+    SELECT c.candidate
+    FROM unnest(steep_repl.modified_column_candidates()) WITH ORDINALITY AS c(candidate, priority)
+    JOIN pg_attribute a ON a.attname = c.candidate
+    WHERE a.attrelid = to_regclass(format('%I.%I', p_schema, p_table))
+      AND a.attnum > 0
+      AND NOT a.attisdropped
+      AND a.atttypid IN ('timestamptz'::regtype, 'timestamp'::regtype, 'date'::regtype)
+    ORDER BY c.priority
+    LIMIT 1;
+$function$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.detect_modified_column(TEXT, TEXT) IS
+    'Detect the last-modified timestamp column of a table from steep_repl.modified_columns candidates.';
+
+-- Last-modified timestamp of a row version
+-- Uses p_column when given, otherwise the first parseable column among
+-- modified_column_candidates(). NULL if none parse.
+CREATE FUNCTION steep_repl.row_modified_at(p_row JSONB, p_column TEXT DEFAULT NULL)
+RETURNS TIMESTAMPTZ AS $function$
+DECLARE
+    v_col TEXT;
+BEGIN
+    FOREACH v_col IN ARRAY CASE
+        WHEN p_column IS NOT NULL THEN ARRAY[p_column]
+        ELSE steep_repl.modified_column_candidates()
+    END
+    LOOP
+        IF p_row ? v_col AND jsonb_typeof(p_row->v_col) = 'string' THEN
+            BEGIN
+                RETURN (p_row->>v_col)::TIMESTAMPTZ;
+            EXCEPTION WHEN others THEN
+                -- Not a timestamp; try the next candidate
+                NULL;
+            END;
+        END IF;
+    END LOOP;
+
+    RETURN NULL;
+END;
+$function$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.row_modified_at(JSONB, TEXT) IS
+    'Extract the last-modified timestamp from a row version, or NULL if none is present.';
+
+-- Resolve a conflict between two row versions using a merge strategy
+CREATE FUNCTION steep_repl.resolve_conflict(
+    p_strategy TEXT,
+    p_a JSONB,
+    p_b JSONB,
+    p_modified_column TEXT DEFAULT NULL
+)
+RETURNS TEXT AS $function$
+DECLARE
+    v_a_time TIMESTAMPTZ;
+    v_b_time TIMESTAMPTZ;
+BEGIN
+    CASE p_strategy
+        WHEN 'prefer-node-a' THEN
+            RETURN 'kept_a';
+        WHEN 'prefer-node-b' THEN
+            RETURN 'kept_b';
+        WHEN 'manual' THEN
+            RETURN 'skipped';
+        WHEN 'last-modified' THEN
+            v_a_time := steep_repl.row_modified_at(p_a, p_modified_column);
+            v_b_time := steep_repl.row_modified_at(p_b, p_modified_column);
+
+            -- Newer side wins; a side with a timestamp beats one without;
+            -- ties and missing timestamps fall back to A
+            IF v_b_time IS NOT NULL AND (v_a_time IS NULL OR v_b_time > v_a_time) THEN
+                RETURN 'kept_b';
+            END IF;
+            RETURN 'kept_a';
+        ELSE
+            RAISE EXCEPTION 'unknown strategy: %', p_strategy
+                USING HINT = 'Valid strategies are prefer-node-a, prefer-node-b, last-modified, manual';
+    END CASE;
+END;
+$function$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.resolve_conflict(TEXT, JSONB, JSONB, TEXT) IS
+    'Resolve a conflict between two row versions. Returns kept_a, kept_b, or skipped.';
+
+-- Resolve a conflict for a specific table, reporting how it was decided
+-- For last-modified without p_modified_column, the table's timestamp column
+-- is auto-detected; tables without one fall back to prefer-node-a (local).
+CREATE FUNCTION steep_repl.resolve_table_conflict(
+    p_schema TEXT,
+    p_table TEXT,
+    p_strategy TEXT,
+    p_a JSONB,
+    p_b JSONB,
+    p_modified_column TEXT DEFAULT NULL
+)
+RETURNS TABLE (
+    resolution TEXT,
+    resolved_by TEXT
+) AS $function$
+DECLARE
+    v_column TEXT;
+BEGIN
+    IF p_strategy = 'last-modified' THEN
+        v_column := COALESCE(p_modified_column, steep_repl.detect_modified_column(p_schema, p_table));
+
+        IF v_column IS NULL THEN
+            resolution := 'kept_a';
+            resolved_by := 'strategy:last-modified:fallback:prefer-node-a';
+            RETURN NEXT;
+            RETURN;
+        END IF;
+    END IF;
+
+    resolution := steep_repl.resolve_conflict(p_strategy, p_a, p_b, v_column);
+    resolved_by := 'strategy:' || p_strategy;
+    RETURN NEXT;
+END;
+$function$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.resolve_table_conflict(TEXT, TEXT, TEXT, JSONB, JSONB, TEXT) IS
+    'Resolve a conflict for a table, auto-detecting the last-modified column. Returns the decision and resolved_by.';
+
+-- Preview how a strategy would resolve two row versions, without queuing a merge
+CREATE FUNCTION steep_repl.preview_resolution(
+    p_strategy TEXT,
+    p_a JSONB,
+    p_b JSONB,
+    p_modified_column TEXT DEFAULT NULL
+)
+RETURNS TEXT AS $function$
+BEGIN
+    IF p_a IS NULL OR p_b IS NULL THEN
+        RAISE EXCEPTION 'preview_resolution requires both row versions';
+    END IF;
+
+    RETURN steep_repl.resolve_conflict(p_strategy, p_a, p_b, p_modified_column);
+END;
+$function$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.preview_resolution(TEXT, JSONB, JSONB, TEXT) IS
+    'Preview the decision a merge strategy would make for two row versions (kept_a, kept_b, or skipped).';
 "#,
     name = "create_merge_functions",
     requires = ["create_schema"],
@@ -413,6 +800,114 @@ mod tests {
         assert_eq!(result, Ok(Some(true)), "compare_table_summary function should exist");
     }
 
+    #[pg_test]
+    fn test_merge_preview_counts_divergence() {
+        // The peer is a separate database reached over a loopback connection,
+        // so its rows are committed independently of this test's transaction
+        Spi::run("CREATE EXTENSION IF NOT EXISTS dblink").expect("dblink");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=postgres',
+                                'CREATE DATABASE steep_preview_peer')"
+        ).expect("create peer database");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=steep_preview_peer',
+                'CREATE TABLE public.preview_items (id INT PRIMARY KEY, v TEXT);
+                 INSERT INTO public.preview_items VALUES (1, ''a''), (2, ''b''), (3, ''changed''), (5, ''remote'')')"
+        ).expect("seed peer table");
+
+        Spi::run("CREATE TABLE public.preview_items (id INT PRIMARY KEY, v TEXT)").expect("create local table");
+        Spi::run("INSERT INTO public.preview_items VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'local')")
+            .expect("seed local table");
+
+        let counts = Spi::get_one::<String>(
+            "SELECT table_schema || '.' || table_name || ':' || local_only || '/' || remote_only || '/' || matches || '/' || conflicts
+             FROM steep_repl.merge_preview(
+                 'host=localhost port=' || current_setting('port') || ' dbname=steep_preview_peer',
+                 ARRAY['preview_items'])"
+        );
+        assert_eq!(counts, Ok(Some("public.preview_items:1/1/2/1".to_string())));
+
+        // Cleanup
+        Spi::run("DROP TABLE public.preview_items").expect("drop local table");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=postgres',
+                                'DROP DATABASE steep_preview_peer')"
+        ).expect("drop peer database");
+    }
+
+    #[pg_test]
+    fn test_validate_merge_ready_and_missing_table() {
+        // The peer is a separate database with steep_repl installed, reached
+        // over a loopback connection
+        Spi::run("CREATE EXTENSION IF NOT EXISTS dblink").expect("dblink");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=postgres',
+                                'CREATE DATABASE steep_validate_peer')"
+        ).expect("create peer database");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=steep_validate_peer',
+                'CREATE EXTENSION steep_repl CASCADE;
+                 CREATE TABLE public.validate_items (id INT PRIMARY KEY, v TEXT)')"
+        ).expect("seed peer");
+
+        Spi::run("CREATE TABLE public.validate_items (id INT PRIMARY KEY, v TEXT)").expect("create local table");
+        Spi::run("CREATE TABLE public.validate_local_only (id INT PRIMARY KEY)").expect("create local-only table");
+
+        let ready = Spi::get_one::<String>(
+            "SELECT string_agg(check_name || '=' || ok, ',' ORDER BY ord)
+             FROM steep_repl.validate_merge(
+                 'host=localhost port=' || current_setting('port') || ' dbname=steep_validate_peer',
+                 ARRAY['validate_items']) WITH ORDINALITY AS v(check_name, ok, detail, ord)"
+        );
+        assert_eq!(
+            ready,
+            Ok(Some(
+                "peer_reachable=true,extension_version=true,table_exists=true,columns=true,primary_key=true,overall=true"
+                    .to_string()
+            ))
+        );
+
+        let missing = Spi::get_one::<String>(
+            "SELECT detail
+             FROM steep_repl.validate_merge(
+                 'host=localhost port=' || current_setting('port') || ' dbname=steep_validate_peer',
+                 ARRAY['validate_items', 'public.validate_local_only'])
+             WHERE check_name = 'table_exists' AND NOT ok"
+        );
+        assert_eq!(missing, Ok(Some("public.validate_local_only is missing on the peer".to_string())));
+
+        let overall = Spi::get_one::<bool>(
+            "SELECT ok
+             FROM steep_repl.validate_merge(
+                 'host=localhost port=' || current_setting('port') || ' dbname=steep_validate_peer',
+                 ARRAY['validate_items', 'validate_local_only'])
+             WHERE check_name = 'overall'"
+        );
+        assert_eq!(overall, Ok(Some(false)), "a missing table should block the merge");
+
+        // Cleanup
+        Spi::run("DROP TABLE public.validate_items, public.validate_local_only").expect("drop local tables");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=postgres',
+                                'DROP DATABASE steep_validate_peer')"
+        ).expect("drop peer database");
+    }
+
+    #[pg_test]
+    fn test_version_series_pre_1_0_uses_minor() {
+        let result = Spi::get_one::<String>(
+            "SELECT string_agg(steep_repl.version_series(v), ',' ORDER BY n)
+             FROM unnest(ARRAY['0.1.0', '0.1.7', '0.2.0', '1.4.2', '2.0.0']) WITH ORDINALITY AS t(v, n)"
+        );
+        assert_eq!(result, Ok(Some("0.1,0.1,0.2,1,2".to_string())));
+
+        // 0.1 and 0.2 are incompatible, so validate_merge rejects the pair
+        let compatible = Spi::get_one::<bool>(
+            "SELECT steep_repl.version_series('0.1.0') = steep_repl.version_series('0.2.0')"
+        );
+        assert_eq!(compatible, Ok(Some(false)), "0.1 vs 0.2 should not be compatible");
+    }
+
     #[pg_test]
     fn test_quiesce_writes_function_exists() {
         let result = Spi::get_one::<bool>(
@@ -492,4 +987,103 @@ mod tests {
         );
         assert_eq!(result, Ok(Some(true)), "overlap_summary type should exist");
     }
+
+    #[pg_test]
+    fn test_preview_resolution_strategies() {
+        let older = r#"'{"id": 1, "name": "old", "updated_at": "2024-01-01T00:00:00Z"}'"#;
+        let newer = r#"'{"id": 1, "name": "new", "updated_at": "2024-06-01T00:00:00Z"}'"#;
+        let untimed = r#"'{"id": 1, "name": "none"}'"#;
+
+        let cases = vec![
+            ("prefer-node-a", older, newer, "kept_a"),
+            ("prefer-node-b", older, newer, "kept_b"),
+            ("manual", older, newer, "skipped"),
+            ("last-modified", older, newer, "kept_b"),
+            ("last-modified", newer, older, "kept_a"),
+            ("last-modified", untimed, older, "kept_b"),
+            ("last-modified", older, untimed, "kept_a"),
+            ("last-modified", untimed, untimed, "kept_a"),
+        ];
+
+        for (strategy, a, b, expected) in cases {
+            let result = Spi::get_one::<String>(&format!(
+                "SELECT steep_repl.preview_resolution('{}', {}, {})",
+                strategy, a, b
+            ));
+            assert_eq!(result, Ok(Some(expected.to_string())), "strategy {} with a={} b={}", strategy, a, b);
+        }
+    }
+
+    #[pg_test]
+    fn test_preview_resolution_explicit_modified_column() {
+        // synced_at says A is newer even though updated_at says B is
+        let result = Spi::get_one::<String>(
+            r#"SELECT steep_repl.preview_resolution(
+                'last-modified',
+                '{"updated_at": "2024-01-01T00:00:00Z", "synced_at": "2024-09-01T00:00:00Z"}',
+                '{"updated_at": "2024-06-01T00:00:00Z", "synced_at": "2024-02-01T00:00:00Z"}',
+                'synced_at'
+            )"#
+        );
+        assert_eq!(result, Ok(Some("kept_a".to_string())));
+    }
+
+    #[pg_test(error = "unknown strategy: newest-wins")]
+    fn test_preview_resolution_unknown_strategy() {
+        let _ = Spi::get_one::<String>(
+            "SELECT steep_repl.preview_resolution('newest-wins', '{}', '{}')"
+        );
+    }
+
+    #[pg_test]
+    fn test_resolve_table_conflict_detects_modified_column() {
+        Spi::run("CREATE TABLE public.test_lm_orders (id INT PRIMARY KEY, updated_at TIMESTAMPTZ)")
+            .expect("create table with updated_at");
+        Spi::run("CREATE TABLE public.test_lm_plain (id INT PRIMARY KEY, note TEXT)")
+            .expect("create table without timestamp");
+
+        let detected = Spi::get_one::<String>(
+            "SELECT steep_repl.detect_modified_column('public', 'test_lm_orders')"
+        );
+        assert_eq!(detected, Ok(Some("updated_at".to_string())));
+
+        let auto = Spi::get_one::<String>(
+            r#"SELECT resolution || '|' || resolved_by FROM steep_repl.resolve_table_conflict(
+                'public', 'test_lm_orders', 'last-modified',
+                '{"id": 1, "updated_at": "2024-01-01T00:00:00Z"}',
+                '{"id": 1, "updated_at": "2024-06-01T00:00:00Z"}')"#
+        );
+        assert_eq!(auto, Ok(Some("kept_b|strategy:last-modified".to_string())));
+
+        // No timestamp column: falls back to the local side, recorded distinctly
+        let fallback = Spi::get_one::<String>(
+            r#"SELECT resolution || '|' || resolved_by FROM steep_repl.resolve_table_conflict(
+                'public', 'test_lm_plain', 'last-modified',
+                '{"id": 1, "note": "a"}',
+                '{"id": 1, "note": "b"}')"#
+        );
+        assert_eq!(fallback, Ok(Some("kept_a|strategy:last-modified:fallback:prefer-node-a".to_string())));
+
+        Spi::run("DROP TABLE public.test_lm_orders, public.test_lm_plain").expect("cleanup tables");
+    }
+
+    #[pg_test]
+    fn test_modified_columns_guc_controls_candidates() {
+        Spi::run("CREATE TABLE public.test_lm_custom (id INT PRIMARY KEY, changed_on TIMESTAMP, updated_at TIMESTAMPTZ)")
+            .expect("create table");
+
+        Spi::run("SET steep_repl.modified_columns = 'changed_on, updated_at'").expect("set guc");
+        let detected = Spi::get_one::<String>(
+            "SELECT steep_repl.detect_modified_column('public', 'test_lm_custom')"
+        );
+        assert_eq!(detected, Ok(Some("changed_on".to_string())), "first configured candidate should win");
+
+        Spi::run("RESET steep_repl.modified_columns").expect("reset guc");
+        let detected = Spi::get_one::<String>(
+            "SELECT steep_repl.detect_modified_column('public', 'test_lm_custom')"
+        );
+        assert_eq!(detected, Ok(Some("updated_at".to_string())));
+
+        Spi::run("DROP TABLE public.test_lm_custom").expect("cleanup table");
+    }
 }
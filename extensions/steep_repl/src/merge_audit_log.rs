@@ -35,7 +35,7 @@ CREATE TABLE steep_repl.merge_audit_log (
     category        TEXT NOT NULL CHECK (category IN ('match', 'conflict', 'local_only', 'remote_only')),
 
     -- Resolution (only for conflicts and transfers)
-    resolution      TEXT CHECK (resolution IS NULL OR resolution IN ('kept_a', 'kept_b', 'skipped')),
+    resolution      TEXT CHECK (resolution IS NULL OR resolution IN ('kept_a', 'kept_b', 'skipped', 'transferred_a_to_b', 'transferred_b_to_a')),
 
     -- Full row values for debugging
     node_a_value    JSONB,                   -- Full row from Node A (NULL if remote_only)
@@ -62,7 +62,7 @@ COMMENT ON COLUMN steep_repl.merge_audit_log.pk_value IS
 COMMENT ON COLUMN steep_repl.merge_audit_log.category IS
     'Row category: match (identical), conflict (different), local_only (A), remote_only (B)';
 COMMENT ON COLUMN steep_repl.merge_audit_log.resolution IS
-    'How conflict was resolved: kept_a, kept_b, or skipped';
+    'How the row was handled: kept_a, kept_b, or skipped for conflicts; transferred_a_to_b or transferred_b_to_a for transfers';
 COMMENT ON COLUMN steep_repl.merge_audit_log.node_a_value IS
     'Full row data from Node A as JSONB (NULL if row only exists on B)';
 COMMENT ON COLUMN steep_repl.merge_audit_log.node_b_value IS
@@ -120,6 +120,63 @@ $$ LANGUAGE sql STABLE;
 COMMENT ON FUNCTION steep_repl.get_merge_summary IS
     'Get summary statistics for a merge operation by category and resolution.';
 
+-- Get per-table summary for a merge
+CREATE FUNCTION steep_repl.merge_summary_by_table(p_merge_id UUID)
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT,
+    category TEXT,
+    resolution TEXT,
+    count BIGINT
+) AS $$
+    SELECT
+        m.table_schema,
+        m.table_name,
+        m.category,
+        m.resolution,
+        count(*)::BIGINT
+    FROM steep_repl.merge_audit_log m
+    WHERE m.merge_id = p_merge_id
+    GROUP BY m.table_schema, m.table_name, m.category, m.resolution
+    ORDER BY m.table_schema, m.table_name, m.category, m.resolution;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.merge_summary_by_table IS
+    'Get summary statistics for a merge operation per table, by category and resolution.';
+
+-- Export a merge's audit rows as CSV lines, header first
+-- Fields containing a comma, quote or newline are quoted (RFC 4180); NULLs are
+-- empty. JSONB columns use their single-line text form, timestamps ISO 8601.
+CREATE FUNCTION steep_repl.export_merge_audit_csv(p_merge_id UUID)
+RETURNS SETOF TEXT AS $$
+    SELECT line
+    FROM (
+        SELECT 0::BIGINT AS sort_id,
+               'id,merge_id,table_schema,table_name,pk_value,category,resolution,node_a_value,node_b_value,resolved_at,resolved_by' AS line
+        UNION ALL
+        SELECT m.id,
+               (SELECT string_agg(
+                           CASE
+                               WHEN f.v IS NULL THEN ''
+                               WHEN f.v ~ '[",
+]' THEN '"' || replace(f.v, '"', '""') || '"'
+                               ELSE f.v
+                           END, ',' ORDER BY f.ord)
+                FROM unnest(ARRAY[
+                    m.id::TEXT, m.merge_id::TEXT, m.table_schema, m.table_name,
+                    m.pk_value::TEXT, m.category, m.resolution,
+                    m.node_a_value::TEXT, m.node_b_value::TEXT,
+                    to_json(m.resolved_at) #>> '{}', m.resolved_by
+                ]) WITH ORDINALITY AS f(v, ord))
+        FROM steep_repl.merge_audit_log m
+        WHERE m.merge_id = p_merge_id
+    ) csv
+    ORDER BY sort_id;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.export_merge_audit_csv IS
+    'Export the audit log of a merge operation as CSV lines with a header row.';
+
 -- Get conflicts for a merge
 CREATE FUNCTION steep_repl.get_merge_conflicts(p_merge_id UUID)
 RETURNS SETOF steep_repl.merge_audit_log AS $$
@@ -148,6 +205,45 @@ $$ LANGUAGE plpgsql;
 
 COMMENT ON FUNCTION steep_repl.prune_merge_audit_log IS
     'Delete merge audit log entries older than the specified interval. Returns count of deleted rows.';
+
+-- Enforce conflict thresholds for a merge
+-- Called after the analysis pass has logged its decisions and before any
+-- changes are applied. Raises conflict_threshold_exceeded when either limit
+-- is exceeded; the logged audit rows are left in place for inspection.
+CREATE FUNCTION steep_repl.check_conflict_threshold(
+    p_merge_id UUID,
+    p_max_conflicts BIGINT DEFAULT NULL,
+    p_max_conflict_ratio REAL DEFAULT NULL
+)
+RETURNS BIGINT AS $$
+DECLARE
+    v_total BIGINT;
+    v_conflicts BIGINT;
+BEGIN
+    SELECT count(*), count(*) FILTER (WHERE category = 'conflict')
+    INTO v_total, v_conflicts
+    FROM steep_repl.merge_audit_log
+    WHERE merge_id = p_merge_id;
+
+    IF p_max_conflicts IS NOT NULL AND v_conflicts > p_max_conflicts THEN
+        RAISE EXCEPTION 'conflict_threshold_exceeded: % conflicts exceeds max_conflicts %',
+            v_conflicts, p_max_conflicts
+            USING DETAIL = format('merge_id=%s total_rows=%s', p_merge_id, v_total);
+    END IF;
+
+    IF p_max_conflict_ratio IS NOT NULL AND v_total > 0
+       AND v_conflicts::REAL / v_total > p_max_conflict_ratio THEN
+        RAISE EXCEPTION 'conflict_threshold_exceeded: conflict ratio % exceeds max_conflict_ratio %',
+            round(v_conflicts::NUMERIC / v_total, 4), p_max_conflict_ratio
+            USING DETAIL = format('merge_id=%s conflicts=%s total_rows=%s', p_merge_id, v_conflicts, v_total);
+    END IF;
+
+    RETURN v_conflicts;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.check_conflict_threshold IS
+    'Abort a merge with conflict_threshold_exceeded if its logged conflicts exceed max_conflicts or max_conflict_ratio. Returns the conflict count.';
 "#,
     name = "create_merge_audit_log_table",
     requires = ["create_schema"],
@@ -311,6 +407,95 @@ mod tests {
         )).expect("cleanup should succeed");
     }
 
+    #[pg_test]
+    fn test_merge_summary_by_table_splits_tables() {
+        let merge_id = Spi::get_one::<pgrx::Uuid>(
+            "SELECT gen_random_uuid()"
+        ).expect("generate uuid").unwrap();
+
+        // Two conflicts on orders, one on customers, one match on customers
+        for (table, id, category, resolution) in [
+            ("orders", 1, "conflict", "'kept_a'"),
+            ("orders", 2, "conflict", "'kept_b'"),
+            ("customers", 1, "conflict", "'kept_a'"),
+            ("customers", 2, "match", "NULL"),
+        ] {
+            Spi::run(&format!(
+                "SELECT steep_repl.log_merge_decision('{}'::uuid, 'public', '{}', '{{\"id\": {}}}'::jsonb, '{}', {}, NULL, NULL, NULL)",
+                merge_id, table, id, category, resolution
+            )).expect("log decision");
+        }
+
+        let orders_conflicts = Spi::get_one::<i64>(&format!(
+            "SELECT sum(count)::BIGINT FROM steep_repl.merge_summary_by_table('{}')
+             WHERE table_name = 'orders' AND category = 'conflict'",
+            merge_id
+        ));
+        assert_eq!(orders_conflicts, Ok(Some(2)), "orders should have 2 conflicts");
+
+        let customers_conflicts = Spi::get_one::<i64>(&format!(
+            "SELECT count FROM steep_repl.merge_summary_by_table('{}')
+             WHERE table_name = 'customers' AND category = 'conflict'",
+            merge_id
+        ));
+        assert_eq!(customers_conflicts, Ok(Some(1)), "customers should have 1 conflict");
+
+        let rows = Spi::get_one::<i64>(&format!(
+            "SELECT count(*) FROM steep_repl.merge_summary_by_table('{}')",
+            merge_id
+        ));
+        assert_eq!(rows, Ok(Some(4)), "one row per table, category and resolution");
+
+        // Cleanup
+        Spi::run(&format!(
+            "DELETE FROM steep_repl.merge_audit_log WHERE merge_id = '{}'",
+            merge_id
+        )).expect("cleanup should succeed");
+    }
+
+    #[pg_test]
+    fn test_check_conflict_threshold_within_limit() {
+        let merge_id = Spi::get_one::<pgrx::Uuid>(
+            "SELECT gen_random_uuid()"
+        ).expect("generate uuid").unwrap();
+
+        Spi::run(&format!(
+            "SELECT steep_repl.log_merge_decision('{}'::uuid, 'public', 't', jsonb_build_object('id', i), 'conflict', 'kept_a', NULL, NULL, NULL)
+             FROM generate_series(1, 2) i",
+            merge_id
+        )).expect("log conflicts");
+
+        let conflicts = Spi::get_one::<i64>(&format!(
+            "SELECT steep_repl.check_conflict_threshold('{}', 2)",
+            merge_id
+        ));
+        assert_eq!(conflicts, Ok(Some(2)), "merge at the threshold should proceed");
+
+        // Cleanup
+        Spi::run(&format!(
+            "DELETE FROM steep_repl.merge_audit_log WHERE merge_id = '{}'",
+            merge_id
+        )).expect("cleanup should succeed");
+    }
+
+    #[pg_test(error = "conflict_threshold_exceeded: 3 conflicts exceeds max_conflicts 2")]
+    fn test_check_conflict_threshold_aborts() {
+        let merge_id = Spi::get_one::<pgrx::Uuid>(
+            "SELECT gen_random_uuid()"
+        ).expect("generate uuid").unwrap();
+
+        Spi::run(&format!(
+            "SELECT steep_repl.log_merge_decision('{}'::uuid, 'public', 't', jsonb_build_object('id', i), 'conflict', NULL, NULL, NULL, NULL)
+             FROM generate_series(1, 3) i",
+            merge_id
+        )).expect("log conflicts");
+
+        let _ = Spi::get_one::<i64>(&format!(
+            "SELECT steep_repl.check_conflict_threshold('{}', 2)",
+            merge_id
+        ));
+    }
+
     #[pg_test]
     fn test_merge_audit_log_indexes() {
         // Check that all expected indexes exist
@@ -332,4 +517,47 @@ mod tests {
             assert_eq!(result, Ok(Some(true)), "index {} should exist", idx_name);
         }
     }
+
+    #[pg_test]
+    fn test_export_merge_audit_csv_escapes_values() {
+        let merge_id = Spi::get_one::<pgrx::Uuid>(
+            "SELECT gen_random_uuid()"
+        ).expect("generate uuid").unwrap();
+
+        Spi::run(&format!(
+            r#"SELECT steep_repl.log_merge_decision('{}'::uuid, 'public', 'orders', '{{"id": 7}}'::jsonb,
+                   'conflict', 'kept_a', '{{"id": 7, "note": "a, b"}}'::jsonb, NULL, 'strategy:prefer-node-a')"#,
+            merge_id
+        )).expect("log decision");
+
+        let header = Spi::get_one::<String>(&format!(
+            "SELECT line FROM steep_repl.export_merge_audit_csv('{}') AS line LIMIT 1",
+            merge_id
+        ));
+        assert_eq!(
+            header,
+            Ok(Some("id,merge_id,table_schema,table_name,pk_value,category,resolution,node_a_value,node_b_value,resolved_at,resolved_by".to_string())),
+            "first line should be the header"
+        );
+
+        let lines = Spi::get_one::<i64>(&format!(
+            "SELECT count(*) FROM steep_repl.export_merge_audit_csv('{}')",
+            merge_id
+        ));
+        assert_eq!(lines, Ok(Some(2)), "header plus one row");
+
+        // Quotes are doubled, fields with commas or quotes are wrapped, NULL is empty
+        let escaped = Spi::get_one::<bool>(&format!(
+            r#"SELECT line LIKE '%,public,orders,"{{""id"": 7}}",conflict,kept_a,"{{""id"": 7, ""note"": ""a, b""}}",,%,strategy:prefer-node-a'
+               FROM steep_repl.export_merge_audit_csv('{}') AS line OFFSET 1"#,
+            merge_id
+        ));
+        assert_eq!(escaped, Ok(Some(true)), "values should be CSV-escaped");
+
+        // Cleanup
+        Spi::run(&format!(
+            "DELETE FROM steep_repl.merge_audit_log WHERE merge_id = '{}'",
+            merge_id
+        )).expect("cleanup should succeed");
+    }
 }
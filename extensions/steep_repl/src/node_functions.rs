@@ -0,0 +1,808 @@
+//! Node SQL functions for steep_repl extension.
+//!
+//! This module provides SQL functions for resolving and managing the
+//! nodes registered in steep_repl.nodes.
+
+use pgrx::prelude::*;
+
+extension_sql!(
+    r#"
+-- Resolve the local node identity from the steep_repl.node_id GUC
+-- Errors if the setting is missing or does not name a registered node,
+-- unless p_missing_ok is set, in which case NULL is returned instead
+CREATE FUNCTION steep_repl.local_node(p_missing_ok BOOLEAN DEFAULT false)
+RETURNS TEXT AS $$
+DECLARE
+    v_node_id TEXT;
+BEGIN
+    v_node_id := NULLIF(current_setting('steep_repl.node_id', true), '');
+
+    IF v_node_id IS NULL THEN
+        IF p_missing_ok THEN
+            RETURN NULL;
+        END IF;
+        RAISE EXCEPTION 'steep_repl.node_id is not set'
+            USING HINT = 'Set steep_repl.node_id to this server''s node_id in postgresql.conf';
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM steep_repl.nodes WHERE node_id = v_node_id) THEN
+        IF p_missing_ok THEN
+            RETURN NULL;
+        END IF;
+        RAISE EXCEPTION 'Local node % not found in steep_repl.nodes', v_node_id;
+    END IF;
+
+    RETURN v_node_id;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.local_node(BOOLEAN) IS 'Resolve the local node_id configured by steep_repl.node_id';
+
+-- Rename a node and every reference to it in one transaction
+-- Foreign keys referencing steep_repl.nodes are discovered from the catalog,
+-- so new referencing tables are covered automatically. Cached fingerprints
+-- (not FK-bound) are moved as well. The rename is recorded in audit_log.
+CREATE FUNCTION steep_repl.rename_node(p_old TEXT, p_new TEXT)
+RETURNS VOID AS $$
+DECLARE
+    v_old_row JSONB;
+    v_fk RECORD;
+    v_was_local BOOLEAN;
+BEGIN
+    IF p_new IS NULL OR p_new = '' THEN
+        RAISE EXCEPTION 'New node_id must not be empty';
+    END IF;
+
+    SELECT to_jsonb(n) INTO v_old_row
+    FROM steep_repl.nodes n
+    WHERE n.node_id = p_old
+    FOR UPDATE;
+
+    IF v_old_row IS NULL THEN
+        RAISE EXCEPTION 'Node % not found in steep_repl.nodes', p_old;
+    END IF;
+
+    IF EXISTS (SELECT 1 FROM steep_repl.nodes WHERE node_id = p_new) THEN
+        RAISE EXCEPTION 'Node % already exists', p_new;
+    END IF;
+
+    v_was_local := p_old IS NOT DISTINCT FROM steep_repl.local_node(true);
+
+    -- Copy the row under the new id so references can be repointed
+    INSERT INTO steep_repl.nodes
+    SELECT (jsonb_populate_record(NULL::steep_repl.nodes,
+        v_old_row || jsonb_build_object('node_id', p_new))).*;
+
+    FOR v_fk IN
+        SELECT c.conrelid::regclass AS tbl, a.attname AS col
+        FROM pg_constraint c
+        JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = ANY(c.conkey)
+        WHERE c.contype = 'f'
+          AND c.confrelid = 'steep_repl.nodes'::regclass
+    LOOP
+        EXECUTE format('UPDATE %s SET %I = $1 WHERE %I = $2', v_fk.tbl, v_fk.col, v_fk.col)
+        USING p_new, p_old;
+    END LOOP;
+
+    UPDATE steep_repl.schema_fingerprints SET node_id = p_new WHERE node_id = p_old;
+    UPDATE steep_repl.coordinator_state
+    SET value = jsonb_build_object('node_id', p_new), updated_at = now()
+    WHERE key = 'preferred_snapshot_source' AND value->>'node_id' = p_old;
+
+    DELETE FROM steep_repl.nodes WHERE node_id = p_old;
+
+    INSERT INTO steep_repl.audit_log (action, actor, target_type, target_id, old_value, new_value, client_ip)
+    VALUES (
+        'node.renamed',
+        current_user || '@' || COALESCE(host(inet_client_addr()), 'localhost'),
+        'node',
+        p_new,
+        jsonb_build_object('node_id', p_old),
+        jsonb_build_object('node_id', p_new),
+        inet_client_addr()
+    );
+
+    IF v_was_local THEN
+        RAISE NOTICE 'Renamed the local node; update steep_repl.node_id to %', p_new;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.rename_node(TEXT, TEXT) IS 'Rename a node_id and cascade the change to all referencing rows';
+
+-- Cluster-wide health rollup for dashboards
+-- red: no healthy coordinator or healthy nodes are not a majority (no quorum)
+-- yellow: some nodes unhealthy or failed actions in the last hour
+-- green: otherwise
+CREATE FUNCTION steep_repl.cluster_health()
+RETURNS TABLE (
+    overall TEXT,
+    healthy_nodes INTEGER,
+    total_nodes INTEGER,
+    active_operations INTEGER,
+    failed_recent INTEGER
+) AS $$
+DECLARE
+    v_has_coordinator BOOLEAN;
+BEGIN
+    SELECT count(*) FILTER (WHERE n.status = 'healthy'),
+           count(*),
+           bool_or(n.is_coordinator AND n.status = 'healthy')
+    INTO healthy_nodes, total_nodes, v_has_coordinator
+    FROM steep_repl.nodes n;
+
+    SELECT (SELECT count(*) FROM steep_repl.snapshots s WHERE s.status IN ('generating', 'applying'))
+         + (SELECT count(*) FROM steep_repl.init_progress p WHERE p.phase IN ('preparing', 'copying', 'catching_up'))
+    INTO active_operations;
+
+    SELECT count(*) INTO failed_recent
+    FROM steep_repl.audit_log a
+    WHERE NOT a.success AND a.occurred_at > now() - interval '1 hour';
+
+    overall := CASE
+        WHEN NOT COALESCE(v_has_coordinator, false) OR healthy_nodes * 2 <= total_nodes THEN 'red'
+        WHEN healthy_nodes < total_nodes OR failed_recent > 0 THEN 'yellow'
+        ELSE 'green'
+    END;
+
+    RETURN NEXT;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.cluster_health() IS 'Cluster health rollup (green/yellow/red) with node, operation, and failure counts';
+
+-- WAL position difference between this node and a peer
+-- The peer is read first so a loopback comparison never goes negative.
+-- estimated_lag comes from the peer's last replay timestamp when it is a
+-- standby; it is NULL for a primary peer that is behind by bytes.
+CREATE FUNCTION steep_repl.replication_lag(p_peer_connstr TEXT)
+RETURNS TABLE (
+    local_lsn PG_LSN,
+    peer_lsn PG_LSN,
+    lag_bytes NUMERIC,
+    estimated_lag INTERVAL
+) AS $$
+DECLARE
+    v_replay_at TIMESTAMPTZ;
+BEGIN
+    CREATE EXTENSION IF NOT EXISTS dblink;
+
+    SELECT r.lsn, r.replay_at INTO peer_lsn, v_replay_at
+    FROM dblink(p_peer_connstr, $q$
+        SELECT CASE WHEN pg_is_in_recovery() THEN pg_last_wal_replay_lsn() ELSE pg_current_wal_lsn() END,
+               pg_last_xact_replay_timestamp()
+    $q$) AS r(lsn PG_LSN, replay_at TIMESTAMPTZ);
+
+    local_lsn := pg_current_wal_lsn();
+    lag_bytes := GREATEST(pg_wal_lsn_diff(local_lsn, peer_lsn), 0);
+    estimated_lag := CASE
+        WHEN lag_bytes = 0 THEN interval '0'
+        WHEN v_replay_at IS NOT NULL THEN GREATEST(clock_timestamp() - v_replay_at, interval '0')
+    END;
+
+    RETURN NEXT;
+END;
+$$ LANGUAGE plpgsql VOLATILE;
+
+COMMENT ON FUNCTION steep_repl.replication_lag(TEXT) IS 'Bytes (and, for standbys, time) a peer is behind this node''s current WAL position';
+
+-- Redirect snapshots to a node without changing callers (e.g. during source maintenance)
+-- Stored in coordinator_state; NULL clears the preference.
+CREATE FUNCTION steep_repl.set_preferred_source(p_node_id TEXT)
+RETURNS VOID AS $$
+DECLARE
+    v_old JSONB;
+BEGIN
+    IF p_node_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM steep_repl.nodes WHERE node_id = p_node_id) THEN
+        RAISE EXCEPTION 'Node % not found in steep_repl.nodes', p_node_id;
+    END IF;
+
+    SELECT value INTO v_old FROM steep_repl.coordinator_state
+    WHERE key = 'preferred_snapshot_source'
+    FOR UPDATE;
+
+    IF p_node_id IS NULL THEN
+        DELETE FROM steep_repl.coordinator_state WHERE key = 'preferred_snapshot_source';
+    ELSE
+        INSERT INTO steep_repl.coordinator_state (key, value)
+        VALUES ('preferred_snapshot_source', jsonb_build_object('node_id', p_node_id))
+        ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = now();
+    END IF;
+
+    INSERT INTO steep_repl.audit_log (action, actor, target_type, target_id, old_value, new_value, client_ip)
+    VALUES (
+        'snapshot.source_preferred',
+        current_user || '@' || COALESCE(host(inet_client_addr()), 'localhost'),
+        'node',
+        p_node_id,
+        v_old,
+        CASE WHEN p_node_id IS NOT NULL THEN jsonb_build_object('node_id', p_node_id) END,
+        inet_client_addr()
+    );
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.set_preferred_source(TEXT) IS 'Set (or clear with NULL) the preferred node for snapshots';
+
+-- Node to take a snapshot from when the caller does not name one
+-- The preferred source if set and healthy, else the highest-priority healthy node.
+CREATE FUNCTION steep_repl.snapshot_source()
+RETURNS TEXT AS $$
+    SELECT COALESCE(
+        (SELECT n.node_id
+         FROM steep_repl.coordinator_state c
+         JOIN steep_repl.nodes n ON n.node_id = c.value->>'node_id'
+         WHERE c.key = 'preferred_snapshot_source' AND n.status = 'healthy'),
+        (SELECT n.node_id
+         FROM steep_repl.nodes n
+         WHERE n.status = 'healthy'
+         ORDER BY n.priority DESC, n.node_id
+         LIMIT 1)
+    );
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.snapshot_source() IS 'Default snapshot source: the preferred node if healthy, else the highest-priority healthy node';
+
+-- Portable cluster configuration for disaster recovery and environment cloning
+-- Only the registration fields are exported; runtime state (status, last_seen,
+-- init_* and throughput metrics) is rebuilt by the daemons after import.
+CREATE FUNCTION steep_repl.export_cluster_config()
+RETURNS JSONB AS $$
+    SELECT jsonb_build_object(
+        'format', 1,
+        'exported_at', now(),
+        'preferred_snapshot_source',
+            (SELECT c.value->>'node_id' FROM steep_repl.coordinator_state c
+             WHERE c.key = 'preferred_snapshot_source'),
+        'nodes', COALESCE(jsonb_agg(jsonb_build_object(
+            'node_id', n.node_id,
+            'node_name', n.node_name,
+            'host', n.host,
+            'port', n.port,
+            'grpc_host', n.grpc_host,
+            'grpc_port', n.grpc_port,
+            'priority', n.priority,
+            'is_coordinator', n.is_coordinator
+        ) ORDER BY n.node_id), '[]'::jsonb)
+    )
+    FROM steep_repl.nodes n;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.export_cluster_config() IS 'Export node registrations as a JSONB document for import_cluster_config';
+
+-- Recreate nodes from export_cluster_config() output
+-- Idempotent: existing nodes are updated in place, missing ones inserted.
+-- Nodes not present in the document are left untouched. Returns the number
+-- of nodes in the document.
+CREATE FUNCTION steep_repl.import_cluster_config(p_config JSONB)
+RETURNS INTEGER AS $$
+DECLARE
+    v_count INTEGER;
+    v_preferred TEXT;
+BEGIN
+    IF p_config IS NULL OR jsonb_typeof(p_config->'nodes') IS DISTINCT FROM 'array' THEN
+        RAISE EXCEPTION 'Cluster config must contain a nodes array';
+    END IF;
+
+    IF COALESCE((p_config->>'format')::INTEGER, 1) <> 1 THEN
+        RAISE EXCEPTION 'Unsupported cluster config format %', p_config->>'format';
+    END IF;
+
+    INSERT INTO steep_repl.nodes (node_id, node_name, host, port, grpc_host, grpc_port, priority, is_coordinator)
+    SELECT c.node_id, c.node_name, c.host,
+           COALESCE(c.port, 5432), c.grpc_host, c.grpc_port,
+           COALESCE(c.priority, 50), COALESCE(c.is_coordinator, false)
+    FROM jsonb_to_recordset(p_config->'nodes') AS c(
+        node_id TEXT, node_name TEXT, host TEXT, port INTEGER,
+        grpc_host TEXT, grpc_port INTEGER, priority INTEGER, is_coordinator BOOLEAN
+    )
+    ON CONFLICT (node_id) DO UPDATE SET
+        node_name = EXCLUDED.node_name,
+        host = EXCLUDED.host,
+        port = EXCLUDED.port,
+        grpc_host = EXCLUDED.grpc_host,
+        grpc_port = EXCLUDED.grpc_port,
+        priority = EXCLUDED.priority,
+        is_coordinator = EXCLUDED.is_coordinator;
+
+    v_count := jsonb_array_length(p_config->'nodes');
+
+    v_preferred := p_config->>'preferred_snapshot_source';
+    IF v_preferred IS NOT NULL THEN
+        INSERT INTO steep_repl.coordinator_state (key, value)
+        VALUES ('preferred_snapshot_source', jsonb_build_object('node_id', v_preferred))
+        ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = now();
+    END IF;
+
+    INSERT INTO steep_repl.audit_log (action, actor, target_type, target_id, new_value, client_ip)
+    VALUES (
+        'cluster.config_imported',
+        current_user || '@' || COALESCE(host(inet_client_addr()), 'localhost'),
+        'cluster',
+        NULL,
+        jsonb_build_object('nodes', v_count, 'exported_at', p_config->'exported_at'),
+        inet_client_addr()
+    );
+
+    RETURN v_count;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.import_cluster_config(JSONB) IS 'Idempotently recreate nodes from an export_cluster_config() document';
+
+-- Pre-flight for deregistering a node
+-- One row per dependency check, then an 'overall' row that is ok only when
+-- nothing blocks removal. Snapshots count while they can still be applied.
+CREATE FUNCTION steep_repl.can_remove_node(p_node_id TEXT)
+RETURNS TABLE (
+    check_name TEXT,
+    ok BOOLEAN,
+    detail TEXT
+) AS $$
+DECLARE
+    v_is_coordinator BOOLEAN;
+    v_items TEXT;
+    v_safe BOOLEAN := true;
+BEGIN
+    SELECT n.is_coordinator INTO v_is_coordinator
+    FROM steep_repl.nodes n
+    WHERE n.node_id = p_node_id;
+
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Node % not found in steep_repl.nodes', p_node_id;
+    END IF;
+
+    SELECT string_agg(n.node_id || ' (' || n.init_state || ')', ', ' ORDER BY n.node_id) INTO v_items
+    FROM steep_repl.nodes n
+    WHERE n.init_source_node = p_node_id AND n.node_id <> p_node_id;
+    check_name := 'dependent_inits';
+    ok := v_items IS NULL;
+    detail := COALESCE('init source for ' || v_items, 'no nodes initialized from this node');
+    v_safe := v_safe AND ok;
+    RETURN NEXT;
+
+    SELECT string_agg(s.snapshot_id, ', ' ORDER BY s.snapshot_id) INTO v_items
+    FROM steep_repl.snapshots s
+    WHERE (s.source_node_id = p_node_id OR s.target_node_id = p_node_id)
+      AND s.status IN ('pending', 'generating', 'complete', 'applying');
+    check_name := 'snapshots';
+    ok := v_items IS NULL;
+    detail := COALESCE('referenced by snapshots ' || v_items, 'no usable snapshots reference this node');
+    v_safe := v_safe AND ok;
+    RETURN NEXT;
+
+    SELECT string_agg(sl.slot_name, ', ' ORDER BY sl.slot_name) INTO v_items
+    FROM steep_repl.init_slots sl
+    WHERE sl.node_id = p_node_id AND sl.used_by_node IS NULL;
+    check_name := 'init_slots';
+    ok := v_items IS NULL;
+    detail := COALESCE('owns unused init slots ' || v_items, 'no unused init slots');
+    v_safe := v_safe AND ok;
+    RETURN NEXT;
+
+    check_name := 'coordinator';
+    ok := NOT v_is_coordinator;
+    detail := CASE WHEN v_is_coordinator THEN 'node is the coordinator' ELSE 'node is not the coordinator' END;
+    v_safe := v_safe AND ok;
+    RETURN NEXT;
+
+    check_name := 'overall';
+    ok := v_safe;
+    detail := CASE WHEN v_safe THEN 'safe to remove' ELSE 'removal blocked' END;
+    RETURN NEXT;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.can_remove_node(TEXT) IS 'Report dependencies (inits, snapshots, slots, coordinator role) blocking removal of a node';
+
+-- Nodes registered more than once under different node_ids. Rows of one
+-- physical node share (host, port), with host compared case-insensitively;
+-- group_id numbers the groups so rows can be told apart when listed together.
+CREATE FUNCTION steep_repl.find_duplicate_nodes()
+RETURNS TABLE (
+    group_id INTEGER,
+    host TEXT,
+    port INTEGER,
+    node_id TEXT,
+    node_name TEXT,
+    status TEXT,
+    is_coordinator BOOLEAN,
+    last_seen TIMESTAMPTZ
+) AS $$
+    SELECT dense_rank() OVER (ORDER BY lower(n.host), n.port)::INTEGER,
+           lower(n.host), n.port, n.node_id, n.node_name, n.status, n.is_coordinator, n.last_seen
+    FROM (
+        SELECT nd.*, count(*) OVER (PARTITION BY lower(nd.host), nd.port) AS group_size
+        FROM steep_repl.nodes nd
+    ) n
+    WHERE n.group_size > 1
+    ORDER BY lower(n.host), n.port, n.node_id;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.find_duplicate_nodes() IS 'Groups of nodes registered under different node_ids with the same host and port';
+"#,
+    name = "create_node_functions",
+    requires = [
+        "create_nodes_table",
+        "create_coordinator_state_table",
+        "create_audit_log_table",
+        "create_schema_fingerprints_table",
+        "create_snapshots_table",
+        "create_init_progress_table",
+        "create_init_slots_table",
+    ],
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn test_local_node_function_exists() {
+        let result = Spi::get_one::<bool>(
+            "SELECT EXISTS(
+                SELECT 1 FROM pg_proc p
+                JOIN pg_namespace n ON p.pronamespace = n.oid
+                WHERE n.nspname = 'steep_repl' AND p.proname = 'local_node'
+            )"
+        );
+        assert_eq!(result, Ok(Some(true)), "local_node function should exist");
+    }
+
+    #[pg_test]
+    fn test_local_node_uses_guc() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('local-guc-node', 'Local', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run("SET steep_repl.node_id = 'local-guc-node'").expect("set guc");
+
+        let result = Spi::get_one::<String>("SELECT steep_repl.local_node()");
+        assert_eq!(result, Ok(Some("local-guc-node".to_string())));
+
+        // Cleanup
+        Spi::run("RESET steep_repl.node_id").expect("reset guc");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'local-guc-node'")
+            .expect("cleanup should succeed");
+    }
+
+    #[pg_test(error = "steep_repl.node_id is not set")]
+    fn test_local_node_errors_when_unset() {
+        Spi::run("RESET steep_repl.node_id").expect("reset guc");
+        let _ = Spi::get_one::<String>("SELECT steep_repl.local_node()");
+    }
+
+    #[pg_test]
+    fn test_local_node_missing_ok() {
+        Spi::run("RESET steep_repl.node_id").expect("reset guc");
+        let unset = Spi::get_one::<String>("SELECT steep_repl.local_node(true)");
+        assert_eq!(unset, Ok(None), "unset node_id should resolve to NULL");
+
+        Spi::run("SET steep_repl.node_id = 'unregistered-node'").expect("set guc");
+        let unregistered = Spi::get_one::<String>("SELECT steep_repl.local_node(true)");
+        assert_eq!(unregistered, Ok(None), "unregistered node_id should resolve to NULL");
+
+        // Cleanup
+        Spi::run("RESET steep_repl.node_id").expect("reset guc");
+    }
+
+    #[pg_test]
+    fn test_rename_node_updates_references() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('rename-old', 'Old', 'host-old', 5432, 50, 'healthy'),
+                    ('rename-peer', 'Peer', 'host-peer', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "UPDATE steep_repl.nodes SET init_source_node = 'rename-old' WHERE node_id = 'rename-peer'"
+        ).expect("set init source");
+        Spi::run(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, target_node_id)
+             VALUES ('snap_rename', 'rename-old', 'rename-peer')"
+        ).expect("snapshot insert should succeed");
+
+        Spi::run("SELECT steep_repl.rename_node('rename-old', 'rename-new')").expect("rename should succeed");
+
+        let source = Spi::get_one::<String>(
+            "SELECT source_node_id FROM steep_repl.snapshots WHERE snapshot_id = 'snap_rename'"
+        );
+        assert_eq!(source, Ok(Some("rename-new".to_string())), "snapshot source should follow");
+
+        let init_source = Spi::get_one::<String>(
+            "SELECT init_source_node FROM steep_repl.nodes WHERE node_id = 'rename-peer'"
+        );
+        assert_eq!(init_source, Ok(Some("rename-new".to_string())), "init_source_node should follow");
+
+        let old_exists = Spi::get_one::<bool>(
+            "SELECT EXISTS(SELECT 1 FROM steep_repl.nodes WHERE node_id = 'rename-old')"
+        );
+        assert_eq!(old_exists, Ok(Some(false)));
+
+        let audited = Spi::get_one::<String>(
+            "SELECT old_value->>'node_id' FROM steep_repl.audit_log
+             WHERE action = 'node.renamed' AND target_id = 'rename-new'"
+        );
+        assert_eq!(audited, Ok(Some("rename-old".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE action = 'node.renamed' AND target_id = 'rename-new'")
+            .expect("cleanup audit log should succeed");
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_rename'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("UPDATE steep_repl.nodes SET init_source_node = NULL WHERE node_id = 'rename-peer'")
+            .expect("clear init source");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id IN ('rename-new', 'rename-peer')")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test(error = "Node rename-b already exists")]
+    fn test_rename_node_refuses_existing_id() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('rename-a', 'A', 'host-a', 5432, 50, 'healthy'),
+                    ('rename-b', 'B', 'host-b', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run("SELECT steep_repl.rename_node('rename-a', 'rename-b')").expect("rename should fail");
+    }
+
+    #[pg_test]
+    fn test_cluster_health_colors() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status, is_coordinator)
+             VALUES ('health-a', 'A', 'host-a', 5432, 50, 'healthy', true),
+                    ('health-b', 'B', 'host-b', 5432, 50, 'healthy', false),
+                    ('health-c', 'C', 'host-c', 5432, 50, 'healthy', false)"
+        ).expect("node insert should succeed");
+
+        let green = Spi::get_one::<String>(
+            "SELECT overall || ':' || healthy_nodes || '/' || total_nodes FROM steep_repl.cluster_health()"
+        );
+        assert_eq!(green, Ok(Some("green:3/3".to_string())), "all healthy should be green");
+
+        // One node down keeps quorum
+        Spi::run("UPDATE steep_repl.nodes SET status = 'unreachable' WHERE node_id = 'health-c'")
+            .expect("degrade node");
+        let yellow = Spi::get_one::<String>("SELECT overall FROM steep_repl.cluster_health()");
+        assert_eq!(yellow, Ok(Some("yellow".to_string())), "minority down should be yellow");
+
+        // Two of three down loses quorum
+        Spi::run("UPDATE steep_repl.nodes SET status = 'offline' WHERE node_id = 'health-b'")
+            .expect("degrade node");
+        let no_quorum = Spi::get_one::<String>("SELECT overall FROM steep_repl.cluster_health()");
+        assert_eq!(no_quorum, Ok(Some("red".to_string())), "no quorum should be red");
+
+        // Healthy nodes but no coordinator
+        Spi::run("UPDATE steep_repl.nodes SET status = 'healthy', is_coordinator = false").expect("reset nodes");
+        let no_coordinator = Spi::get_one::<String>("SELECT overall FROM steep_repl.cluster_health()");
+        assert_eq!(no_coordinator, Ok(Some("red".to_string())), "missing coordinator should be red");
+
+        // Recent failed action
+        Spi::run("UPDATE steep_repl.nodes SET is_coordinator = true WHERE node_id = 'health-a'")
+            .expect("restore coordinator");
+        Spi::run(
+            "INSERT INTO steep_repl.audit_log (action, actor, success, error_message)
+             VALUES ('snapshot.failed', 'health@localhost', false, 'disk full')"
+        ).expect("audit insert should succeed");
+        let failed = Spi::get_one::<String>(
+            "SELECT overall || ':' || failed_recent FROM steep_repl.cluster_health()"
+        );
+        assert_eq!(failed, Ok(Some("yellow:1".to_string())), "recent failures should be yellow");
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE actor = 'health@localhost'")
+            .expect("cleanup audit log should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'health-%'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_replication_lag_loopback() {
+        Spi::run("CREATE EXTENSION IF NOT EXISTS dblink").expect("dblink");
+
+        let ok = Spi::get_one::<bool>(
+            "SELECT lag_bytes >= 0 AND local_lsn >= peer_lsn
+             FROM steep_repl.replication_lag(
+                 'host=localhost port=' || current_setting('port') || ' dbname=postgres')",
+        );
+        assert_eq!(ok, Ok(Some(true)), "loopback lag should be non-negative");
+    }
+
+    #[pg_test]
+    fn test_snapshot_source_prefers_configured_node() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status) VALUES
+                ('src-primary', 'Primary', 'localhost', 5432, 90, 'healthy'),
+                ('src-replica', 'Replica', 'localhost', 5433, 10, 'healthy')"
+        ).expect("node insert should succeed");
+
+        // Without a preference the highest-priority healthy node is used
+        let result = Spi::get_one::<String>("SELECT steep_repl.snapshot_source()");
+        assert_eq!(result, Ok(Some("src-primary".to_string())));
+
+        Spi::run("SELECT steep_repl.set_preferred_source('src-replica')").expect("set preference");
+        let result = Spi::get_one::<String>("SELECT steep_repl.snapshot_source()");
+        assert_eq!(result, Ok(Some("src-replica".to_string())), "preference should win");
+
+        // An unhealthy preferred node falls back to priority order
+        Spi::run("UPDATE steep_repl.nodes SET status = 'offline' WHERE node_id = 'src-replica'")
+            .expect("mark offline");
+        let result = Spi::get_one::<String>("SELECT steep_repl.snapshot_source()");
+        assert_eq!(result, Ok(Some("src-primary".to_string())));
+
+        Spi::run("SELECT steep_repl.set_preferred_source(NULL)").expect("clear preference");
+        let cleared = Spi::get_one::<bool>(
+            "SELECT NOT EXISTS(SELECT 1 FROM steep_repl.coordinator_state WHERE key = 'preferred_snapshot_source')",
+        );
+        assert_eq!(cleared, Ok(Some(true)));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE action = 'snapshot.source_preferred'")
+            .expect("cleanup audit log should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'src-%'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test(error = "Node missing-node not found in steep_repl.nodes")]
+    fn test_set_preferred_source_unknown_node() {
+        Spi::run("SELECT steep_repl.set_preferred_source('missing-node')").expect("should error");
+    }
+
+    #[pg_test]
+    fn test_cluster_config_round_trip() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, grpc_host, grpc_port, priority, is_coordinator, status)
+             VALUES ('cfg-a', 'Node A', 'host-a', 5432, 'host-a', 15460, 80, true, 'healthy'),
+                    ('cfg-b', 'Node B', 'host-b', 5433, NULL, NULL, 20, false, 'degraded')"
+        ).expect("node insert should succeed");
+        Spi::run("SELECT steep_repl.set_preferred_source('cfg-b')").expect("set preference");
+
+        Spi::run(
+            "CREATE TEMP TABLE cfg_export AS SELECT steep_repl.export_cluster_config() AS doc"
+        ).expect("export should succeed");
+
+        // Wipe the cluster and restore it from the export
+        Spi::run("SELECT steep_repl.set_preferred_source(NULL)").expect("clear preference");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'cfg-%'").expect("wipe nodes");
+
+        let imported = Spi::get_one::<i32>(
+            "SELECT steep_repl.import_cluster_config(doc) FROM cfg_export"
+        );
+        assert_eq!(imported, Ok(Some(2)));
+
+        let restored = Spi::get_one::<String>(
+            "SELECT string_agg(node_id || ':' || host || ':' || port || ':' || priority || ':' || is_coordinator, ',' ORDER BY node_id)
+             FROM steep_repl.nodes WHERE node_id LIKE 'cfg-%'"
+        );
+        assert_eq!(restored, Ok(Some("cfg-a:host-a:5432:80:true,cfg-b:host-b:5433:20:false".to_string())));
+
+        let grpc = Spi::get_one::<i32>("SELECT grpc_port FROM steep_repl.nodes WHERE node_id = 'cfg-a'");
+        assert_eq!(grpc, Ok(Some(15460)));
+
+        // Runtime state is not carried over
+        let status = Spi::get_one::<String>("SELECT status FROM steep_repl.nodes WHERE node_id = 'cfg-b'");
+        assert_eq!(status, Ok(Some("unknown".to_string())));
+
+        let preferred = Spi::get_one::<String>(
+            "SELECT value->>'node_id' FROM steep_repl.coordinator_state WHERE key = 'preferred_snapshot_source'"
+        );
+        assert_eq!(preferred, Ok(Some("cfg-b".to_string())));
+
+        // Importing again is a no-op
+        Spi::run("SELECT steep_repl.import_cluster_config(doc) FROM cfg_export").expect("re-import");
+        let count = Spi::get_one::<i64>("SELECT count(*) FROM steep_repl.nodes WHERE node_id LIKE 'cfg-%'");
+        assert_eq!(count, Ok(Some(2)));
+
+        // Cleanup
+        Spi::run("DROP TABLE cfg_export").expect("drop temp table");
+        Spi::run("DELETE FROM steep_repl.coordinator_state WHERE key = 'preferred_snapshot_source'")
+            .expect("cleanup coordinator state");
+        Spi::run("DELETE FROM steep_repl.audit_log WHERE action IN ('snapshot.source_preferred', 'cluster.config_imported')")
+            .expect("cleanup audit log should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'cfg-%'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test(error = "Cluster config must contain a nodes array")]
+    fn test_import_cluster_config_rejects_bad_document() {
+        Spi::run("SELECT steep_repl.import_cluster_config('{}'::jsonb)").expect("should error");
+    }
+
+    #[pg_test]
+    fn test_can_remove_node_reports_dependencies() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('rm-source', 'Source', 'host-s', 5432, 50, 'healthy'),
+                    ('rm-target', 'Target', 'host-t', 5432, 50, 'healthy'),
+                    ('rm-lonely', 'Lonely', 'host-l', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "UPDATE steep_repl.nodes SET init_source_node = 'rm-source', init_state = 'copying'
+             WHERE node_id = 'rm-target'"
+        ).expect("set init source");
+
+        let blocked = Spi::get_one::<bool>(
+            "SELECT ok FROM steep_repl.can_remove_node('rm-source') WHERE check_name = 'overall'"
+        );
+        assert_eq!(blocked, Ok(Some(false)), "init source should not be removable");
+
+        let detail = Spi::get_one::<String>(
+            "SELECT detail FROM steep_repl.can_remove_node('rm-source') WHERE check_name = 'dependent_inits'"
+        );
+        assert_eq!(detail, Ok(Some("init source for rm-target (copying)".to_string())));
+
+        let safe = Spi::get_one::<bool>(
+            "SELECT bool_and(ok) FROM steep_repl.can_remove_node('rm-lonely')"
+        );
+        assert_eq!(safe, Ok(Some(true)), "isolated node should be removable");
+
+        // Cleanup
+        Spi::run("UPDATE steep_repl.nodes SET init_source_node = NULL WHERE node_id = 'rm-target'")
+            .expect("clear init source");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'rm-%'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_can_remove_node_blocks_on_snapshots_and_coordinator() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status, is_coordinator)
+             VALUES ('rm-coord', 'Coordinator', 'host-c', 5432, 50, 'healthy', true),
+                    ('rm-peer', 'Peer', 'host-p', 5432, 50, 'healthy', false)"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, target_node_id, status)
+             VALUES ('snap_rm', 'rm-peer', NULL, 'complete')"
+        ).expect("snapshot insert should succeed");
+
+        let coord = Spi::get_one::<bool>(
+            "SELECT ok FROM steep_repl.can_remove_node('rm-coord') WHERE check_name = 'coordinator'"
+        );
+        assert_eq!(coord, Ok(Some(false)));
+
+        let snaps = Spi::get_one::<String>(
+            "SELECT detail FROM steep_repl.can_remove_node('rm-peer') WHERE check_name = 'snapshots'"
+        );
+        assert_eq!(snaps, Ok(Some("referenced by snapshots snap_rm".to_string())));
+
+        // Expired snapshots no longer block
+        Spi::run("UPDATE steep_repl.snapshots SET status = 'expired' WHERE snapshot_id = 'snap_rm'")
+            .expect("expire snapshot");
+        let overall = Spi::get_one::<bool>(
+            "SELECT ok FROM steep_repl.can_remove_node('rm-peer') WHERE check_name = 'overall'"
+        );
+        assert_eq!(overall, Ok(Some(true)));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_rm'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'rm-%'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test(error = "Node missing-node not found in steep_repl.nodes")]
+    fn test_can_remove_node_unknown_node() {
+        let _ = Spi::get_one::<bool>("SELECT bool_and(ok) FROM steep_repl.can_remove_node('missing-node')");
+    }
+
+    #[pg_test]
+    fn test_find_duplicate_nodes_groups_same_host_port() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('dup-a', 'Primary', 'db1.example.com', 5432, 50, 'healthy'),
+                    ('dup-b', 'Primary again', 'DB1.example.com', 5432, 50, 'unknown'),
+                    ('dup-c', 'Other port', 'db1.example.com', 5433, 50, 'healthy'),
+                    ('dup-d', 'Unique', 'db2.example.com', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+
+        let groups = Spi::get_one::<String>(
+            "SELECT string_agg(node_id, ',' ORDER BY node_id) || '/' || count(DISTINCT group_id)
+             FROM steep_repl.find_duplicate_nodes() WHERE node_id LIKE 'dup-%'"
+        );
+        assert_eq!(groups, Ok(Some("dup-a,dup-b/1".to_string())), "same host and port should form one group");
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id LIKE 'dup-%'")
+            .expect("cleanup nodes should succeed");
+    }
+}
@@ -1,6 +1,8 @@
 //! Schema creation for steep_repl extension.
 //!
-//! This module creates the steep_repl schema as the bootstrap step.
+//! This module creates the steep_repl schema as the bootstrap step, along
+//! with schema_name() for dynamic SQL that needs to name the schema and
+//! api() listing the functions it contains.
 
 use pgrx::prelude::*;
 
@@ -10,6 +12,47 @@ extension_sql!(
 CREATE SCHEMA IF NOT EXISTS steep_repl;
 
 COMMENT ON SCHEMA steep_repl IS 'Steep bidirectional replication coordination schema';
+
+-- Schema holding the extension's objects
+-- Dynamic SQL builds names from this rather than a hardcoded 'steep_repl.'
+-- so a relocatable build only has to change the schema, not every query.
+-- Falls back to steep_repl when the objects are not installed as an extension.
+CREATE FUNCTION steep_repl.schema_name()
+RETURNS TEXT AS $$
+    SELECT COALESCE(
+        (SELECT n.nspname::TEXT
+         FROM pg_depend d
+         JOIN pg_namespace n ON n.oid = d.objid
+         WHERE d.classid = 'pg_namespace'::regclass
+           AND d.refclassid = 'pg_extension'::regclass
+           AND d.refobjid = (SELECT oid FROM pg_extension WHERE extname = 'steep_repl')
+           AND d.deptype = 'e'),
+        'steep_repl'
+    );
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.schema_name() IS 'Schema containing the steep_repl extension objects';
+
+-- Self-describing API catalog for tooling and docs generation
+-- One row per function or procedure in the extension schema, overloads included.
+CREATE FUNCTION steep_repl.api()
+RETURNS TABLE (
+    function_name TEXT,
+    arguments TEXT,
+    return_type TEXT,
+    comment TEXT
+) AS $$
+    SELECT p.proname::TEXT,
+           pg_get_function_arguments(p.oid),
+           COALESCE(pg_get_function_result(p.oid), 'procedure'),
+           obj_description(p.oid, 'pg_proc')
+    FROM pg_proc p
+    WHERE p.pronamespace = steep_repl.schema_name()::regnamespace
+      AND p.prokind IN ('f', 'p')
+    ORDER BY p.proname, pg_get_function_arguments(p.oid);
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.api() IS 'Functions in the steep_repl schema with their arguments, return types and comments';
 "#,
     name = "create_schema",
     bootstrap,
@@ -27,4 +70,44 @@ mod tests {
         );
         assert_eq!(result, Ok(Some(true)), "steep_repl schema should exist");
     }
+
+    #[pg_test]
+    fn test_schema_name_returns_active_schema() {
+        let result = Spi::get_one::<String>("SELECT steep_repl.schema_name()");
+        assert_eq!(result, Ok(Some("steep_repl".to_string())));
+
+        let owns_objects = Spi::get_one::<bool>(
+            "SELECT 'steep_repl.nodes'::regclass::oid IN (
+                SELECT oid FROM pg_class WHERE relnamespace = steep_repl.schema_name()::regnamespace
+            )"
+        );
+        assert_eq!(owns_objects, Ok(Some(true)), "schema_name() should hold the extension tables");
+    }
+
+    #[pg_test]
+    fn test_api_lists_functions_with_arguments() {
+        let args = Spi::get_one::<String>(
+            "SELECT arguments FROM steep_repl.api() WHERE function_name = 'rename_node'",
+        );
+        assert_eq!(args, Ok(Some("p_old text, p_new text".to_string())));
+
+        let read_file = Spi::get_one::<String>(
+            "SELECT arguments FROM steep_repl.api() WHERE function_name = 'read_snapshot_file'",
+        );
+        assert!(
+            matches!(read_file, Ok(Some(ref a)) if a.contains("p_offset bigint DEFAULT 0")),
+            "defaults should be included: {read_file:?}"
+        );
+
+        let local_node = Spi::get_one::<bool>(
+            "SELECT return_type = 'text' AND comment IS NOT NULL
+             FROM steep_repl.api() WHERE function_name = 'local_node'",
+        );
+        assert_eq!(local_node, Ok(Some(true)));
+
+        let listed_self = Spi::get_one::<bool>(
+            "SELECT EXISTS(SELECT 1 FROM steep_repl.api() WHERE function_name = 'api')",
+        );
+        assert_eq!(listed_self, Ok(Some(true)));
+    }
 }
@@ -0,0 +1,392 @@
+//! Schema DDL extraction for steep_repl extension.
+//!
+//! This module provides dump_schema_ddl(), a catalog-based equivalent of
+//! `pg_dump --schema-only` used to carry table definitions in snapshots.
+
+use pgrx::prelude::*;
+
+extension_sql!(
+    r#"
+-- Ordered DDL for the tables, indexes, constraints and sequences in the given schemas
+-- Replaying the statements in ordinal order into an empty database recreates
+-- the definitions, starting with CREATE SCHEMA IF NOT EXISTS for each schema. Sequence-backed column defaults are emitted after the
+-- sequences they reference. Partitioned tables are emitted with PARTITION BY
+-- and their partitions as PARTITION OF, parents first. search_path is pinned
+-- so every name is qualified.
+CREATE FUNCTION steep_repl.dump_schema_ddl(p_schemas TEXT[])
+RETURNS TABLE (
+    ordinal INTEGER,
+    object_type TEXT,  -- schema, table, index, constraint, sequence, default
+    object_name TEXT,
+    ddl TEXT
+) AS $$
+    WITH tables AS (
+        SELECT c.oid, n.nspname, c.relname, c.relkind
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind IN ('r', 'p')
+          AND NOT c.relispartition
+          AND n.nspname = ANY(p_schemas)
+    ),
+    cols AS (
+        SELECT t.oid, a.attnum, a.attname, d.expr,
+               a.attgenerated, a.attidentity, a.attnotnull,
+               format_type(a.atttypid, a.atttypmod) AS typ,
+               d.expr LIKE 'nextval(%' AS seq_default
+        FROM tables t
+        JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum > 0 AND NOT a.attisdropped
+        LEFT JOIN LATERAL (
+            SELECT pg_get_expr(ad.adbin, ad.adrelid) AS expr
+            FROM pg_attrdef ad
+            WHERE ad.adrelid = t.oid AND ad.adnum = a.attnum
+        ) d ON true
+    ),
+    stmts AS (
+        -- 0. Schemas, so the statements replay into an empty database
+        SELECT 0 AS stage, 'schema' AS object_type, format('%I', n.nspname) AS object_name,
+               format('CREATE SCHEMA IF NOT EXISTS %I;', n.nspname) AS ddl, 0 AS sub
+        FROM pg_namespace n
+        WHERE n.nspname = ANY(p_schemas)
+
+        UNION ALL
+
+        -- 1. Tables; sequence-backed defaults are attached in step 4
+        SELECT 1, 'table',
+               format('%I.%I', t.nspname, t.relname),
+               format(E'CREATE TABLE %I.%I (\n%s\n)', t.nspname, t.relname,
+                   COALESCE((
+                       SELECT string_agg(
+                           format('    %I %s', c.attname, c.typ)
+                           || CASE
+                                  WHEN c.attgenerated = 's' THEN format(' GENERATED ALWAYS AS (%s) STORED', c.expr)
+                                  WHEN c.attgenerated = 'v' THEN format(' GENERATED ALWAYS AS (%s) VIRTUAL', c.expr)
+                                  WHEN c.attidentity = 'a' THEN ' GENERATED ALWAYS AS IDENTITY'
+                                  WHEN c.attidentity = 'd' THEN ' GENERATED BY DEFAULT AS IDENTITY'
+                                  WHEN c.expr IS NOT NULL AND NOT c.seq_default THEN ' DEFAULT ' || c.expr
+                                  ELSE ''
+                              END
+                           || CASE WHEN c.attnotnull AND c.attidentity = '' THEN ' NOT NULL' ELSE '' END,
+                           E',\n' ORDER BY c.attnum)
+                       FROM cols c WHERE c.oid = t.oid
+                   ), ''))
+               || CASE WHEN t.relkind = 'p' THEN ' PARTITION BY ' || pg_get_partkeydef(t.oid) ELSE '' END
+               || ';',
+               0
+        FROM tables t
+
+        UNION ALL
+
+        -- Partitions inherit their columns; deeper levels follow their parents
+        SELECT 1, 'table', format('%I.%I', n.nspname, c.relname),
+               format('CREATE TABLE %I.%I PARTITION OF %I.%I %s', n.nspname, c.relname,
+                      pn.nspname, p.relname, pg_get_expr(c.relpartbound, c.oid))
+               || CASE WHEN c.relkind = 'p' THEN ' PARTITION BY ' || pg_get_partkeydef(c.oid) ELSE '' END
+               || ';',
+               (SELECT count(*)::INTEGER FROM pg_partition_ancestors(c.oid))
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_inherits i ON i.inhrelid = c.oid
+        JOIN pg_class p ON p.oid = i.inhparent
+        JOIN pg_namespace pn ON pn.oid = p.relnamespace
+        WHERE c.relispartition
+          AND c.relkind IN ('r', 'p')
+          AND n.nspname = ANY(p_schemas)
+
+        UNION ALL
+
+        -- 2. Indexes not backing a constraint; indexes on a partitioned table
+        --    are created without ONLY so they cascade to the partitions
+        SELECT 2, 'index', format('%I.%I', t.nspname, i.relname),
+               replace(pg_get_indexdef(x.indexrelid), ' ON ONLY ', ' ON ') || ';', 0
+        FROM tables t
+        JOIN pg_index x ON x.indrelid = t.oid
+        JOIN pg_class i ON i.oid = x.indexrelid
+        WHERE NOT EXISTS (SELECT 1 FROM pg_constraint k WHERE k.conindid = x.indexrelid AND k.conrelid = t.oid)
+
+        UNION ALL
+
+        -- 3. Constraints, foreign keys last so referenced keys exist
+        SELECT 3, 'constraint', format('%I.%I.%I', t.nspname, t.relname, k.conname),
+               format('ALTER TABLE %I.%I ADD CONSTRAINT %I %s;', t.nspname, t.relname, k.conname,
+                      pg_get_constraintdef(k.oid)),
+               CASE k.contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'x' THEN 2 WHEN 'c' THEN 3 ELSE 4 END
+        FROM tables t
+        JOIN pg_constraint k ON k.conrelid = t.oid
+        WHERE k.contype IN ('p', 'u', 'x', 'c', 'f')
+
+        UNION ALL
+
+        -- 4. Sequences (identity sequences come with their columns), their
+        --    ownership, then the column defaults that use them
+        SELECT 4, 'sequence', format('%I.%I', n.nspname, c.relname),
+               format('CREATE SEQUENCE %I.%I AS %s INCREMENT BY %s MINVALUE %s MAXVALUE %s START WITH %s CACHE %s%s;',
+                      n.nspname, c.relname, format_type(s.seqtypid, NULL), s.seqincrement,
+                      s.seqmin, s.seqmax, s.seqstart, s.seqcache,
+                      CASE WHEN s.seqcycle THEN ' CYCLE' ELSE '' END)
+               || COALESCE((
+                      SELECT format(E'\nALTER SEQUENCE %I.%I OWNED BY %I.%I.%I;',
+                                    n.nspname, c.relname, tn.nspname, tc.relname, ta.attname)
+                      FROM pg_depend dep
+                      JOIN pg_class tc ON tc.oid = dep.refobjid
+                      JOIN pg_namespace tn ON tn.oid = tc.relnamespace
+                      JOIN pg_attribute ta ON ta.attrelid = tc.oid AND ta.attnum = dep.refobjsubid
+                      WHERE dep.classid = 'pg_class'::regclass AND dep.objid = c.oid
+                        AND dep.refclassid = 'pg_class'::regclass AND dep.deptype = 'a'
+                  ), ''),
+               0
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_sequence s ON s.seqrelid = c.oid
+        WHERE c.relkind = 'S'
+          AND n.nspname = ANY(p_schemas)
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend dep
+              WHERE dep.classid = 'pg_class'::regclass AND dep.objid = c.oid AND dep.deptype = 'i'
+          )
+
+        UNION ALL
+
+        SELECT 4, 'default', format('%I.%I.%I', t.nspname, t.relname, c.attname),
+               format('ALTER TABLE %I.%I ALTER COLUMN %I SET DEFAULT %s;', t.nspname, t.relname, c.attname, c.expr),
+               1
+        FROM tables t
+        JOIN cols c ON c.oid = t.oid
+        WHERE c.seq_default AND c.attgenerated = ''
+    )
+    SELECT (row_number() OVER (ORDER BY stage, sub, object_name))::INTEGER,
+           object_type, object_name, ddl
+    FROM stmts
+    ORDER BY 1;
+$$ LANGUAGE sql STABLE
+SET search_path = pg_catalog;
+
+COMMENT ON FUNCTION steep_repl.dump_schema_ddl(TEXT[]) IS
+    'Ordered CREATE/ALTER statements (schemas, tables, indexes, constraints, sequences) for the given schemas';
+"#,
+    name = "create_schema_ddl_functions",
+    requires = ["create_schema"],
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    fn create_source_schema() {
+        Spi::run("DROP SCHEMA IF EXISTS ddl_src CASCADE").expect("drop");
+        Spi::run("DROP SCHEMA IF EXISTS ddl_dst CASCADE").expect("drop");
+        Spi::run(
+            "CREATE SCHEMA ddl_src;
+             CREATE TABLE ddl_src.parent (
+                 id SERIAL PRIMARY KEY,
+                 code TEXT NOT NULL UNIQUE,
+                 created_at TIMESTAMPTZ DEFAULT now()
+             );
+             CREATE TABLE ddl_src.child (
+                 id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                 parent_id INT NOT NULL REFERENCES ddl_src.parent(id),
+                 qty NUMERIC(10,2) CHECK (qty >= 0),
+                 label VARCHAR(40),
+                 label_upper TEXT GENERATED ALWAYS AS (upper(label)) STORED
+             );
+             CREATE INDEX child_label_idx ON ddl_src.child (lower(label));
+             CREATE SEQUENCE ddl_src.standalone_seq START 100;",
+        )
+        .expect("source schema");
+    }
+
+    fn cleanup() {
+        Spi::run("DROP SCHEMA IF EXISTS ddl_src CASCADE").expect("cleanup");
+        Spi::run("DROP SCHEMA IF EXISTS ddl_dst CASCADE").expect("cleanup");
+    }
+
+    #[pg_test]
+    fn test_dump_schema_ddl_order() {
+        create_source_schema();
+
+        // Stages never go backwards: schema < table < index < constraint < sequence/default
+        let ordered = Spi::get_one::<bool>(
+            "SELECT bool_and(stage >= prev)
+             FROM (
+                 SELECT stage, lag(stage, 1, 0) OVER (ORDER BY ordinal) AS prev
+                 FROM (
+                     SELECT ordinal, CASE object_type
+                         WHEN 'schema' THEN 0 WHEN 'table' THEN 1 WHEN 'index' THEN 2 WHEN 'constraint' THEN 3 ELSE 4 END AS stage
+                     FROM steep_repl.dump_schema_ddl(ARRAY['ddl_src'])
+                 ) s
+             ) o",
+        );
+        assert_eq!(ordered, Ok(Some(true)), "statements should be staged in order");
+
+        // FKs come after the keys they reference
+        let fk_last = Spi::get_one::<bool>(
+            "SELECT max(ordinal) FILTER (WHERE ddl LIKE '%PRIMARY KEY%')
+                  < min(ordinal) FILTER (WHERE ddl LIKE '%FOREIGN KEY%')
+             FROM steep_repl.dump_schema_ddl(ARRAY['ddl_src'])",
+        );
+        assert_eq!(fk_last, Ok(Some(true)));
+
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_dump_schema_ddl_round_trip() {
+        create_source_schema();
+
+        Spi::run(
+            "CREATE SCHEMA ddl_dst;
+             DO $$
+             DECLARE r RECORD;
+             BEGIN
+                 FOR r IN SELECT ddl FROM steep_repl.dump_schema_ddl(ARRAY['ddl_src']) ORDER BY ordinal LOOP
+                     EXECUTE replace(r.ddl, 'ddl_src.', 'ddl_dst.');
+                 END LOOP;
+             END $$;",
+        )
+        .expect("replaying the DDL should succeed");
+
+        // Column definitions match exactly (defaults differ only by schema)
+        let diff = Spi::get_one::<i64>(
+            "WITH src AS (
+                 SELECT table_name, column_name, data_type, is_nullable,
+                        replace(column_default, 'ddl_src.', 'ddl_dst.') AS column_default,
+                        is_identity, identity_generation, is_generated, generation_expression, ordinal_position
+                 FROM information_schema.columns WHERE table_schema = 'ddl_src'
+             ), dst AS (
+                 SELECT table_name, column_name, data_type, is_nullable, column_default,
+                        is_identity, identity_generation, is_generated, generation_expression, ordinal_position
+                 FROM information_schema.columns WHERE table_schema = 'ddl_dst'
+             )
+             SELECT count(*) FROM ((SELECT * FROM src EXCEPT SELECT * FROM dst)
+                                   UNION ALL (SELECT * FROM dst EXCEPT SELECT * FROM src)) d",
+        );
+        assert_eq!(diff, Ok(Some(0)), "replayed tables should match the source definitions");
+
+        let indexes = Spi::get_one::<bool>(
+            "SELECT (SELECT count(*) FROM pg_indexes WHERE schemaname = 'ddl_src')
+                  = (SELECT count(*) FROM pg_indexes WHERE schemaname = 'ddl_dst')",
+        );
+        assert_eq!(indexes, Ok(Some(true)), "indexes and key constraints should be recreated");
+
+        let next_id = Spi::get_one::<i32>("INSERT INTO ddl_dst.parent (code) VALUES ('x') RETURNING id");
+        assert_eq!(next_id, Ok(Some(1)), "serial default should be wired to the new sequence");
+
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_dump_schema_ddl_replays_into_empty_database() {
+        cleanup();
+        Spi::run(
+            "CREATE SCHEMA ddl_src;
+             CREATE TABLE ddl_src.readings (
+                 id INT PRIMARY KEY,
+                 celsius INT NOT NULL,
+                 fahrenheit INT GENERATED ALWAYS AS (celsius * 9 / 5 + 32) VIRTUAL
+             );
+             CREATE TABLE ddl_src.measurements (
+                 id INT NOT NULL,
+                 taken_on DATE NOT NULL
+             ) PARTITION BY RANGE (taken_on);
+             CREATE TABLE ddl_src.measurements_2024 PARTITION OF ddl_src.measurements
+                 FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');",
+        )
+        .expect("source schema");
+
+        // Replay with the schema itself gone, as on a fresh target
+        Spi::run(
+            "CREATE TEMP TABLE ddl_saved AS
+                 SELECT ordinal, ddl FROM steep_repl.dump_schema_ddl(ARRAY['ddl_src']);
+             DROP SCHEMA ddl_src CASCADE;
+             DO $$
+             DECLARE r RECORD;
+             BEGIN
+                 FOR r IN SELECT ddl FROM ddl_saved ORDER BY ordinal LOOP
+                     EXECUTE r.ddl;
+                 END LOOP;
+             END $$;
+             DROP TABLE ddl_saved;",
+        )
+        .expect("replaying the DDL without the schema should succeed");
+
+        let generated = Spi::get_one::<String>(
+            "SELECT attgenerated::TEXT FROM pg_attribute
+             WHERE attrelid = 'ddl_src.readings'::regclass AND attname = 'fahrenheit'",
+        );
+        assert_eq!(generated, Ok(Some("v".to_string())), "virtual generated column should stay virtual");
+
+        let fahrenheit = Spi::get_one::<i32>(
+            "INSERT INTO ddl_src.readings (id, celsius) VALUES (1, 100) RETURNING fahrenheit",
+        );
+        assert_eq!(fahrenheit, Ok(Some(212)), "virtual expression should be preserved");
+
+        let root = Spi::get_one::<String>(
+            "SELECT relkind::TEXT || ':' || pg_get_partkeydef(oid) FROM pg_class WHERE oid = 'ddl_src.measurements'::regclass",
+        );
+        assert_eq!(root, Ok(Some("p:RANGE (taken_on)".to_string())), "partitioned root should keep its PARTITION BY");
+
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_dump_schema_ddl_partitions() {
+        cleanup();
+        Spi::run(
+            "CREATE SCHEMA ddl_src;
+             CREATE TABLE ddl_src.events (
+                 id BIGINT NOT NULL,
+                 region TEXT NOT NULL,
+                 happened_on DATE NOT NULL,
+                 PRIMARY KEY (id, region, happened_on)
+             ) PARTITION BY RANGE (happened_on);
+             CREATE TABLE ddl_src.events_2024_01 PARTITION OF ddl_src.events
+                 FOR VALUES FROM ('2024-01-01') TO ('2024-02-01');
+             CREATE TABLE ddl_src.events_2024_02 PARTITION OF ddl_src.events
+                 FOR VALUES FROM ('2024-02-01') TO ('2024-03-01') PARTITION BY LIST (region);
+             CREATE TABLE ddl_src.events_2024_02_eu PARTITION OF ddl_src.events_2024_02 FOR VALUES IN ('eu');
+             CREATE TABLE ddl_src.events_2024_02_rest PARTITION OF ddl_src.events_2024_02 DEFAULT;
+             CREATE INDEX events_region_idx ON ddl_src.events (region);",
+        )
+        .expect("source schema");
+
+        Spi::run(
+            "CREATE SCHEMA ddl_dst;
+             DO $$
+             DECLARE r RECORD;
+             BEGIN
+                 FOR r IN SELECT ddl FROM steep_repl.dump_schema_ddl(ARRAY['ddl_src']) ORDER BY ordinal LOOP
+                     EXECUTE replace(r.ddl, 'ddl_src.', 'ddl_dst.');
+                 END LOOP;
+             END $$;",
+        )
+        .expect("replaying partitioned DDL should succeed");
+
+        let tree = Spi::get_one::<String>(
+            "SELECT string_agg(c.relname || ':' || COALESCE(pg_get_expr(c.relpartbound, c.oid), 'root'), ',' ORDER BY c.relname)
+             FROM pg_partition_tree('ddl_dst.events') t
+             JOIN pg_class c ON c.oid = t.relid",
+        );
+        assert_eq!(
+            tree,
+            Ok(Some(
+                "events:root,\
+                 events_2024_01:FOR VALUES FROM ('2024-01-01') TO ('2024-02-01'),\
+                 events_2024_02:FOR VALUES FROM ('2024-02-01') TO ('2024-03-01'),\
+                 events_2024_02_eu:FOR VALUES IN ('eu'),\
+                 events_2024_02_rest:DEFAULT"
+                    .to_string()
+            )),
+            "partition hierarchy should be recreated"
+        );
+
+        // Rows route through the recreated hierarchy and the parent index cascades
+        Spi::run("INSERT INTO ddl_dst.events VALUES (1, 'eu', '2024-02-10'), (2, 'us', '2024-01-05')")
+            .expect("insert should route to partitions");
+        let indexed = Spi::get_one::<i64>(
+            "SELECT count(*) FROM pg_indexes WHERE schemaname = 'ddl_dst' AND indexdef LIKE '%(region)%'",
+        );
+        assert_eq!(indexed, Ok(Some(5)), "region index should exist on every table in the tree");
+
+        cleanup();
+    }
+}
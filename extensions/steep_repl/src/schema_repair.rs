@@ -0,0 +1,315 @@
+//! Schema self-repair for steep_repl extension.
+//!
+//! This module records the tables, columns, constraints, indexes, and
+//! functions present when the extension is installed, and provides
+//! ensure_schema() to recreate what is missing after manual edits.
+
+use pgrx::prelude::*;
+
+extension_sql!(
+    r#"
+-- Expected steep_repl objects, frozen at install time
+-- Runs after all other extension SQL so every object is captured. Definitions
+-- are rendered with an empty search_path so every name in them is qualified.
+-- Constraint-backed indexes are excluded; they belong to their constraints.
+DO $do$
+DECLARE
+    v_search_path TEXT := current_setting('search_path');
+BEGIN
+    PERFORM set_config('search_path', 'pg_catalog', true);
+
+    EXECUTE format(
+        $f$
+        CREATE FUNCTION steep_repl.expected_objects()
+        RETURNS TABLE (object_type TEXT, object_name TEXT, definition TEXT) AS %L
+        LANGUAGE sql IMMUTABLE
+        $f$,
+        'SELECT * FROM (VALUES ' || (
+            SELECT string_agg(format('(%L, %L, %L)', object_type, object_name, definition), ', '
+                              ORDER BY object_type, object_name)
+            FROM (
+                -- Owned sequences are recreated with their table
+                SELECT 'table' AS object_type, c.relname::TEXT AS object_name,
+                       COALESCE((
+                           SELECT string_agg(format('CREATE SEQUENCE IF NOT EXISTS %s; ', d.objid::regclass), '')
+                           FROM pg_depend d
+                           WHERE d.refobjid = c.oid AND d.classid = 'pg_class'::regclass
+                             AND d.deptype = 'a' AND d.objid IN (SELECT oid FROM pg_class WHERE relkind = 'S')
+                       ), '')
+                       || format('CREATE TABLE %s (%s); ', c.oid::regclass, (
+                           SELECT string_agg(
+                               format('%I %s', a.attname, format_type(a.atttypid, a.atttypmod))
+                               || CASE
+                                   WHEN a.attgenerated = 's' THEN ' GENERATED ALWAYS AS (' || pg_get_expr(ad.adbin, ad.adrelid) || ') STORED'
+                                   WHEN a.attidentity = 'a' THEN ' GENERATED ALWAYS AS IDENTITY'
+                                   WHEN a.attidentity = 'd' THEN ' GENERATED BY DEFAULT AS IDENTITY'
+                                   WHEN ad.adbin IS NOT NULL THEN ' DEFAULT ' || pg_get_expr(ad.adbin, ad.adrelid)
+                                   ELSE ''
+                               END
+                               || CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END,
+                               ', ' ORDER BY a.attnum)
+                           FROM pg_attribute a
+                           LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+                           WHERE a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+                       ))
+                       || COALESCE((
+                           SELECT string_agg(format('ALTER SEQUENCE %s OWNED BY %s.%I; ', d.objid::regclass, c.oid::regclass, a.attname), '')
+                           FROM pg_depend d
+                           JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = d.refobjsubid
+                           WHERE d.refobjid = c.oid AND d.classid = 'pg_class'::regclass
+                             AND d.deptype = 'a' AND d.objid IN (SELECT oid FROM pg_class WHERE relkind = 'S')
+                       ), '') AS definition
+                FROM pg_class c
+                WHERE c.relnamespace = steep_repl.schema_name()::regnamespace AND c.relkind = 'r'
+                UNION ALL
+                SELECT 'column', c.relname || '.' || a.attname,
+                       format('ALTER TABLE %s ADD COLUMN %I %s', c.oid::regclass, a.attname, format_type(a.atttypid, a.atttypmod))
+                       || CASE
+                           WHEN a.attgenerated = 's' THEN ' GENERATED ALWAYS AS (' || pg_get_expr(ad.adbin, ad.adrelid) || ') STORED'
+                           WHEN a.attidentity = 'a' THEN ' GENERATED ALWAYS AS IDENTITY'
+                           WHEN a.attidentity = 'd' THEN ' GENERATED BY DEFAULT AS IDENTITY'
+                           WHEN ad.adbin IS NOT NULL THEN ' DEFAULT ' || pg_get_expr(ad.adbin, ad.adrelid)
+                           ELSE ''
+                       END
+                       || CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END
+                FROM pg_class c
+                JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+                LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+                WHERE c.relnamespace = steep_repl.schema_name()::regnamespace AND c.relkind = 'r'
+                UNION ALL
+                SELECT 'constraint', c.relname || '.' || con.conname,
+                       format('ALTER TABLE %s ADD CONSTRAINT %I %s', c.oid::regclass, con.conname, pg_get_constraintdef(con.oid))
+                FROM pg_constraint con
+                JOIN pg_class c ON c.oid = con.conrelid
+                WHERE con.connamespace = steep_repl.schema_name()::regnamespace AND con.contype IN ('p', 'u', 'c', 'f', 'x')
+                UNION ALL
+                SELECT 'index', i.indexname::TEXT, i.indexdef
+                FROM pg_indexes i
+                WHERE i.schemaname = steep_repl.schema_name()
+                  AND NOT EXISTS (
+                      SELECT 1 FROM pg_constraint con
+                      WHERE con.conindid = format('%I.%I', i.schemaname, i.indexname)::regclass
+                  )
+                UNION ALL
+                SELECT 'function', p.oid::regprocedure::TEXT, NULL
+                FROM pg_proc p
+                WHERE p.pronamespace = steep_repl.schema_name()::regnamespace
+            ) objs
+        ) || ') AS v(object_type, object_name, definition)'
+    );
+
+    PERFORM set_config('search_path', v_search_path, true);
+END;
+$do$;
+
+COMMENT ON FUNCTION steep_repl.expected_objects() IS 'Tables, columns, constraints, indexes, and functions the installed extension version expects';
+
+-- Verify expected objects and recreate what can be recreated safely
+-- Objects are checked in dependency order: tables, then columns, then
+-- constraints (foreign keys last), then indexes. A missing table is recreated
+-- empty and re-attached to the extension. A missing column or constraint is
+-- re-added only if the existing rows allow it (e.g. a NOT NULL column without
+-- a default cannot be added to a non-empty table). Anything that fails, and
+-- any missing function, is reported for a reinstall instead, with the reason
+-- in detail.
+CREATE FUNCTION steep_repl.ensure_schema()
+RETURNS TABLE (
+    object_type TEXT,
+    object_name TEXT,
+    action TEXT,  -- recreated, missing
+    detail TEXT   -- why a missing object could not be recreated
+) AS $$
+DECLARE
+    v_type TEXT;
+    v_obj RECORD;
+    v_table TEXT;
+    v_sequence TEXT;
+BEGIN
+    FOREACH v_type IN ARRAY ARRAY['table', 'column', 'constraint', 'index', 'function'] LOOP
+        FOR v_obj IN
+            SELECT e.object_type, e.object_name, e.definition
+            FROM steep_repl.expected_objects() e
+            WHERE e.object_type = v_type
+              AND CASE e.object_type
+                WHEN 'table' THEN to_regclass(format('%I.%I', steep_repl.schema_name(), e.object_name)) IS NULL
+                WHEN 'column' THEN NOT EXISTS (
+                    SELECT 1 FROM pg_attribute a
+                    WHERE a.attrelid = to_regclass(format('%I.%I', steep_repl.schema_name(), split_part(e.object_name, '.', 1)))
+                      AND a.attname = split_part(e.object_name, '.', 2)
+                      AND NOT a.attisdropped
+                )
+                WHEN 'constraint' THEN NOT EXISTS (
+                    SELECT 1 FROM pg_constraint con
+                    WHERE con.conrelid = to_regclass(format('%I.%I', steep_repl.schema_name(), split_part(e.object_name, '.', 1)))
+                      AND con.conname = split_part(e.object_name, '.', 2)
+                )
+                WHEN 'index' THEN to_regclass(format('%I.%I', steep_repl.schema_name(), e.object_name)) IS NULL
+                WHEN 'function' THEN to_regprocedure(e.object_name) IS NULL
+            END
+            ORDER BY e.definition LIKE '%FOREIGN KEY%', e.object_name
+        LOOP
+            object_type := v_obj.object_type;
+            object_name := v_obj.object_name;
+            action := 'missing';
+            detail := NULL;
+
+            IF v_obj.definition IS NULL THEN
+                detail := 'no repair definition; reinstall the extension';
+            ELSE
+                BEGIN
+                    EXECUTE v_obj.definition;
+
+                    IF v_obj.object_type = 'table'
+                       AND EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'steep_repl') THEN
+                        v_table := format('%I.%I', steep_repl.schema_name(), v_obj.object_name);
+                        EXECUTE format('ALTER EXTENSION steep_repl ADD TABLE %s', v_table);
+                        FOR v_sequence IN
+                            SELECT d.objid::regclass::TEXT
+                            FROM pg_depend d
+                            WHERE d.refobjid = v_table::regclass AND d.deptype = 'a'
+                              AND d.classid = 'pg_class'::regclass
+                        LOOP
+                            EXECUTE format('ALTER EXTENSION steep_repl ADD SEQUENCE %s', v_sequence);
+                        END LOOP;
+                    END IF;
+
+                    action := 'recreated';
+                EXCEPTION WHEN OTHERS THEN
+                    detail := SQLERRM;
+                END;
+            END IF;
+
+            RETURN NEXT;
+        END LOOP;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.ensure_schema() IS 'Recreate missing steep_repl tables, columns, constraints, and indexes and report what could not be repaired';
+"#,
+    name = "create_schema_repair_functions",
+    finalize,
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn test_ensure_schema_clean_install() {
+        let result = Spi::get_one::<i64>("SELECT count(*) FROM steep_repl.ensure_schema()");
+        assert_eq!(result, Ok(Some(0)), "a fresh install should need no repairs");
+    }
+
+    #[pg_test]
+    fn test_ensure_schema_recreates_dropped_index() {
+        Spi::run("DROP INDEX steep_repl.idx_snapshots_tags").expect("drop index");
+
+        let repaired = Spi::get_one::<String>(
+            "SELECT object_type || ':' || object_name || ':' || action || coalesce(':' || detail, '')
+             FROM steep_repl.ensure_schema()"
+        );
+        assert_eq!(
+            repaired,
+            Ok(Some("index:idx_snapshots_tags:recreated".to_string())),
+            "a successful repair has no detail"
+        );
+
+        let exists = Spi::get_one::<bool>(
+            "SELECT to_regclass('steep_repl.idx_snapshots_tags') IS NOT NULL"
+        );
+        assert_eq!(exists, Ok(Some(true)), "index should exist again");
+    }
+
+    #[pg_test]
+    fn test_ensure_schema_restores_columns_and_constraints() {
+        Spi::run("ALTER TABLE steep_repl.snapshots DROP COLUMN tags").expect("drop column");
+        Spi::run("ALTER TABLE steep_repl.nodes DROP CONSTRAINT nodes_port_check").expect("drop check");
+        Spi::run("ALTER TABLE steep_repl.nodes DROP CONSTRAINT nodes_pkey CASCADE").expect("drop primary key");
+
+        let repaired = Spi::get_one::<String>(
+            "SELECT string_agg(object_type || ':' || object_name || ':' || action, ',' ORDER BY object_type, object_name)
+             FROM steep_repl.ensure_schema()
+             WHERE object_name IN ('snapshots.tags', 'nodes.nodes_port_check', 'nodes.nodes_pkey',
+                                   'snapshots.snapshots_source_node_id_fkey', 'idx_snapshots_tags')"
+        );
+        assert_eq!(
+            repaired,
+            Ok(Some(
+                "column:snapshots.tags:recreated,\
+                 constraint:nodes.nodes_pkey:recreated,\
+                 constraint:nodes.nodes_port_check:recreated,\
+                 constraint:snapshots.snapshots_source_node_id_fkey:recreated,\
+                 index:idx_snapshots_tags:recreated"
+                    .to_string()
+            )),
+            "dropped foreign keys should come back after the primary key they reference"
+        );
+
+        let remaining = Spi::get_one::<i64>("SELECT count(*) FROM steep_repl.ensure_schema()");
+        assert_eq!(remaining, Ok(Some(0)), "a second pass should find nothing to repair");
+    }
+
+    #[pg_test]
+    fn test_ensure_schema_reports_unaddable_column() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('repair-node', 'Repair', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run("ALTER TABLE steep_repl.nodes DROP COLUMN node_name").expect("drop column");
+
+        // NOT NULL without a default cannot be added back to a non-empty table
+        let result = Spi::get_one::<String>(
+            "SELECT action || ':' || detail FROM steep_repl.ensure_schema() WHERE object_name = 'nodes.node_name'"
+        );
+        assert_eq!(
+            result,
+            Ok(Some("missing:column \"node_name\" of relation \"nodes\" contains null values".to_string())),
+            "the failed repair should report the error that stopped it"
+        );
+    }
+
+    #[pg_test]
+    fn test_ensure_schema_recreates_dropped_table() {
+        Spi::run("ALTER EXTENSION steep_repl DROP TABLE steep_repl.audit_log").expect("detach table");
+        Spi::run("ALTER EXTENSION steep_repl DROP SEQUENCE steep_repl.audit_log_id_seq").expect("detach sequence");
+        Spi::run("DROP TABLE steep_repl.audit_log").expect("drop table");
+
+        let repaired = Spi::get_one::<String>(
+            "SELECT action FROM steep_repl.ensure_schema() WHERE object_type = 'table' AND object_name = 'audit_log'"
+        );
+        assert_eq!(repaired, Ok(Some("recreated".to_string())));
+
+        let member = Spi::get_one::<bool>(
+            "SELECT EXISTS(
+                SELECT 1 FROM pg_depend d
+                JOIN pg_extension e ON e.oid = d.refobjid
+                WHERE e.extname = 'steep_repl' AND d.deptype = 'e'
+                  AND d.objid = 'steep_repl.audit_log'::regclass
+            )"
+        );
+        assert_eq!(member, Ok(Some(true)), "recreated table should belong to the extension again");
+
+        let id = Spi::get_one::<i64>(
+            "INSERT INTO steep_repl.audit_log (action, actor) VALUES ('test.repair', 'test') RETURNING id"
+        );
+        assert!(matches!(id, Ok(Some(_))), "recreated table should accept rows with its serial id");
+    }
+
+    #[pg_test]
+    fn test_ensure_schema_reports_missing_function() {
+        Spi::run("ALTER FUNCTION steep_repl.snapshot_layout_version() RENAME TO snapshot_layout_version_moved")
+            .expect("rename function");
+
+        let result = Spi::get_one::<String>(
+            "SELECT action || ':' || detail FROM steep_repl.ensure_schema()
+             WHERE object_name = 'steep_repl.snapshot_layout_version()'"
+        );
+        assert_eq!(result, Ok(Some("missing:no repair definition; reinstall the extension".to_string())));
+
+        // Cleanup
+        Spi::run("ALTER FUNCTION steep_repl.snapshot_layout_version_moved() RENAME TO snapshot_layout_version")
+            .expect("restore function");
+    }
+}
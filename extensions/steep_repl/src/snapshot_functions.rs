@@ -0,0 +1,1433 @@
+//! Snapshot SQL functions for steep_repl extension.
+//!
+//! This module provides SQL functions that inspect generated snapshots on
+//! disk. A snapshot directory (snapshots.storage_path) uses layout version 1:
+//!
+//! - manifest.json: snapshot metadata and one entry per table
+//! - data/<schema>.<table>.csv[.gz|.lz4|.zst]: CSV (with header) per table
+//!
+//! Manifest table entries reference their data file by relative path, and
+//! their checksums use the manifest's checksum_algo (sha256 or xxhash64).
+
+use pgrx::prelude::*;
+
+extension_sql!(
+    r#"
+-- Snapshot directory layout version (see module docs)
+CREATE FUNCTION steep_repl.snapshot_layout_version()
+RETURNS INTEGER AS $$
+    SELECT 1;
+$$ LANGUAGE sql IMMUTABLE;
+
+COMMENT ON FUNCTION steep_repl.snapshot_layout_version() IS 'Version of the on-disk snapshot layout (manifest.json + data/<schema>.<table>.csv[.ext])';
+
+-- Relative path of a table's data file within a snapshot directory
+CREATE FUNCTION steep_repl.snapshot_data_file(
+    p_schema TEXT,
+    p_table TEXT,
+    p_compression TEXT DEFAULT 'none'
+)
+RETURNS TEXT AS $$
+DECLARE
+    v_ext TEXT;
+BEGIN
+    v_ext := CASE p_compression
+        WHEN 'none' THEN ''
+        WHEN 'gzip' THEN '.gz'
+        WHEN 'lz4' THEN '.lz4'
+        WHEN 'zstd' THEN '.zst'
+    END;
+
+    IF v_ext IS NULL THEN
+        RAISE EXCEPTION 'Unknown compression type: %', p_compression;
+    END IF;
+
+    RETURN 'data/' || p_schema || '.' || p_table || '.csv' || v_ext;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+COMMENT ON FUNCTION steep_repl.snapshot_data_file(TEXT, TEXT, TEXT) IS 'Relative data file path for a table in the snapshot layout';
+
+-- Generate a unique snapshot ID: snap_<timestamp>_<node prefix>_<sequence>
+-- The timestamp keeps IDs readable; the sequence guarantees uniqueness.
+CREATE FUNCTION steep_repl.next_snapshot_id(p_node_id TEXT)
+RETURNS TEXT AS $$
+    SELECT format('snap_%s_%s_%s',
+        to_char(clock_timestamp(), 'YYYYMMDD_HH24MISS'),
+        left(p_node_id, 8),
+        nextval('steep_repl.snapshot_id_seq'));
+$$ LANGUAGE sql VOLATILE;
+
+COMMENT ON FUNCTION steep_repl.next_snapshot_id(TEXT) IS 'Generate a unique, monotonic snapshot ID for a source node';
+
+-- Read the manifest.json of a snapshot
+-- Returns NULL if the snapshot has no storage path or the manifest is missing
+CREATE FUNCTION steep_repl.snapshot_manifest(p_snapshot_id TEXT)
+RETURNS JSONB AS $$
+    SELECT pg_read_file(rtrim(s.storage_path, '/') || '/manifest.json', true)::jsonb
+    FROM steep_repl.snapshots s
+    WHERE s.snapshot_id = p_snapshot_id
+      AND s.storage_path IS NOT NULL;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.snapshot_manifest(TEXT) IS 'Read the manifest.json of a snapshot from its storage path';
+
+-- Read a chunk of a file inside a snapshot's storage directory
+-- Lets clients without server filesystem access page through snapshot files.
+-- Paths are relative to the storage root; absolute paths and '..' segments
+-- are rejected so reads cannot escape it.
+CREATE FUNCTION steep_repl.read_snapshot_file(
+    p_snapshot_id TEXT,
+    p_relative_path TEXT,
+    p_offset BIGINT DEFAULT 0,
+    p_length INTEGER DEFAULT 1048576
+)
+RETURNS BYTEA AS $$
+DECLARE
+    v_storage_path TEXT;
+BEGIN
+    SELECT s.storage_path INTO v_storage_path
+    FROM steep_repl.snapshots s
+    WHERE s.snapshot_id = p_snapshot_id;
+
+    IF v_storage_path IS NULL THEN
+        RAISE EXCEPTION 'Snapshot % has no storage path', p_snapshot_id;
+    END IF;
+
+    IF p_relative_path IS NULL OR p_relative_path = ''
+       OR p_relative_path LIKE '/%'
+       OR '..' = ANY(string_to_array(p_relative_path, '/')) THEN
+        RAISE EXCEPTION 'Invalid snapshot file path: %', p_relative_path
+            USING HINT = 'Paths must be relative to the snapshot directory and must not contain ..';
+    END IF;
+
+    IF p_offset < 0 OR p_length < 0 THEN
+        RAISE EXCEPTION 'Offset and length must not be negative';
+    END IF;
+
+    RETURN pg_read_binary_file(rtrim(v_storage_path, '/') || '/' || p_relative_path, p_offset, p_length);
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.read_snapshot_file(TEXT, TEXT, BIGINT, INTEGER) IS 'Read a byte range of a file within a snapshot directory';
+
+-- Checksum of a snapshot data file as recorded in the manifest, e.g. "sha256:<hex>"
+-- Matches the daemon's FileChecksum for each supported checksum_algo
+CREATE FUNCTION steep_repl.snapshot_checksum(p_data BYTEA, p_algo TEXT DEFAULT 'sha256')
+RETURNS TEXT AS $$
+BEGIN
+    CASE p_algo
+        WHEN 'sha256' THEN
+            RETURN 'sha256:' || encode(sha256(p_data), 'hex');
+        WHEN 'xxhash64' THEN
+            RETURN 'xxhash64:' || steep_repl.xxhash64(p_data);
+        ELSE
+            RAISE EXCEPTION 'unsupported checksum algorithm: %', p_algo
+                USING HINT = 'Valid algorithms are sha256, xxhash64';
+    END CASE;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE STRICT;
+
+COMMENT ON FUNCTION steep_repl.snapshot_checksum(BYTEA, TEXT) IS 'Algorithm-prefixed checksum of snapshot file contents (sha256 or xxhash64)';
+
+-- Verify that the files referenced by complete snapshots still exist
+-- Checks manifest.json and every data file it lists; optionally re-hashes
+-- data files against the manifest checksums. Unrecoverable snapshots can be
+-- flipped to the files_missing status.
+CREATE FUNCTION steep_repl.verify_snapshot_storage(
+    p_rehash BOOLEAN DEFAULT false,
+    p_mark_missing BOOLEAN DEFAULT false
+)
+RETURNS TABLE (
+    snapshot_id TEXT,
+    status TEXT,  -- ok, missing, corrupt, skipped
+    missing_files TEXT[],
+    corrupt_files TEXT[]
+) AS $function$
+DECLARE
+    v_snap RECORD;
+    v_root TEXT;
+    v_manifest JSONB;
+    v_table JSONB;
+    v_path TEXT;
+    v_algo TEXT;
+    v_missing TEXT[];
+    v_corrupt TEXT[];
+BEGIN
+    FOR v_snap IN
+        SELECT s.snapshot_id, s.storage_path
+        FROM steep_repl.snapshots s
+        WHERE s.status = 'complete'
+        ORDER BY s.created_at, s.snapshot_id
+    LOOP
+        snapshot_id := v_snap.snapshot_id;
+        v_missing := '{}';
+        v_corrupt := '{}';
+
+        -- Only local filesystem paths can be checked (e.g., not s3://)
+        IF v_snap.storage_path IS NULL OR v_snap.storage_path LIKE '%://%' THEN
+            status := 'skipped';
+            missing_files := v_missing;
+            corrupt_files := v_corrupt;
+            RETURN NEXT;
+            CONTINUE;
+        END IF;
+
+        v_root := rtrim(v_snap.storage_path, '/');
+        v_manifest := pg_read_file(v_root || '/manifest.json', true)::jsonb;
+
+        IF v_manifest IS NULL THEN
+            v_missing := ARRAY['manifest.json'];
+        ELSE
+            -- Manifests written before checksum_algo was recorded use sha256
+            v_algo := COALESCE(NULLIF(v_manifest->>'checksum_algo', ''), 'sha256');
+
+            FOR v_table IN SELECT * FROM jsonb_array_elements(COALESCE(v_manifest->'tables', '[]'::jsonb))
+            LOOP
+                v_path := v_root || '/' || (v_table->>'file');
+
+                IF pg_stat_file(v_path, true) IS NULL THEN
+                    v_missing := v_missing || (v_table->>'file');
+                ELSIF p_rehash AND v_table->>'checksum' IS NOT NULL
+                      AND steep_repl.snapshot_checksum(pg_read_binary_file(v_path), v_algo) <> v_table->>'checksum' THEN
+                    v_corrupt := v_corrupt || (v_table->>'file');
+                END IF;
+            END LOOP;
+        END IF;
+
+        status := CASE
+            WHEN cardinality(v_missing) > 0 THEN 'missing'
+            WHEN cardinality(v_corrupt) > 0 THEN 'corrupt'
+            ELSE 'ok'
+        END;
+        missing_files := v_missing;
+        corrupt_files := v_corrupt;
+
+        IF p_mark_missing AND status IN ('missing', 'corrupt') THEN
+            UPDATE steep_repl.snapshots s
+            SET status = 'files_missing',
+                error_message = format('Storage verification failed: %s missing, %s corrupt',
+                    cardinality(v_missing), cardinality(v_corrupt))
+            WHERE s.snapshot_id = v_snap.snapshot_id;
+        END IF;
+
+        RETURN NEXT;
+    END LOOP;
+END;
+$function$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.verify_snapshot_storage(BOOLEAN, BOOLEAN) IS 'Verify that files referenced by complete snapshots still exist, optionally re-hashing and marking unrecoverable snapshots as files_missing';
+
+-- Compare the manifests of two snapshots table by table
+-- Returns row/byte counts from each side and whether the data file checksums match
+CREATE FUNCTION steep_repl.diff_snapshots(p_a TEXT, p_b TEXT)
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT,
+    status TEXT,  -- MATCH, MISMATCH, A_ONLY, B_ONLY
+    a_row_count BIGINT,
+    b_row_count BIGINT,
+    a_size_bytes BIGINT,
+    b_size_bytes BIGINT,
+    checksum_match BOOLEAN
+) AS $function$
+DECLARE
+    v_a JSONB;
+    v_b JSONB;
+BEGIN
+    v_a := steep_repl.snapshot_manifest(p_a);
+    IF v_a IS NULL THEN
+        RAISE EXCEPTION 'Manifest for snapshot % not found', p_a;
+    END IF;
+
+    v_b := steep_repl.snapshot_manifest(p_b);
+    IF v_b IS NULL THEN
+        RAISE EXCEPTION 'Manifest for snapshot % not found', p_b;
+    END IF;
+
+    RETURN QUERY
+    WITH a AS (
+        SELECT t->>'schema' AS s, t->>'name' AS n,
+               (t->>'row_count')::BIGINT AS row_count,
+               (t->>'size_bytes')::BIGINT AS size_bytes,
+               t->>'checksum' AS checksum
+        FROM jsonb_array_elements(COALESCE(v_a->'tables', '[]'::jsonb)) t
+    ),
+    b AS (
+        SELECT t->>'schema' AS s, t->>'name' AS n,
+               (t->>'row_count')::BIGINT AS row_count,
+               (t->>'size_bytes')::BIGINT AS size_bytes,
+               t->>'checksum' AS checksum
+        FROM jsonb_array_elements(COALESCE(v_b->'tables', '[]'::jsonb)) t
+    )
+    SELECT
+        COALESCE(a.s, b.s),
+        COALESCE(a.n, b.n),
+        CASE
+            WHEN a.n IS NULL THEN 'B_ONLY'
+            WHEN b.n IS NULL THEN 'A_ONLY'
+            WHEN a.checksum IS NOT DISTINCT FROM b.checksum
+                 AND a.row_count IS NOT DISTINCT FROM b.row_count THEN 'MATCH'
+            ELSE 'MISMATCH'
+        END,
+        a.row_count,
+        b.row_count,
+        a.size_bytes,
+        b.size_bytes,
+        CASE WHEN a.n IS NULL OR b.n IS NULL THEN NULL
+             ELSE a.checksum IS NOT DISTINCT FROM b.checksum
+        END
+    FROM a
+    FULL OUTER JOIN b ON a.s = b.s AND a.n = b.n
+    ORDER BY 1, 2;
+END;
+$function$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.diff_snapshots(TEXT, TEXT) IS 'Compare two snapshot manifests per table: presence, row/byte counts, and checksum equality';
+
+-- Load a snapshot data file into a new temp table shaped like a local table
+-- Data files carry every column except generated ones (COPY TO's default), so
+-- the temp table and COPY use that explicit column list, which is returned.
+-- Compressed files are streamed through the matching decompressor.
+CREATE FUNCTION steep_repl.load_snapshot_data(p_schema TEXT, p_table TEXT, p_temp TEXT, p_path TEXT)
+RETURNS TEXT[] AS $function$
+DECLARE
+    v_cols TEXT[];
+    v_list TEXT;
+    v_source TEXT;
+BEGIN
+    SELECT array_agg(a.attname::TEXT ORDER BY a.attnum)
+    INTO v_cols
+    FROM pg_attribute a
+    WHERE a.attrelid = format('%I.%I', p_schema, p_table)::regclass
+      AND a.attnum > 0
+      AND NOT a.attisdropped
+      AND a.attgenerated = '';
+
+    SELECT string_agg(quote_ident(c), ', ') INTO v_list FROM unnest(v_cols) c;
+
+    v_source := CASE
+        WHEN p_path LIKE '%.gz' THEN format('PROGRAM %L', 'gzip -dc ' || quote_literal(p_path))
+        WHEN p_path LIKE '%.lz4' THEN format('PROGRAM %L', 'lz4 -dc ' || quote_literal(p_path))
+        WHEN p_path LIKE '%.zst' THEN format('PROGRAM %L', 'zstd -dc ' || quote_literal(p_path))
+        ELSE quote_literal(p_path)
+    END;
+
+    EXECUTE format('CREATE TEMP TABLE %I AS SELECT %s FROM %I.%I WITH NO DATA', p_temp, v_list, p_schema, p_table);
+    EXECUTE format('COPY %I (%s) FROM %s WITH (FORMAT csv, HEADER true)', p_temp, v_list, v_source);
+
+    RETURN v_cols;
+END;
+$function$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.load_snapshot_data(TEXT, TEXT, TEXT, TEXT) IS 'Load a snapshot data file into a temp table using the local table''s non-generated columns; returns the column list';
+
+-- Re-check an applied snapshot against its manifest without re-applying
+-- Row counts are read locally when p_target_node is this node (steep_repl.local_node),
+-- otherwise via dblink. With p_check_data, each uncompressed data file is loaded
+-- into a temp table and compared by row hashes (local target only).
+CREATE FUNCTION steep_repl.verify_applied_snapshot(
+    p_snapshot_id TEXT,
+    p_target_node TEXT,
+    p_check_data BOOLEAN DEFAULT false
+)
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT,
+    status TEXT,  -- PASS, FAIL, MISSING
+    expected_rows BIGINT,
+    actual_rows BIGINT,
+    data_match BOOLEAN
+) AS $function$
+DECLARE
+    v_manifest JSONB;
+    v_base TEXT;
+    v_local BOOLEAN;
+    v_host TEXT;
+    v_port INTEGER;
+    v_conn_str TEXT;
+    v_table JSONB;
+    v_schema TEXT;
+    v_name TEXT;
+    v_file TEXT;
+    v_expected BIGINT;
+    v_actual BIGINT;
+    v_match BOOLEAN;
+    v_cols TEXT[];
+    v_counts JSONB := '{}'::jsonb;
+BEGIN
+    v_manifest := steep_repl.snapshot_manifest(p_snapshot_id);
+    IF v_manifest IS NULL THEN
+        RAISE EXCEPTION 'Manifest for snapshot % not found', p_snapshot_id;
+    END IF;
+
+    SELECT rtrim(storage_path, '/') INTO v_base
+    FROM steep_repl.snapshots
+    WHERE snapshot_id = p_snapshot_id;
+
+    SELECT host, port INTO v_host, v_port
+    FROM steep_repl.nodes
+    WHERE node_id = p_target_node;
+
+    IF v_host IS NULL THEN
+        RAISE EXCEPTION 'Target node % not found in steep_repl.nodes', p_target_node;
+    END IF;
+
+    v_local := p_target_node IS NOT DISTINCT FROM steep_repl.local_node(true);
+
+    IF NOT v_local THEN
+        IF p_check_data THEN
+            RAISE EXCEPTION 'Data verification requires running on the target node %', p_target_node;
+        END IF;
+
+        -- Ensure dblink extension is available
+        CREATE EXTENSION IF NOT EXISTS dblink;
+
+        v_conn_str := format(
+            'host=%s port=%s dbname=%s user=%s sslmode=disable',
+            v_host,
+            COALESCE(v_port, 5432),
+            current_database(),
+            current_user
+        );
+
+        -- Count all manifest tables in one round trip; absent tables are omitted
+        SELECT COALESCE(jsonb_object_agg(r.qualified, r.row_count), '{}'::jsonb)
+        INTO v_counts
+        FROM dblink(
+            v_conn_str,
+            format(
+                $$
+                    SELECT s || '.' || n,
+                           (xpath('/row/c/text()',
+                               query_to_xml(format('SELECT count(*) AS c FROM %%I.%%I', s, n), false, true, '')
+                           ))[1]::text::bigint
+                    FROM unnest(%L::text[], %L::text[]) AS t(s, n)
+                    WHERE to_regclass(format('%%I.%%I', s, n)) IS NOT NULL
+                $$,
+                (SELECT array_agg(t->>'schema') FROM jsonb_array_elements(v_manifest->'tables') t),
+                (SELECT array_agg(t->>'name') FROM jsonb_array_elements(v_manifest->'tables') t)
+            )
+        ) AS r(qualified TEXT, row_count BIGINT);
+    END IF;
+
+    FOR v_table IN SELECT * FROM jsonb_array_elements(COALESCE(v_manifest->'tables', '[]'::jsonb))
+    LOOP
+        v_schema := v_table->>'schema';
+        v_name := v_table->>'name';
+        v_file := v_table->>'file';
+        v_expected := (v_table->>'row_count')::BIGINT;
+        v_actual := NULL;
+        v_match := NULL;
+
+        IF v_local THEN
+            IF to_regclass(format('%I.%I', v_schema, v_name)) IS NOT NULL THEN
+                EXECUTE format('SELECT count(*) FROM %I.%I', v_schema, v_name) INTO v_actual;
+            END IF;
+        ELSE
+            v_actual := (v_counts->>(v_schema || '.' || v_name))::BIGINT;
+        END IF;
+
+        IF p_check_data AND v_actual IS NOT NULL AND v_file LIKE '%.csv' THEN
+            -- Generated columns are not in the file, so hash only the loaded columns
+            v_cols := steep_repl.load_snapshot_data(v_schema, v_name, '_snapshot_verify', v_base || '/' || v_file);
+            EXECUTE format(
+                'SELECT (SELECT count(*) FROM _snapshot_verify) = count(*)
+                        AND (SELECT sum(steep_repl.row_hash(v)::numeric) FROM _snapshot_verify v)
+                            IS NOT DISTINCT FROM sum(steep_repl.row_hash(t)::numeric)
+                 FROM (SELECT %s FROM %I.%I) t',
+                (SELECT string_agg(quote_ident(c), ', ') FROM unnest(v_cols) c),
+                v_schema, v_name
+            ) INTO v_match;
+            DROP TABLE _snapshot_verify;
+        END IF;
+
+        table_schema := v_schema;
+        table_name := v_name;
+        expected_rows := v_expected;
+        actual_rows := v_actual;
+        data_match := v_match;
+        status := CASE
+            WHEN v_actual IS NULL THEN 'MISSING'
+            WHEN v_actual IS DISTINCT FROM v_expected OR v_match IS FALSE THEN 'FAIL'
+            ELSE 'PASS'
+        END;
+        RETURN NEXT;
+    END LOOP;
+END;
+$function$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.verify_applied_snapshot(TEXT, TEXT, BOOLEAN) IS 'Verify an applied snapshot on a target node against its manifest: per-table row counts and optional data comparison';
+
+-- Check that foreign keys declared on this database hold within a snapshot
+-- Data files are loaded into temp tables shaped like the local tables (see
+-- steep_repl.load_snapshot_data). Only constraints whose parent and child
+-- tables are both in the snapshot are checked. Returns one row per violated
+-- constraint.
+CREATE FUNCTION steep_repl.validate_snapshot_integrity(p_snapshot_id TEXT)
+RETURNS TABLE (
+    constraint_name TEXT,
+    child_table TEXT,
+    parent_table TEXT,
+    orphaned_rows BIGINT,
+    sample_key JSONB
+) AS $function$
+DECLARE
+    v_manifest JSONB;
+    v_base TEXT;
+    v_table JSONB;
+    v_idx INTEGER := 0;
+    v_temp TEXT;
+    v_fk RECORD;
+    v_count BIGINT;
+    v_sample JSONB;
+BEGIN
+    v_manifest := steep_repl.snapshot_manifest(p_snapshot_id);
+    IF v_manifest IS NULL THEN
+        RAISE EXCEPTION 'Manifest for snapshot % not found', p_snapshot_id;
+    END IF;
+
+    SELECT rtrim(storage_path, '/') INTO v_base
+    FROM steep_repl.snapshots
+    WHERE snapshot_id = p_snapshot_id;
+
+    CREATE TEMP TABLE _snapshot_integrity_tables (
+        rel REGCLASS,
+        temp_name TEXT
+    ) ON COMMIT DROP;
+
+    -- Load each snapshot table that exists locally into a temp copy
+    FOR v_table IN SELECT * FROM jsonb_array_elements(COALESCE(v_manifest->'tables', '[]'::jsonb))
+    LOOP
+        CONTINUE WHEN to_regclass(format('%I.%I', v_table->>'schema', v_table->>'name')) IS NULL;
+
+        v_idx := v_idx + 1;
+        v_temp := '_snapshot_integrity_' || v_idx;
+        PERFORM steep_repl.load_snapshot_data(v_table->>'schema', v_table->>'name', v_temp,
+            v_base || '/' || (v_table->>'file'));
+
+        INSERT INTO _snapshot_integrity_tables
+        VALUES (format('%I.%I', v_table->>'schema', v_table->>'name')::regclass, v_temp);
+    END LOOP;
+
+    -- Check every FK between two loaded tables (MATCH SIMPLE semantics)
+    FOR v_fk IN
+        SELECT
+            con.conname::TEXT AS conname,
+            con.conrelid::regclass::TEXT AS child,
+            con.confrelid::regclass::TEXT AS parent,
+            ct.temp_name AS child_temp,
+            pt.temp_name AS parent_temp,
+            (SELECT array_agg(a.attname::TEXT ORDER BY k.ord)
+             FROM unnest(con.conkey) WITH ORDINALITY k(attnum, ord)
+             JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum) AS child_cols,
+            (SELECT array_agg(a.attname::TEXT ORDER BY k.ord)
+             FROM unnest(con.confkey) WITH ORDINALITY k(attnum, ord)
+             JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum) AS parent_cols
+        FROM pg_constraint con
+        JOIN _snapshot_integrity_tables ct ON ct.rel = con.conrelid
+        JOIN _snapshot_integrity_tables pt ON pt.rel = con.confrelid
+        WHERE con.contype = 'f'
+        ORDER BY 2, 1
+    LOOP
+        EXECUTE format(
+            'SELECT count(*), (array_agg(jsonb_build_object(%s)))[1]
+             FROM %I c
+             WHERE %s AND NOT EXISTS (SELECT 1 FROM %I p WHERE %s)',
+            (SELECT string_agg(format('%L, c.%I', col, col), ', ') FROM unnest(v_fk.child_cols) col),
+            v_fk.child_temp,
+            (SELECT string_agg(format('c.%I IS NOT NULL', col), ' AND ') FROM unnest(v_fk.child_cols) col),
+            v_fk.parent_temp,
+            (SELECT string_agg(format('p.%I = c.%I', pc, cc), ' AND ')
+             FROM unnest(v_fk.parent_cols, v_fk.child_cols) AS m(pc, cc))
+        ) INTO v_count, v_sample;
+
+        IF v_count > 0 THEN
+            constraint_name := v_fk.conname;
+            child_table := v_fk.child;
+            parent_table := v_fk.parent;
+            orphaned_rows := v_count;
+            sample_key := v_sample;
+            RETURN NEXT;
+        END IF;
+    END LOOP;
+
+    -- Drop temp copies now so the function can be called again in the same transaction
+    FOR v_temp IN SELECT temp_name FROM _snapshot_integrity_tables
+    LOOP
+        EXECUTE format('DROP TABLE %I', v_temp);
+    END LOOP;
+    DROP TABLE _snapshot_integrity_tables;
+END;
+$function$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.validate_snapshot_integrity(TEXT) IS 'Check that declared foreign keys hold within a snapshot''s data files; returns violated constraints';
+
+-- Keep the most recent p_keep complete/applied snapshots carrying p_tag and
+-- mark the rest expired. Pinned snapshots are neither expired nor counted
+-- toward p_keep. Returns the number of snapshots expired.
+CREATE FUNCTION steep_repl.expire_snapshots_by_tag(p_tag TEXT, p_keep INTEGER)
+RETURNS INTEGER AS $$
+DECLARE
+    v_expired INTEGER;
+BEGIN
+    IF p_keep IS NULL OR p_keep < 0 THEN
+        RAISE EXCEPTION 'p_keep must be a non-negative integer, got %', p_keep;
+    END IF;
+
+    UPDATE steep_repl.snapshots s
+    SET status = 'expired',
+        expires_at = COALESCE(s.expires_at, now())
+    WHERE s.snapshot_id IN (
+        SELECT snapshot_id
+        FROM steep_repl.snapshots
+        WHERE p_tag = ANY(tags)
+          AND status IN ('complete', 'applied')
+          AND NOT pinned
+        ORDER BY created_at DESC, snapshot_id DESC
+        OFFSET p_keep
+    );
+
+    GET DIAGNOSTICS v_expired = ROW_COUNT;
+    RETURN v_expired;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.expire_snapshots_by_tag(TEXT, INTEGER) IS 'Expire all but the most recent N complete snapshots with the given tag. Returns count of expired snapshots.';
+
+-- Pin or unpin a snapshot. Pinning also clears expires_at so time-based
+-- cleanup leaves the snapshot alone; unpinning does not restore it.
+CREATE FUNCTION steep_repl.pin_snapshot(p_snapshot_id TEXT, p_pinned BOOLEAN DEFAULT true)
+RETURNS VOID AS $$
+BEGIN
+    UPDATE steep_repl.snapshots
+    SET pinned = p_pinned,
+        expires_at = CASE WHEN p_pinned THEN NULL ELSE expires_at END
+    WHERE snapshot_id = p_snapshot_id;
+
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Snapshot % not found in steep_repl.snapshots', p_snapshot_id;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.pin_snapshot(TEXT, BOOLEAN) IS 'Pin (or unpin) a snapshot so retention never expires it';
+
+-- Newest complete full snapshot of a node, to use as an incremental base
+-- Incremental snapshots (base_snapshot_id set) and snapshots without an LSN
+-- never qualify. Both columns are NULL when there is no candidate.
+CREATE FUNCTION steep_repl.best_incremental_base(
+    p_source_node TEXT,
+    OUT snapshot_id TEXT,
+    OUT lsn TEXT
+)
+RETURNS RECORD AS $$
+    SELECT s.snapshot_id, s.lsn
+    FROM steep_repl.snapshots s
+    WHERE s.source_node_id = p_source_node
+      AND s.status = 'complete'
+      AND s.base_snapshot_id IS NULL
+      AND s.lsn IS NOT NULL
+    ORDER BY COALESCE(s.completed_at, s.created_at) DESC, s.snapshot_id DESC
+    LIMIT 1;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.best_incremental_base(TEXT) IS 'Most recent complete full snapshot (and its LSN) of a source node, or NULL if none qualifies';
+
+-- Tables of a snapshot that took longest to export, from the manifest's
+-- per-table duration_ms. Entries from manifests without timings are skipped.
+CREATE FUNCTION steep_repl.slowest_tables(p_snapshot_id TEXT, p_limit INTEGER DEFAULT 10)
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT,
+    duration_ms BIGINT,
+    row_count BIGINT,
+    size_bytes BIGINT
+) AS $function$
+DECLARE
+    v_manifest JSONB;
+BEGIN
+    v_manifest := steep_repl.snapshot_manifest(p_snapshot_id);
+    IF v_manifest IS NULL THEN
+        RAISE EXCEPTION 'Manifest for snapshot % not found', p_snapshot_id;
+    END IF;
+
+    RETURN QUERY
+    SELECT t->>'schema',
+           t->>'name',
+           (t->>'duration_ms')::BIGINT,
+           (t->>'row_count')::BIGINT,
+           (t->>'size_bytes')::BIGINT
+    FROM jsonb_array_elements(COALESCE(v_manifest->'tables', '[]'::jsonb)) t
+    WHERE t ? 'duration_ms'
+    ORDER BY (t->>'duration_ms')::BIGINT DESC, t->>'schema', t->>'name'
+    LIMIT p_limit;
+END;
+$function$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.slowest_tables(TEXT, INTEGER) IS 'Tables of a snapshot ordered by export duration, slowest first';
+"#,
+    name = "create_snapshot_functions",
+    requires = ["create_snapshots_table", "create_merge_functions"],
+);
+
+/// XXH64 (seed 0) of `data` as 16 lowercase hex digits, matching the
+/// daemon's xxhash64 snapshot checksums.
+#[pg_extern(immutable, strict, parallel_safe, schema = "steep_repl", requires = ["create_schema"])]
+fn xxhash64(data: &[u8]) -> String {
+    format!("{:016x}", xxh64(data))
+}
+
+const XX_PRIME1: u64 = 11400714785074694791;
+const XX_PRIME2: u64 = 14029467366897019727;
+const XX_PRIME3: u64 = 1609587929392839161;
+const XX_PRIME4: u64 = 9650029242287828579;
+const XX_PRIME5: u64 = 2870177450012600261;
+
+fn xx_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(XX_PRIME2))
+        .rotate_left(31)
+        .wrapping_mul(XX_PRIME1)
+}
+
+fn xx_merge_round(acc: u64, val: u64) -> u64 {
+    (acc ^ xx_round(0, val))
+        .wrapping_mul(XX_PRIME1)
+        .wrapping_add(XX_PRIME4)
+}
+
+fn read_u64(b: &[u8]) -> u64 {
+    u64::from_le_bytes(b[..8].try_into().expect("8 bytes"))
+}
+
+fn read_u32(b: &[u8]) -> u32 {
+    u32::from_le_bytes(b[..4].try_into().expect("4 bytes"))
+}
+
+fn xxh64(data: &[u8]) -> u64 {
+    let mut rest = data;
+    let mut h = if data.len() >= 32 {
+        let mut v1 = XX_PRIME1.wrapping_add(XX_PRIME2);
+        let mut v2 = XX_PRIME2;
+        let mut v3 = 0u64;
+        let mut v4 = 0u64.wrapping_sub(XX_PRIME1);
+        while rest.len() >= 32 {
+            v1 = xx_round(v1, read_u64(&rest[0..]));
+            v2 = xx_round(v2, read_u64(&rest[8..]));
+            v3 = xx_round(v3, read_u64(&rest[16..]));
+            v4 = xx_round(v4, read_u64(&rest[24..]));
+            rest = &rest[32..];
+        }
+        let mut h = v1
+            .rotate_left(1)
+            .wrapping_add(v2.rotate_left(7))
+            .wrapping_add(v3.rotate_left(12))
+            .wrapping_add(v4.rotate_left(18));
+        h = xx_merge_round(h, v1);
+        h = xx_merge_round(h, v2);
+        h = xx_merge_round(h, v3);
+        xx_merge_round(h, v4)
+    } else {
+        XX_PRIME5
+    };
+    h = h.wrapping_add(data.len() as u64);
+
+    while rest.len() >= 8 {
+        h ^= xx_round(0, read_u64(rest));
+        h = h.rotate_left(27).wrapping_mul(XX_PRIME1).wrapping_add(XX_PRIME4);
+        rest = &rest[8..];
+    }
+    if rest.len() >= 4 {
+        h ^= (read_u32(rest) as u64).wrapping_mul(XX_PRIME1);
+        h = h.rotate_left(23).wrapping_mul(XX_PRIME2).wrapping_add(XX_PRIME3);
+        rest = &rest[4..];
+    }
+    for &c in rest {
+        h ^= (c as u64).wrapping_mul(XX_PRIME5);
+        h = h.rotate_left(11).wrapping_mul(XX_PRIME1);
+    }
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(XX_PRIME2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(XX_PRIME3);
+    h ^ (h >> 32)
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use crate::test_support::ScratchDir;
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn test_snapshot_manifest_function_exists() {
+        let result = Spi::get_one::<bool>(
+            "SELECT EXISTS(
+                SELECT 1 FROM pg_proc p
+                JOIN pg_namespace n ON p.pronamespace = n.oid
+                WHERE n.nspname = 'steep_repl' AND p.proname = 'snapshot_manifest'
+            )"
+        );
+        assert_eq!(result, Ok(Some(true)), "snapshot_manifest function should exist");
+    }
+
+    #[pg_test]
+    fn test_verify_snapshot_storage_function_exists() {
+        let result = Spi::get_one::<bool>(
+            "SELECT EXISTS(
+                SELECT 1 FROM pg_proc p
+                JOIN pg_namespace n ON p.pronamespace = n.oid
+                WHERE n.nspname = 'steep_repl' AND p.proname = 'verify_snapshot_storage'
+            )"
+        );
+        assert_eq!(result, Ok(Some(true)), "verify_snapshot_storage function should exist");
+    }
+
+    #[pg_test]
+    fn test_verify_snapshot_storage_reports_missing_file() {
+        // Write a snapshot directory with one of its two data files present
+        let dir = ScratchDir::new("verify_storage");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_verify_01", "tables": [{"schema": "public", "name": "t1", "file": "data/public.t1.csv"}, {"schema": "public", "name": "t2", "file": "data/public.t2.csv"}]}"#,
+        );
+        dir.write("data/public.t1.csv", "1\n");
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('verify-node', 'Verify', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_verify_01', 'verify-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let status = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.verify_snapshot_storage() WHERE snapshot_id = 'snap_verify_01'"
+        );
+        assert_eq!(status, Ok(Some("missing".to_string())));
+
+        let missing = Spi::get_one::<bool>(
+            "SELECT missing_files = ARRAY['data/public.t2.csv']
+             FROM steep_repl.verify_snapshot_storage() WHERE snapshot_id = 'snap_verify_01'"
+        );
+        assert_eq!(missing, Ok(Some(true)), "only the removed file should be reported");
+
+        // Marking flips the snapshot out of complete
+        Spi::run("SELECT * FROM steep_repl.verify_snapshot_storage(false, true)")
+            .expect("verify with mark should succeed");
+        let snap_status = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.snapshots WHERE snapshot_id = 'snap_verify_01'"
+        );
+        assert_eq!(snap_status, Ok(Some("files_missing".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_verify_01'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'verify-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_xxhash64_known_vectors() {
+        let cases = vec![
+            ("", "ef46db3751d8e999"),
+            ("a", "d24ec4f1a98c6e5b"),
+            ("abc", "44bc2cf5ad770999"),
+            ("Nobody inspects the spammish repetition", "fbcea83c8a378bf1"),
+        ];
+
+        for (input, expected) in cases {
+            let result = Spi::get_one::<String>(&format!(
+                "SELECT steep_repl.xxhash64(convert_to('{}', 'UTF8'))",
+                input
+            ));
+            assert_eq!(result, Ok(Some(expected.to_string())), "input {:?}", input);
+        }
+    }
+
+    #[pg_test]
+    fn test_verify_snapshot_storage_rehashes_xxhash64() {
+        // Data file containing exactly "abc" (XXH64 44bc2cf5ad770999)
+        let dir = ScratchDir::new("verify_xx");
+        dir.write("data/public.t1.csv", "abc");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_verify_xx", "checksum_algo": "xxhash64", "tables": [{"schema": "public", "name": "t1", "file": "data/public.t1.csv", "checksum": "xxhash64:44bc2cf5ad770999"}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('verify-xx-node', 'Verify', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_verify_xx', 'verify-xx-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let status = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.verify_snapshot_storage(true) WHERE snapshot_id = 'snap_verify_xx'"
+        );
+        assert_eq!(status, Ok(Some("ok".to_string())), "xxhash64 checksum should verify");
+
+        // Changing the file must be detected with the same algorithm
+        dir.write("data/public.t1.csv", "abd");
+        let status = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.verify_snapshot_storage(true) WHERE snapshot_id = 'snap_verify_xx'"
+        );
+        assert_eq!(status, Ok(Some("corrupt".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_verify_xx'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'verify-xx-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_diff_snapshots_reports_changed_table() {
+        let base = ScratchDir::new("diff_base");
+        base.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_diff_base", "tables": [{"schema": "public", "name": "orders", "row_count": 100, "size_bytes": 4096, "checksum": "sha256:aaa", "file": "data/public.orders.csv"}, {"schema": "public", "name": "users", "row_count": 10, "size_bytes": 512, "checksum": "sha256:bbb", "file": "data/public.users.csv"}]}"#,
+        );
+        let incr = ScratchDir::new("diff_incr");
+        incr.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_diff_incr", "tables": [{"schema": "public", "name": "orders", "row_count": 150, "size_bytes": 6144, "checksum": "sha256:ccc", "file": "data/public.orders.csv"}, {"schema": "public", "name": "users", "row_count": 10, "size_bytes": 512, "checksum": "sha256:bbb", "file": "data/public.users.csv"}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('diff-node', 'Diff', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_diff_base', 'diff-node', '{}', 'complete'),
+                    ('snap_diff_incr', 'diff-node', '{}', 'complete')",
+            base.path(),
+            incr.path()
+        )).expect("snapshot insert should succeed");
+
+        let orders = Spi::get_one::<String>(
+            "SELECT status || ':' || a_row_count || ':' || b_row_count
+             FROM steep_repl.diff_snapshots('snap_diff_base', 'snap_diff_incr')
+             WHERE table_name = 'orders'"
+        );
+        assert_eq!(orders, Ok(Some("MISMATCH:100:150".to_string())));
+
+        let users = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.diff_snapshots('snap_diff_base', 'snap_diff_incr')
+             WHERE table_name = 'users'"
+        );
+        assert_eq!(users, Ok(Some("MATCH".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id IN ('snap_diff_base', 'snap_diff_incr')")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'diff-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_snapshot_layout_version() {
+        let result = Spi::get_one::<i32>("SELECT steep_repl.snapshot_layout_version()");
+        assert_eq!(result, Ok(Some(1)), "layout version should be 1");
+    }
+
+    #[pg_test]
+    fn test_next_snapshot_id_unique() {
+        let result = Spi::get_one::<bool>(
+            "SELECT count(DISTINCT id) = 1000 AND bool_and(id LIKE 'snap\\_%\\_node-abc\\_%')
+             FROM (SELECT steep_repl.next_snapshot_id('node-abcdef') AS id FROM generate_series(1, 1000)) ids"
+        );
+        assert_eq!(result, Ok(Some(true)), "1000 rapid snapshot IDs should be distinct");
+    }
+
+    #[pg_test]
+    fn test_snapshot_data_file_names() {
+        let cases = vec![
+            ("none", "data/public.orders.csv"),
+            ("gzip", "data/public.orders.csv.gz"),
+            ("lz4", "data/public.orders.csv.lz4"),
+            ("zstd", "data/public.orders.csv.zst"),
+        ];
+
+        for (compression, expected) in cases {
+            let result = Spi::get_one::<String>(&format!(
+                "SELECT steep_repl.snapshot_data_file('public', 'orders', '{}')",
+                compression
+            ));
+            assert_eq!(result, Ok(Some(expected.to_string())), "compression {}", compression);
+        }
+    }
+
+    #[pg_test]
+    fn test_snapshot_layout_round_trip() {
+        // Write a snapshot using the documented layout and verify it is consumed intact
+        let dir = ScratchDir::new("layout");
+        let data_file = Spi::get_one::<String>("SELECT steep_repl.snapshot_data_file('public', 'orders')")
+            .expect("data file name")
+            .expect("data file name should not be NULL");
+        Spi::run(&format!(
+            "DO $$
+            BEGIN
+                EXECUTE format('COPY (SELECT 1 AS id) TO %L WITH (FORMAT csv, HEADER true)', '{data_path}');
+                EXECUTE format('COPY (SELECT %L) TO %L',
+                    jsonb_build_object('snapshot_id', 'snap_layout', 'tables', jsonb_build_array(
+                        jsonb_build_object('schema', 'public', 'name', 'orders',
+                            'file', steep_repl.snapshot_data_file('public', 'orders'),
+                            'checksum', 'sha256:' || encode(sha256(pg_read_binary_file('{data_path}')), 'hex'))
+                    ))::text,
+                    '{manifest_path}');
+            END $$",
+            data_path = dir.file(&data_file),
+            manifest_path = dir.file("manifest.json")
+        )).expect("write snapshot files");
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('layout-node', 'Layout', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_layout', 'layout-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let file = Spi::get_one::<String>(
+            "SELECT steep_repl.snapshot_manifest('snap_layout')->'tables'->0->>'file'"
+        );
+        assert_eq!(file, Ok(Some("data/public.orders.csv".to_string())));
+
+        let status = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.verify_snapshot_storage(true) WHERE snapshot_id = 'snap_layout'"
+        );
+        assert_eq!(status, Ok(Some("ok".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_layout'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'layout-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_read_snapshot_file_chunk() {
+        let dir = ScratchDir::new("read");
+        dir.write("data/public.t.csv", "id,name\n");
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('read-node', 'Read', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_read', 'read-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let chunk = Spi::get_one::<String>(
+            "SELECT convert_from(steep_repl.read_snapshot_file('snap_read', 'data/public.t.csv', 3, 4), 'UTF8')"
+        );
+        assert_eq!(chunk, Ok(Some("name".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_read'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'read-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test(error = "Invalid snapshot file path: data/../../etc/passwd")]
+    fn test_read_snapshot_file_rejects_traversal() {
+        let dir = ScratchDir::new("read_traversal");
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('read-node', 'Read', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_read', 'read-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        Spi::run("SELECT steep_repl.read_snapshot_file('snap_read', 'data/../../etc/passwd', 0, 100)")
+            .expect("traversal should be rejected");
+    }
+
+    #[pg_test]
+    fn test_verify_applied_snapshot_detects_tampering() {
+        Spi::run("CREATE TABLE public.verify_orders (id INT, amount INT)").expect("create table");
+        Spi::run("INSERT INTO public.verify_orders VALUES (1, 10), (2, 20)").expect("seed table");
+        let dir = ScratchDir::new("verify_applied");
+        Spi::run(&format!(
+            "COPY public.verify_orders TO '{}' WITH (FORMAT csv, HEADER true)",
+            dir.file("data/public.verify_orders.csv")
+        )).expect("write data file");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_verify", "tables": [{"schema": "public", "name": "verify_orders", "row_count": 2, "file": "data/public.verify_orders.csv"}, {"schema": "public", "name": "verify_missing", "row_count": 5, "file": "data/public.verify_missing.csv"}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('verify-node', 'Verify', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, target_node_id, storage_path, status)
+             VALUES ('snap_verify', 'verify-node', 'verify-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+        Spi::run("SET steep_repl.node_id = 'verify-node'").expect("set guc");
+
+        let matching = Spi::get_one::<String>(
+            "SELECT status || ':' || data_match
+             FROM steep_repl.verify_applied_snapshot('snap_verify', 'verify-node', true)
+             WHERE table_name = 'verify_orders'"
+        );
+        assert_eq!(matching, Ok(Some("PASS:true".to_string())));
+
+        let missing = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.verify_applied_snapshot('snap_verify', 'verify-node')
+             WHERE table_name = 'verify_missing'"
+        );
+        assert_eq!(missing, Ok(Some("MISSING".to_string())));
+
+        // Same row count, different data
+        Spi::run("UPDATE public.verify_orders SET amount = 99 WHERE id = 2").expect("tamper");
+        let tampered = Spi::get_one::<String>(
+            "SELECT status || ':' || actual_rows || ':' || data_match
+             FROM steep_repl.verify_applied_snapshot('snap_verify', 'verify-node', true)
+             WHERE table_name = 'verify_orders'"
+        );
+        assert_eq!(tampered, Ok(Some("FAIL:2:false".to_string())));
+
+        // Cleanup
+        Spi::run("RESET steep_repl.node_id").expect("reset guc");
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_verify'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'verify-node'")
+            .expect("cleanup nodes should succeed");
+        Spi::run("DROP TABLE public.verify_orders").expect("cleanup table");
+    }
+
+    #[pg_test]
+    fn test_verify_applied_snapshot_generated_columns() {
+        // Data files omit generated columns, as COPY TO does by default
+        Spi::run(
+            "CREATE TABLE public.verify_generated (
+                 id INT,
+                 amount INT,
+                 doubled INT GENERATED ALWAYS AS (amount * 2) STORED
+             )"
+        ).expect("create table");
+        Spi::run("INSERT INTO public.verify_generated (id, amount) VALUES (1, 10), (2, 20)").expect("seed table");
+        let dir = ScratchDir::new("verify_generated");
+        Spi::run(&format!(
+            "COPY public.verify_generated TO '{}' WITH (FORMAT csv, HEADER true)",
+            dir.file("data/public.verify_generated.csv")
+        )).expect("write data file");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_verify_gen", "tables": [{"schema": "public", "name": "verify_generated", "row_count": 2, "file": "data/public.verify_generated.csv"}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('verify-gen-node', 'Verify', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, target_node_id, storage_path, status)
+             VALUES ('snap_verify_gen', 'verify-gen-node', 'verify-gen-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+        Spi::run("SET steep_repl.node_id = 'verify-gen-node'").expect("set guc");
+
+        let matching = Spi::get_one::<String>(
+            "SELECT status || ':' || data_match
+             FROM steep_repl.verify_applied_snapshot('snap_verify_gen', 'verify-gen-node', true)"
+        );
+        assert_eq!(matching, Ok(Some("PASS:true".to_string())), "generated columns should not break the data check");
+
+        Spi::run("UPDATE public.verify_generated SET amount = 99 WHERE id = 2").expect("tamper");
+        let tampered = Spi::get_one::<String>(
+            "SELECT status || ':' || data_match
+             FROM steep_repl.verify_applied_snapshot('snap_verify_gen', 'verify-gen-node', true)"
+        );
+        assert_eq!(tampered, Ok(Some("FAIL:false".to_string())));
+
+        // Cleanup
+        Spi::run("RESET steep_repl.node_id").expect("reset guc");
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_verify_gen'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'verify-gen-node'")
+            .expect("cleanup nodes should succeed");
+        Spi::run("DROP TABLE public.verify_generated").expect("cleanup table");
+    }
+
+    #[pg_test]
+    fn test_pinned_snapshot_survives_expiry() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('pin-node', 'Pin', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, status, tags, created_at, expires_at)
+             VALUES ('snap_pin_1', 'pin-node', 'complete', '{nightly}', now() - interval '3 days', now() + interval '1 day'),
+                    ('snap_pin_2', 'pin-node', 'complete', '{nightly}', now() - interval '2 days', NULL),
+                    ('snap_pin_3', 'pin-node', 'complete', '{nightly}', now() - interval '1 day', NULL)"
+        ).expect("snapshot insert should succeed");
+
+        Spi::run("SELECT steep_repl.pin_snapshot('snap_pin_1')").expect("pin should succeed");
+        let cleared = Spi::get_one::<bool>(
+            "SELECT pinned AND expires_at IS NULL FROM steep_repl.snapshots WHERE snapshot_id = 'snap_pin_1'"
+        );
+        assert_eq!(cleared, Ok(Some(true)), "pinning should clear expires_at");
+
+        // Without the pin, keeping one would expire both older snapshots
+        let expired = Spi::get_one::<i32>("SELECT steep_repl.expire_snapshots_by_tag('nightly', 1)");
+        assert_eq!(expired, Ok(Some(1)));
+
+        let statuses = Spi::get_one::<String>(
+            "SELECT string_agg(snapshot_id || '=' || status, ',' ORDER BY snapshot_id)
+             FROM steep_repl.snapshots WHERE source_node_id = 'pin-node'"
+        );
+        assert_eq!(
+            statuses,
+            Ok(Some("snap_pin_1=complete,snap_pin_2=expired,snap_pin_3=complete".to_string()))
+        );
+
+        // Unpinned, it is subject to retention again
+        Spi::run("SELECT steep_repl.pin_snapshot('snap_pin_1', false)").expect("unpin should succeed");
+        let expired = Spi::get_one::<i32>("SELECT steep_repl.expire_snapshots_by_tag('nightly', 1)");
+        assert_eq!(expired, Ok(Some(1)));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE source_node_id = 'pin-node'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'pin-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test(error = "Snapshot snap_missing not found in steep_repl.snapshots")]
+    fn test_pin_snapshot_unknown() {
+        Spi::run("SELECT steep_repl.pin_snapshot('snap_missing')").expect("pin");
+    }
+
+    #[pg_test]
+    fn test_expire_snapshots_by_tag_keeps_most_recent() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('tag-node', 'Tag', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, status, tags, created_at)
+             VALUES ('snap_daily_1', 'tag-node', 'complete', '{daily}', now() - interval '3 days'),
+                    ('snap_daily_2', 'tag-node', 'complete', '{daily}', now() - interval '2 days'),
+                    ('snap_daily_3', 'tag-node', 'complete', '{daily,weekly}', now() - interval '1 day'),
+                    ('snap_weekly_1', 'tag-node', 'complete', '{weekly}', now() - interval '7 days')"
+        ).expect("snapshot insert should succeed");
+
+        let expired = Spi::get_one::<i32>("SELECT steep_repl.expire_snapshots_by_tag('daily', 2)");
+        assert_eq!(expired, Ok(Some(1)), "only the oldest daily snapshot should expire");
+
+        let statuses = Spi::get_one::<String>(
+            "SELECT string_agg(snapshot_id || '=' || status, ',' ORDER BY snapshot_id)
+             FROM steep_repl.snapshots WHERE source_node_id = 'tag-node'"
+        );
+        assert_eq!(
+            statuses,
+            Ok(Some("snap_daily_1=expired,snap_daily_2=complete,snap_daily_3=complete,snap_weekly_1=complete".to_string()))
+        );
+
+        // Already-expired snapshots do not count toward the kept set
+        let expired = Spi::get_one::<i32>("SELECT steep_repl.expire_snapshots_by_tag('weekly', 1)");
+        assert_eq!(expired, Ok(Some(1)));
+        let weekly = Spi::get_one::<String>(
+            "SELECT status FROM steep_repl.snapshots WHERE snapshot_id = 'snap_weekly_1'"
+        );
+        assert_eq!(weekly, Ok(Some("expired".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE source_node_id = 'tag-node'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'tag-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_best_incremental_base_picks_newest_full() {
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('base-node', 'Base', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+
+        let none = Spi::get_one::<bool>(
+            "SELECT (steep_repl.best_incremental_base('base-node')).snapshot_id IS NULL"
+        );
+        assert_eq!(none, Ok(Some(true)), "no snapshots should yield NULL");
+
+        Spi::run(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, status, lsn, completed_at)
+             VALUES ('snap_full_old', 'base-node', 'complete', '0/1000', now() - interval '3 days'),
+                    ('snap_full_new', 'base-node', 'complete', '0/3000', now() - interval '2 days'),
+                    ('snap_failed', 'base-node', 'failed', '0/4000', now() - interval '1 day'),
+                    ('snap_running', 'base-node', 'generating', '0/5000', NULL)"
+        ).expect("snapshot insert should succeed");
+        Spi::run(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, status, lsn, completed_at, base_snapshot_id)
+             VALUES ('snap_incr', 'base-node', 'complete', '0/6000', now(), 'snap_full_new')"
+        ).expect("incremental insert should succeed");
+
+        let base = Spi::get_one::<String>(
+            "SELECT snapshot_id || '@' || lsn FROM steep_repl.best_incremental_base('base-node')"
+        );
+        assert_eq!(
+            base,
+            Ok(Some("snap_full_new@0/3000".to_string())),
+            "newest complete full snapshot should win over incrementals and non-complete ones"
+        );
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE source_node_id = 'base-node'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'base-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_slowest_tables_orders_by_duration() {
+        let dir = ScratchDir::new("slow");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_slow", "tables": [{"schema": "public", "name": "small", "row_count": 10, "size_bytes": 512, "duration_ms": 40}, {"schema": "public", "name": "huge", "row_count": 90000, "size_bytes": 9000000, "duration_ms": 52000}, {"schema": "public", "name": "medium", "row_count": 5000, "size_bytes": 200000, "duration_ms": 1800}, {"schema": "public", "name": "legacy", "row_count": 1, "size_bytes": 64}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('slow-node', 'Slow', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_slow', 'slow-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let order = Spi::get_one::<String>(
+            "SELECT string_agg(table_name || '=' || duration_ms, ',' ORDER BY ord)
+             FROM steep_repl.slowest_tables('snap_slow') WITH ORDINALITY AS s(table_schema, table_name, duration_ms, row_count, size_bytes, ord)"
+        );
+        assert_eq!(
+            order,
+            Ok(Some("huge=52000,medium=1800,small=40".to_string())),
+            "slowest first, untimed entries skipped"
+        );
+
+        let top = Spi::get_one::<String>(
+            "SELECT string_agg(table_name, ',') FROM steep_repl.slowest_tables('snap_slow', 1)"
+        );
+        assert_eq!(top, Ok(Some("huge".to_string())));
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_slow'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'slow-node'")
+            .expect("cleanup nodes should succeed");
+    }
+
+    #[pg_test]
+    fn test_validate_snapshot_integrity_reports_orphans() {
+        Spi::run("CREATE TABLE public.integrity_customers (id INT PRIMARY KEY)").expect("create parent");
+        Spi::run(
+            "CREATE TABLE public.integrity_orders (id INT PRIMARY KEY, customer_id INT REFERENCES public.integrity_customers(id))"
+        ).expect("create child");
+
+        // Snapshot data: order 11 references customer 2, which the snapshot lacks
+        let dir = ScratchDir::new("integrity");
+        dir.write("data/public.integrity_customers.csv", "id\n1\n");
+        dir.write("data/public.integrity_orders.csv", "id,customer_id\n10,1\n11,2\n12,\n");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_integrity", "tables": [{"schema": "public", "name": "integrity_customers", "file": "data/public.integrity_customers.csv"}, {"schema": "public", "name": "integrity_orders", "file": "data/public.integrity_orders.csv"}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('integrity-node', 'Integrity', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_integrity', 'integrity-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let violation = Spi::get_one::<String>(
+            "SELECT child_table || '->' || parent_table || ':' || orphaned_rows || ':' || sample_key::text
+             FROM steep_repl.validate_snapshot_integrity('snap_integrity')"
+        );
+        assert_eq!(
+            violation,
+            Ok(Some(r#"integrity_orders->integrity_customers:1:{"customer_id": 2}"#.to_string())),
+            "the dangling customer reference should be reported; NULL references are allowed"
+        );
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_integrity'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'integrity-node'")
+            .expect("cleanup nodes should succeed");
+        Spi::run("DROP TABLE public.integrity_orders, public.integrity_customers").expect("cleanup tables");
+    }
+
+    #[pg_test]
+    fn test_validate_snapshot_integrity_generated_columns() {
+        Spi::run("CREATE TABLE public.integrity_gen_parent (id INT PRIMARY KEY)").expect("create parent");
+        Spi::run(
+            "CREATE TABLE public.integrity_gen_child (
+                 id INT PRIMARY KEY,
+                 parent_id INT REFERENCES public.integrity_gen_parent(id),
+                 label TEXT,
+                 label_upper TEXT GENERATED ALWAYS AS (upper(label)) STORED
+             )"
+        ).expect("create child");
+
+        // The child's data file omits its generated column
+        let dir = ScratchDir::new("integrity_generated");
+        dir.write("data/public.integrity_gen_parent.csv", "id\n1\n");
+        dir.write("data/public.integrity_gen_child.csv", "id,parent_id,label\n10,1,a\n11,3,b\n");
+        dir.write(
+            "manifest.json",
+            r#"{"snapshot_id": "snap_integrity_gen", "tables": [{"schema": "public", "name": "integrity_gen_parent", "file": "data/public.integrity_gen_parent.csv"}, {"schema": "public", "name": "integrity_gen_child", "file": "data/public.integrity_gen_child.csv"}]}"#,
+        );
+
+        Spi::run(
+            "INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+             VALUES ('integrity-gen-node', 'Integrity', 'localhost', 5432, 50, 'healthy')"
+        ).expect("node insert should succeed");
+        Spi::run(&format!(
+            "INSERT INTO steep_repl.snapshots (snapshot_id, source_node_id, storage_path, status)
+             VALUES ('snap_integrity_gen', 'integrity-gen-node', '{}', 'complete')",
+            dir.path()
+        )).expect("snapshot insert should succeed");
+
+        let violation = Spi::get_one::<String>(
+            "SELECT child_table || ':' || orphaned_rows || ':' || sample_key::text
+             FROM steep_repl.validate_snapshot_integrity('snap_integrity_gen')"
+        );
+        assert_eq!(
+            violation,
+            Ok(Some(r#"integrity_gen_child:1:{"parent_id": 3}"#.to_string())),
+            "tables with generated columns should load and be checked"
+        );
+
+        // Cleanup
+        Spi::run("DELETE FROM steep_repl.snapshots WHERE snapshot_id = 'snap_integrity_gen'")
+            .expect("cleanup snapshots should succeed");
+        Spi::run("DELETE FROM steep_repl.nodes WHERE node_id = 'integrity-gen-node'")
+            .expect("cleanup nodes should succeed");
+        Spi::run("DROP TABLE public.integrity_gen_child, public.integrity_gen_parent").expect("cleanup tables");
+    }
+}
@@ -19,6 +19,10 @@ CREATE TABLE steep_repl.snapshots (
     storage_path TEXT,
     compression TEXT DEFAULT 'gzip',
     checksum TEXT,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    pinned BOOLEAN NOT NULL DEFAULT false,
+    -- Incremental snapshots record the full snapshot they build on
+    base_snapshot_id TEXT REFERENCES steep_repl.snapshots(snapshot_id),
 
     -- Status tracking
     status TEXT NOT NULL DEFAULT 'pending',
@@ -51,7 +55,7 @@ CREATE TABLE steep_repl.snapshots (
     CONSTRAINT snapshots_tables_completed_check CHECK (tables_completed >= 0 AND tables_completed <= table_count),
     CONSTRAINT snapshots_percent_check CHECK (overall_percent >= 0 AND overall_percent <= 100),
     CONSTRAINT snapshots_compression_check CHECK (compression IN ('none', 'gzip', 'lz4', 'zstd')),
-    CONSTRAINT snapshots_status_check CHECK (status IN ('pending', 'generating', 'complete', 'applying', 'applied', 'failed', 'cancelled', 'expired')),
+    CONSTRAINT snapshots_status_check CHECK (status IN ('pending', 'generating', 'complete', 'applying', 'applied', 'failed', 'cancelled', 'expired', 'files_missing')),
     CONSTRAINT snapshots_phase_check CHECK (phase IN ('idle', 'schema', 'data', 'indexes', 'constraints', 'sequences', 'verify'))
 );
 
@@ -64,7 +68,10 @@ COMMENT ON COLUMN steep_repl.snapshots.lsn IS 'WAL position at snapshot time';
 COMMENT ON COLUMN steep_repl.snapshots.storage_path IS 'File system or S3 path';
 COMMENT ON COLUMN steep_repl.snapshots.compression IS 'Compression type (none, gzip, lz4, zstd)';
 COMMENT ON COLUMN steep_repl.snapshots.checksum IS 'SHA256 of manifest';
-COMMENT ON COLUMN steep_repl.snapshots.status IS 'Overall status: pending, generating, complete, applying, applied, failed, cancelled, expired';
+COMMENT ON COLUMN steep_repl.snapshots.tags IS 'Operator-defined labels for grouping and retention (e.g., daily, weekly)';
+COMMENT ON COLUMN steep_repl.snapshots.pinned IS 'Kept for audit or reference; retention never expires pinned snapshots';
+COMMENT ON COLUMN steep_repl.snapshots.base_snapshot_id IS 'Full snapshot an incremental snapshot builds on (NULL for full snapshots)';
+COMMENT ON COLUMN steep_repl.snapshots.status IS 'Overall status: pending, generating, complete, applying, applied, failed, cancelled, expired, files_missing';
 COMMENT ON COLUMN steep_repl.snapshots.phase IS 'Current phase: idle, schema, data, indexes, constraints, sequences, verify';
 COMMENT ON COLUMN steep_repl.snapshots.error_message IS 'Error details if status is failed';
 COMMENT ON COLUMN steep_repl.snapshots.overall_percent IS 'Overall completion percentage (0-100)';
@@ -89,6 +96,12 @@ CREATE INDEX idx_snapshots_target ON steep_repl.snapshots(target_node_id) WHERE
 CREATE INDEX idx_snapshots_status ON steep_repl.snapshots(status);
 CREATE INDEX idx_snapshots_active ON steep_repl.snapshots(status) WHERE status IN ('generating', 'applying');
 CREATE INDEX idx_snapshots_expires ON steep_repl.snapshots(expires_at) WHERE expires_at IS NOT NULL;
+CREATE INDEX idx_snapshots_tags ON steep_repl.snapshots USING gin(tags);
+
+-- Sequence backing snapshot IDs, so concurrent generations never collide
+CREATE SEQUENCE steep_repl.snapshot_id_seq;
+
+COMMENT ON SEQUENCE steep_repl.snapshot_id_seq IS 'Monotonic suffix for generated snapshot IDs';
 
 -- LISTEN/NOTIFY for real-time updates
 CREATE OR REPLACE FUNCTION steep_repl.notify_snapshot_change()
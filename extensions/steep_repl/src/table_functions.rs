@@ -0,0 +1,203 @@
+//! Table enumeration SQL functions for steep_repl extension.
+//!
+//! This module provides the shared view of which user tables are eligible
+//! for snapshots, merges, and fingerprinting, honoring the
+//! steep_repl.included_schemas setting, plus live-table checksums for
+//! data-level drift checks between nodes.
+
+use pgrx::prelude::*;
+
+extension_sql!(
+    r#"
+-- Effective schema allowlist from steep_repl.included_schemas
+-- Returns NULL when unset, meaning all non-system schemas except steep_repl
+CREATE FUNCTION steep_repl.included_schemas()
+RETURNS TEXT[] AS $$
+    SELECT NULLIF(array(
+        SELECT btrim(s)
+        FROM unnest(string_to_array(COALESCE(current_setting('steep_repl.included_schemas', true), ''), ',')) s
+        WHERE btrim(s) <> ''
+    ), '{}');
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.included_schemas() IS 'Schemas allowed by steep_repl.included_schemas (NULL means all user schemas)';
+
+-- User tables eligible for snapshots and merges
+CREATE FUNCTION steep_repl.eligible_tables()
+RETURNS TABLE (
+    table_schema TEXT,
+    table_name TEXT
+) AS $$
+    SELECT t.schemaname::TEXT, t.tablename::TEXT
+    FROM pg_tables t
+    WHERE t.schemaname NOT IN ('pg_catalog', 'information_schema', steep_repl.schema_name())
+      AND (steep_repl.included_schemas() IS NULL
+           OR t.schemaname = ANY(steep_repl.included_schemas()))
+    ORDER BY 1, 2;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION steep_repl.eligible_tables() IS 'User tables eligible for snapshots and merges, restricted by steep_repl.included_schemas';
+
+-- Order-independent checksum of a table's rows: the sum of per-row hashes
+-- Matches the row_hash sum used by verify_applied_snapshot.
+CREATE FUNCTION steep_repl.table_checksum(p_schema TEXT, p_table TEXT)
+RETURNS NUMERIC AS $$
+DECLARE
+    v_checksum NUMERIC;
+BEGIN
+    EXECUTE format('SELECT COALESCE(sum(steep_repl.row_hash(t)::numeric), 0) FROM %I.%I t', p_schema, p_table)
+    INTO v_checksum;
+    RETURN v_checksum;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMENT ON FUNCTION steep_repl.table_checksum(TEXT, TEXT) IS 'Order-independent checksum of all rows in a table';
+
+-- Compare a live table with the same table on a peer via dblink
+-- The peer hashes with hashtextextended directly (what row_hash wraps),
+-- so it does not need steep_repl installed.
+CREATE FUNCTION steep_repl.compare_table(p_peer_connstr TEXT, p_schema TEXT, p_table TEXT)
+RETURNS TABLE (
+    local_rows BIGINT,
+    remote_rows BIGINT,
+    local_checksum NUMERIC,
+    remote_checksum NUMERIC,
+    status TEXT  -- MATCH, MISMATCH
+) AS $$
+BEGIN
+    CREATE EXTENSION IF NOT EXISTS dblink;
+
+    EXECUTE format('SELECT count(*), COALESCE(sum(steep_repl.row_hash(t)::numeric), 0) FROM %I.%I t', p_schema, p_table)
+    INTO local_rows, local_checksum;
+
+    SELECT r.row_count, r.checksum
+    INTO remote_rows, remote_checksum
+    FROM dblink(
+        p_peer_connstr,
+        format('SELECT count(*), COALESCE(sum(hashtextextended(t::text, 0)::numeric), 0) FROM %I.%I t', p_schema, p_table)
+    ) AS r(row_count BIGINT, checksum NUMERIC);
+
+    status := CASE
+        WHEN local_rows = remote_rows AND local_checksum = remote_checksum THEN 'MATCH'
+        ELSE 'MISMATCH'
+    END;
+
+    RETURN NEXT;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION steep_repl.compare_table(TEXT, TEXT, TEXT) IS 'Compare row count and checksum of a table with a peer (MATCH or MISMATCH)';
+"#,
+    name = "create_table_functions",
+    requires = ["create_schema", "create_merge_functions"],
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn test_included_schemas_defaults_to_null() {
+        Spi::run("RESET steep_repl.included_schemas").expect("reset guc");
+        let result = Spi::get_one::<bool>("SELECT steep_repl.included_schemas() IS NULL");
+        assert_eq!(result, Ok(Some(true)), "unset allowlist should mean all schemas");
+    }
+
+    #[pg_test]
+    fn test_eligible_tables_respects_included_schemas() {
+        Spi::run("CREATE SCHEMA IF NOT EXISTS test_app").expect("create schema");
+        Spi::run("CREATE TABLE test_app.orders (id INT)").expect("create app table");
+        Spi::run("CREATE TABLE public.test_excluded (id INT)").expect("create public table");
+
+        // Without an allowlist both tables are eligible
+        let count = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.eligible_tables()
+             WHERE (table_schema, table_name) IN (('test_app', 'orders'), ('public', 'test_excluded'))"
+        );
+        assert_eq!(count, Ok(Some(2)));
+
+        Spi::run("SET steep_repl.included_schemas = 'test_app, other'").expect("set guc");
+
+        let schemas = Spi::get_one::<Vec<String>>("SELECT steep_repl.included_schemas()");
+        assert_eq!(schemas, Ok(Some(vec!["test_app".to_string(), "other".to_string()])));
+
+        let public_count = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.eligible_tables() WHERE table_schema = 'public'"
+        );
+        assert_eq!(public_count, Ok(Some(0)), "public should be excluded by the allowlist");
+
+        // Fingerprint capture follows the same enumeration
+        let captured = Spi::get_one::<i32>("SELECT steep_repl.capture_all_fingerprints('test-node')");
+        assert_eq!(captured, Ok(Some(1)), "only test_app.orders should be captured");
+
+        // Cleanup
+        Spi::run("RESET steep_repl.included_schemas").expect("reset guc");
+        Spi::run("DELETE FROM steep_repl.schema_fingerprints WHERE node_id = 'test-node'")
+            .expect("cleanup fingerprints should succeed");
+        Spi::run("DROP TABLE public.test_excluded").expect("cleanup public table");
+        Spi::run("DROP SCHEMA test_app CASCADE").expect("cleanup schema");
+    }
+
+    #[pg_test]
+    fn test_eligible_tables_excludes_extension_schema() {
+        let own = Spi::get_one::<i64>(
+            "SELECT count(*) FROM steep_repl.eligible_tables() WHERE table_schema = steep_repl.schema_name()"
+        );
+        assert_eq!(own, Ok(Some(0)), "extension tables should never be eligible");
+
+        let uses_helper = Spi::get_one::<bool>(
+            "SELECT pg_get_functiondef('steep_repl.eligible_tables'::regproc) LIKE '%steep_repl.schema_name()%'"
+        );
+        assert_eq!(uses_helper, Ok(Some(true)), "eligible_tables should resolve the schema via schema_name()");
+    }
+
+    #[pg_test]
+    fn test_compare_table_detects_one_row_difference() {
+        Spi::run("CREATE EXTENSION IF NOT EXISTS dblink").expect("dblink");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=postgres',
+                                'CREATE DATABASE steep_checksum_peer')"
+        ).expect("create peer database");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=steep_checksum_peer',
+                'CREATE TABLE public.checksum_items (id INT PRIMARY KEY, v TEXT);
+                 INSERT INTO public.checksum_items VALUES (3, ''c''), (1, ''a''), (2, ''b'')')"
+        ).expect("seed peer table");
+
+        // Same rows, different insertion order
+        Spi::run("CREATE TABLE public.checksum_items (id INT PRIMARY KEY, v TEXT)").expect("create local table");
+        Spi::run("INSERT INTO public.checksum_items VALUES (1, 'a'), (2, 'b'), (3, 'c')").expect("seed local table");
+
+        let compare = "SELECT status FROM steep_repl.compare_table(
+            'host=localhost port=' || current_setting('port') || ' dbname=steep_checksum_peer',
+            'public', 'checksum_items')";
+
+        assert_eq!(Spi::get_one::<String>(compare), Ok(Some("MATCH".to_string())));
+
+        Spi::run("UPDATE public.checksum_items SET v = 'changed' WHERE id = 2").expect("diverge one row");
+        assert_eq!(Spi::get_one::<String>(compare), Ok(Some("MISMATCH".to_string())));
+
+        // Cleanup
+        Spi::run("DROP TABLE public.checksum_items").expect("drop local table");
+        Spi::run(
+            "SELECT dblink_exec('host=localhost port=' || current_setting('port') || ' dbname=postgres',
+                                'DROP DATABASE steep_checksum_peer')"
+        ).expect("drop peer database");
+    }
+
+    #[pg_test]
+    fn test_table_checksum_order_independent() {
+        Spi::run("CREATE TABLE public.checksum_a (id INT, v TEXT)").expect("create table");
+        Spi::run("CREATE TABLE public.checksum_b (id INT, v TEXT)").expect("create table");
+        Spi::run("INSERT INTO public.checksum_a VALUES (1, 'a'), (2, 'b')").expect("seed table");
+        Spi::run("INSERT INTO public.checksum_b VALUES (2, 'b'), (1, 'a')").expect("seed table");
+
+        let same = Spi::get_one::<bool>(
+            "SELECT steep_repl.table_checksum('public', 'checksum_a') = steep_repl.table_checksum('public', 'checksum_b')"
+        );
+        assert_eq!(same, Ok(Some(true)), "row order should not affect the checksum");
+
+        Spi::run("DROP TABLE public.checksum_a, public.checksum_b").expect("cleanup tables");
+    }
+}
@@ -0,0 +1,58 @@
+//! Shared helpers for steep_repl pg_tests.
+//!
+//! Tests run inside the PostgreSQL backend, so fixture files written here are
+//! readable by server-side COPY and pg_read_file without extra permissions.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// A scratch directory unique to one test, removed when dropped.
+///
+/// Dropping also runs when an assertion panics, so a failing test does not
+/// leave files behind for the next run.
+pub(crate) struct ScratchDir {
+    root: PathBuf,
+}
+
+impl ScratchDir {
+    /// Create an empty directory named after `label`, the backend pid, and a counter.
+    pub(crate) fn new(label: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "steep_{}_{}_{}",
+            label,
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("create scratch directory");
+        ScratchDir { root }
+    }
+
+    /// Absolute path of the directory, for storage_path columns.
+    pub(crate) fn path(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    /// Absolute path of `relative` inside the directory, creating its parent.
+    pub(crate) fn file(&self, relative: &str) -> String {
+        let path = self.root.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create scratch subdirectory");
+        }
+        path.to_string_lossy().into_owned()
+    }
+
+    /// Write `contents` to `relative` inside the directory.
+    pub(crate) fn write(&self, relative: &str, contents: &str) {
+        fs::write(self.file(relative), contents).expect("write scratch file");
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
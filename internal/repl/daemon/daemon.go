@@ -169,7 +169,7 @@ func (d *Daemon) Start() error {
 	}
 
 	// Register InitServer with gRPC server before starting
-	initServer := replgrpc.NewInitServer(d.initManager, d.logger, d.debug)
+	initServer := replgrpc.NewInitServer(d.initManager, d.config.NodeID, d.logger, d.debug)
 	grpcServer.SetInitServer(initServer)
 
 	if err := grpcServer.Start(d.ctx); err != nil {
@@ -17,6 +17,7 @@ const (
 	ActionNodeRegistered     AuditAction = "node.registered"
 	ActionNodeUpdated        AuditAction = "node.updated"
 	ActionNodeRemoved        AuditAction = "node.removed"
+	ActionNodeRenamed        AuditAction = "node.renamed"
 	ActionCoordinatorElected AuditAction = "coordinator.elected"
 	ActionStateUpdated       AuditAction = "state.updated"
 	ActionDaemonStarted      AuditAction = "daemon.started"
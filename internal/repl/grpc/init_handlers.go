@@ -23,14 +23,17 @@ type InitServer struct {
 	pb.UnimplementedInitServiceServer
 
 	manager *replinit.Manager
+	nodeID  string
 	logger  *log.Logger
 	debug   bool
 }
 
 // NewInitServer creates a new InitService server.
-func NewInitServer(manager *replinit.Manager, logger *log.Logger, debug bool) *InitServer {
+// nodeID identifies the local node, whose database snapshots are exported from.
+func NewInitServer(manager *replinit.Manager, nodeID string, logger *log.Logger, debug bool) *InitServer {
 	return &InitServer{
 		manager: manager,
+		nodeID:  nodeID,
 		logger:  logger,
 		debug:   debug,
 	}
@@ -571,8 +574,23 @@ func (s *InitServer) CaptureFingerprints(ctx context.Context, req *pb.CaptureFin
 func (s *InitServer) GenerateSnapshot(req *pb.GenerateSnapshotRequest, stream grpc.ServerStreamingServer[pb.SnapshotProgress]) error {
 	s.logRequest("GenerateSnapshot", req.SourceNodeId)
 
+	// Without an explicit source, use the preferred (or highest-priority) healthy node
 	if req.SourceNodeId == "" {
-		return status.Error(codes.InvalidArgument, "source_node_id is required")
+		var source *string
+		if err := s.manager.Pool().QueryRow(stream.Context(), "SELECT steep_repl.snapshot_source()").Scan(&source); err != nil {
+			return status.Errorf(codes.Internal, "failed to resolve snapshot source: %v", err)
+		}
+		if source == nil {
+			return status.Error(codes.InvalidArgument, "source_node_id is required (no preferred or healthy source node)")
+		}
+		// Snapshots are exported from this daemon's database, so a preference
+		// for another node must be served by that node's daemon.
+		if *source != s.nodeID {
+			return status.Errorf(codes.FailedPrecondition,
+				"preferred snapshot source is node %s, but this daemon serves node %s; send the request to %s",
+				*source, s.nodeID, *source)
+		}
+		req.SourceNodeId = *source
 	}
 	if req.OutputPath == "" {
 		return status.Error(codes.InvalidArgument, "output_path is required")
@@ -3,7 +3,9 @@ package init
 
 import (
 	"context"
+	"errors"
 	"fmt"
+	"strings"
 	"time"
 
 	"github.com/google/uuid"
@@ -19,15 +21,83 @@ type Merger struct {
 	localPool  *pgxpool.Pool
 	remotePool *pgxpool.Pool
 	manager    *Manager
+
+	versionLookup        VersionLookupFunc
+	allowVersionMismatch bool
+
+	// Audit settings for the merge in progress (MergeConfig.MaxAuditRows
+	// and MergeConfig.LogLevel)
+	logLevel       MergeLogLevel
+	maxAuditRows   int64
+	auditRows      int64
+	auditTruncated bool
 }
 
+// ErrConflictThresholdExceeded is returned by ExecuteMerge when overlap
+// analysis finds more conflicts than MergeConfig.MaxConflicts or
+// MaxConflictRatio allow, including on a dry run. Nothing has been changed
+// on either node.
+var ErrConflictThresholdExceeded = errors.New("conflict_threshold_exceeded")
+
+// VersionLookupFunc returns the steep_repl extension version reachable
+// through a pool.
+type VersionLookupFunc func(ctx context.Context, pool *pgxpool.Pool) (string, error)
+
 // NewMerger creates a new merger for bidirectional operations.
 func NewMerger(localPool, remotePool *pgxpool.Pool, manager *Manager) *Merger {
 	return &Merger{
-		localPool:  localPool,
-		remotePool: remotePool,
-		manager:    manager,
+		localPool:     localPool,
+		remotePool:    remotePool,
+		manager:       manager,
+		versionLookup: queryExtensionVersion,
+	}
+}
+
+// SetVersionLookup overrides how the extension version is read from each node.
+func (m *Merger) SetVersionLookup(fn VersionLookupFunc) {
+	if fn == nil {
+		fn = queryExtensionVersion
+	}
+	m.versionLookup = fn
+}
+
+// SetAllowVersionMismatch downgrades a major version mismatch between the
+// nodes from a pre-flight error to a warning.
+func (m *Merger) SetAllowVersionMismatch(allow bool) {
+	m.allowVersionMismatch = allow
+}
+
+// queryExtensionVersion reads steep_repl_version() from a node.
+func queryExtensionVersion(ctx context.Context, pool *pgxpool.Pool) (string, error) {
+	if pool == nil {
+		return "", errors.New("no connection")
+	}
+	var version string
+	if err := pool.QueryRow(ctx, "SELECT steep_repl_version()").Scan(&version); err != nil {
+		return "", err
+	}
+	return version, nil
+}
+
+// checkConflictThreshold reports ErrConflictThresholdExceeded when the
+// analysed conflicts exceed config.MaxConflicts, or exceed
+// config.MaxConflictRatio of all analysed rows.
+func checkConflictThreshold(config MergeConfig, result *MergeResult) error {
+	if config.MaxConflicts > 0 && result.TotalConflicts > config.MaxConflicts {
+		return fmt.Errorf("%w: %d conflicts exceeds max_conflicts %d",
+			ErrConflictThresholdExceeded, result.TotalConflicts, config.MaxConflicts)
+	}
+
+	total := result.TotalMatches + result.TotalConflicts + result.TotalLocalOnly + result.TotalRemoteOnly
+	if config.MaxConflictRatio > 0 && total > 0 {
+		ratio := float64(result.TotalConflicts) / float64(total)
+		if ratio > config.MaxConflictRatio {
+			return fmt.Errorf("%w: conflict ratio %.4f exceeds max_conflict_ratio %.4f",
+				ErrConflictThresholdExceeded, ratio, config.MaxConflictRatio)
+		}
 	}
+
+	return nil
 }
 
 // =============================================================================
@@ -56,6 +126,13 @@ func (m *Merger) RunPreflightChecks(ctx context.Context, tables []MergeTableInfo
 		AllTableshavePK:      true,
 		NoActiveTransactions: true,
 		TrackCommitTimestamp: false,
+		VersionCompatible:    true,
+	}
+
+	// Refuse to start when the nodes run different major extension versions
+	m.checkExtensionVersions(ctx, result)
+	if !result.VersionCompatible && !m.allowVersionMismatch {
+		return result, nil
 	}
 
 	// Check track_commit_timestamp
@@ -75,6 +152,23 @@ func (m *Merger) RunPreflightChecks(ctx context.Context, tables []MergeTableInfo
 		}
 	}
 
+	// Check tables are within steep_repl.included_schemas
+	for _, t := range tables {
+		var eligible bool
+		err := m.localPool.QueryRow(ctx, `
+			SELECT EXISTS (
+				SELECT 1 FROM steep_repl.eligible_tables()
+				WHERE table_schema = $1 AND table_name = $2
+			)`, t.Schema, t.Name).Scan(&eligible)
+		if err != nil {
+			result.Warnings = append(result.Warnings, fmt.Sprintf("eligibility check failed for %s.%s: %v", t.Schema, t.Name, err))
+			continue
+		}
+		if !eligible {
+			result.Errors = append(result.Errors, fmt.Sprintf("table %s.%s is not eligible for merge (outside steep_repl.included_schemas)", t.Schema, t.Name))
+		}
+	}
+
 	// Check schema match between local and remote nodes
 	if m.remotePool != nil {
 		for _, t := range tables {
@@ -106,6 +200,52 @@ func (m *Merger) RunPreflightChecks(ctx context.Context, tables []MergeTableInfo
 	return result, nil
 }
 
+// checkExtensionVersions compares the steep_repl version on both nodes.
+// A major version mismatch (major.minor before 1.0) is an error unless
+// allowVersionMismatch is set, in which case it is reported as a warning.
+func (m *Merger) checkExtensionVersions(ctx context.Context, result *PreflightResult) {
+	lookup := m.versionLookup
+	if lookup == nil {
+		lookup = queryExtensionVersion
+	}
+
+	localVersion, err := lookup(ctx, m.localPool)
+	if err != nil {
+		result.Warnings = append(result.Warnings, fmt.Sprintf("could not read local steep_repl version: %v", err))
+		return
+	}
+	remoteVersion, err := lookup(ctx, m.remotePool)
+	if err != nil {
+		result.Warnings = append(result.Warnings, fmt.Sprintf("could not read remote steep_repl version: %v", err))
+		return
+	}
+
+	if versionSeries(localVersion) == versionSeries(remoteVersion) {
+		return
+	}
+
+	result.VersionCompatible = false
+	msg := fmt.Sprintf("steep_repl major version mismatch: local %s, remote %s", localVersion, remoteVersion)
+	if m.allowVersionMismatch {
+		result.Warnings = append(result.Warnings, msg)
+	} else {
+		result.Errors = append(result.Errors, msg)
+	}
+}
+
+// versionSeries returns the compatibility series of a version string: the
+// major version ("1.2.3" -> "1"), or major.minor while the major version is
+// 0 ("0.1.0" -> "0.1"), matching steep_repl.version_series.
+func versionSeries(version string) string {
+	version = strings.TrimPrefix(strings.TrimSpace(version), "v")
+	major, rest, _ := strings.Cut(version, ".")
+	if major != "0" {
+		return major
+	}
+	minor, _, _ := strings.Cut(rest, ".")
+	return major + "." + minor
+}
+
 // compareTableSchemas compares the schema of a table between local and remote nodes.
 // Returns true if schemas match, false otherwise.
 func (m *Merger) compareTableSchemas(ctx context.Context, schema, table string) (bool, error) {
@@ -214,6 +354,20 @@ func (m *Merger) ExecuteMerge(ctx context.Context, config MergeConfig) (*MergeRe
 		result.Tables = append(result.Tables, fmt.Sprintf("%s.%s", t.Schema, t.Name))
 	}
 
+	switch config.LogLevel {
+	case "", LogAll, LogConflictsOnly, LogChangesOnly:
+	default:
+		return result, fmt.Errorf("unknown merge log level: %s", config.LogLevel)
+	}
+
+	// Counters stay exact when the audit log is capped or filtered; only rows are dropped
+	m.logLevel, m.maxAuditRows, m.auditRows, m.auditTruncated = config.LogLevel, config.MaxAuditRows, 0, false
+	defer func() {
+		result.AuditRowsWritten = m.auditRows
+		result.AuditTruncated = m.auditTruncated
+		m.logLevel, m.maxAuditRows = "", 0
+	}()
+
 	// Sort tables by FK dependencies (parents before children)
 	deps, err := m.GetFKDependencies(ctx, config.Tables)
 	if err != nil {
@@ -267,6 +421,29 @@ func (m *Merger) ExecuteMerge(ctx context.Context, config MergeConfig) (*MergeRe
 		result.TotalRemoteOnly += s.RemoteOnly
 	}
 
+	// Diverged data: stop before resolving or transferring anything, leaving
+	// the conflicts found in the audit log for inspection. A dry run reports
+	// the breach too, so a preview shows the real merge would abort.
+	if err := checkConflictThreshold(config, result); err != nil {
+		for i, t := range sortedTables {
+			if config.DryRun || summaries[i].Conflicts == 0 {
+				continue
+			}
+			conflicts, cerr := m.getConflicts(ctx, t.Schema, t.Name, t.PKColumns, config.RemoteServer)
+			if cerr != nil {
+				result.Errors = append(result.Errors, fmt.Sprintf("get conflicts for %s.%s: %v", t.Schema, t.Name, cerr))
+				continue
+			}
+			resolution, resolvedBy := "skipped", "conflict_threshold"
+			for _, c := range conflicts {
+				_ = m.logMergeDecision(ctx, result.MergeID, t.Schema, t.Name, c.PKValue, CategoryConflict, &resolution, c.NodeAValue, c.NodeBValue, &resolvedBy)
+			}
+		}
+		result.Errors = append(result.Errors, err.Error())
+		result.CompletedAt = time.Now()
+		return result, err
+	}
+
 	if config.DryRun {
 		result.CompletedAt = time.Now()
 		return result, nil
@@ -276,6 +453,17 @@ func (m *Merger) ExecuteMerge(ctx context.Context, config MergeConfig) (*MergeRe
 	for i, t := range sortedTables {
 		summary := summaries[i]
 
+		// Matches are only written out at LogAll
+		if summary.Matches > 0 && m.logLevel.Logs(CategoryMatch) {
+			matchPKs, err := m.getRowsByCategory(ctx, t.Schema, t.Name, t.PKColumns, config.RemoteServer, CategoryMatch)
+			if err != nil {
+				result.Errors = append(result.Errors, fmt.Sprintf("get matching rows for %s.%s: %v", t.Schema, t.Name, err))
+			}
+			for _, pk := range matchPKs {
+				_ = m.logMergeDecision(ctx, result.MergeID, t.Schema, t.Name, pk, CategoryMatch, nil, nil, nil, nil)
+			}
+		}
+
 		// Resolve conflicts if any
 		if summary.Conflicts > 0 {
 			if config.Strategy == StrategyManual {
@@ -293,6 +481,17 @@ func (m *Merger) ExecuteMerge(ctx context.Context, config MergeConfig) (*MergeRe
 			result.ConflictsResolved += resolved
 		}
 
+		// Record how identity/generated columns are carried across. This is
+		// informational only, so a lookup failure must not skip the transfer.
+		if summary.LocalOnly > 0 || summary.RemoteOnly > 0 {
+			columns, err := m.getTableColumns(ctx, m.localPool, t.Schema, t.Name)
+			if err != nil {
+				result.Errors = append(result.Errors, fmt.Sprintf("get columns for %s.%s: %v", t.Schema, t.Name, err))
+			} else {
+				result.ColumnHandling = append(result.ColumnHandling, describeColumnHandling(t.Schema, t.Name, columns)...)
+			}
+		}
+
 		// Transfer local-only rows (A→B)
 		if summary.LocalOnly > 0 {
 			localOnlyPKs, err := m.getRowsByCategory(ctx, t.Schema, t.Name, t.PKColumns, config.RemoteServer, CategoryLocalOnly)
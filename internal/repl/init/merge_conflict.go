@@ -30,6 +30,19 @@ func (m *Merger) ResolveConflicts(ctx context.Context, mergeID uuid.UUID, schema
 	var resolved int64
 	resolvedBy := fmt.Sprintf("strategy:%s", strategy)
 
+	// Use the same timestamp column the extension would pick for this table
+	// (steep_repl.modified_columns); without one, fall back to node A.
+	var modifiedColumn *string
+	if strategy == StrategyLastModified {
+		modifiedColumn, err = m.detectModifiedColumn(ctx, schema, table)
+		if err != nil {
+			return 0, err
+		}
+		if modifiedColumn == nil {
+			resolvedBy = "strategy:last-modified:fallback:prefer-node-a"
+		}
+	}
+
 	for _, conflict := range conflicts {
 		var resolution string
 		var keepValue map[string]interface{}
@@ -43,7 +56,11 @@ func (m *Merger) ResolveConflicts(ctx context.Context, mergeID uuid.UUID, schema
 			keepValue = conflict.NodeBValue
 		case StrategyLastModified:
 			// Compare timestamps if available
-			resolution, keepValue = m.ResolveByLastModified(conflict)
+			if modifiedColumn == nil {
+				resolution, keepValue = "kept_a", conflict.NodeAValue
+			} else {
+				resolution, keepValue = ResolveByColumns(conflict, []string{*modifiedColumn})
+			}
 		case StrategyManual:
 			// For manual, just log the conflict without resolving
 			resolution = "skipped"
@@ -104,11 +121,31 @@ func (m *Merger) getConflicts(ctx context.Context, schema, table string, pkColum
 	return conflicts, nil
 }
 
-// ResolveByLastModified resolves a conflict by comparing timestamps.
+// defaultModifiedColumns mirrors the extension's steep_repl.modified_columns default.
+var defaultModifiedColumns = []string{"updated_at", "modified_at", "last_modified", "timestamp"}
+
+// detectModifiedColumn asks the local node which column tracks last
+// modification for a table, honouring steep_repl.modified_columns.
+// Returns nil when the table has no such column.
+func (m *Merger) detectModifiedColumn(ctx context.Context, schema, table string) (*string, error) {
+	var column *string
+	if err := m.localPool.QueryRow(ctx,
+		"SELECT steep_repl.detect_modified_column($1, $2)", schema, table,
+	).Scan(&column); err != nil {
+		return nil, fmt.Errorf("detect modified column for %s.%s: %w", schema, table, err)
+	}
+	return column, nil
+}
+
+// ResolveByLastModified resolves a conflict by comparing timestamps in the
+// default last-modified columns.
 func (m *Merger) ResolveByLastModified(conflict ConflictDetail) (string, map[string]interface{}) {
-	// Look for updated_at or modified_at columns
-	timestampCols := []string{"updated_at", "modified_at", "last_modified", "timestamp"}
+	return ResolveByColumns(conflict, defaultModifiedColumns)
+}
 
+// ResolveByColumns resolves a conflict by comparing the first parseable
+// timestamp among timestampCols on each side.
+func ResolveByColumns(conflict ConflictDetail, timestampCols []string) (string, map[string]interface{}) {
 	var aTime, bTime time.Time
 	var aFound, bFound bool
 
@@ -158,13 +195,17 @@ func ParseTimestamp(v any) (time.Time, error) {
 	case time.Time:
 		return t, nil
 	case string:
-		// Try common formats
+		// Try common formats, including PostgreSQL's text output for
+		// timestamptz and date, which steep_repl.row_modified_at also accepts
 		formats := []string{
 			time.RFC3339,
 			time.RFC3339Nano,
 			"2006-01-02 15:04:05",
 			"2006-01-02T15:04:05",
 			"2006-01-02 15:04:05.999999",
+			"2006-01-02 15:04:05-07",
+			"2006-01-02 15:04:05-07:00",
+			"2006-01-02",
 		}
 		for _, f := range formats {
 			if parsed, err := time.Parse(f, t); err == nil {
@@ -186,15 +227,27 @@ func (m *Merger) applyResolution(ctx context.Context, schema, table string, pkCo
 		targetPool = m.localPool
 	}
 
+	// Generated columns are recomputed from the updated values
+	columns, err := m.getTableColumns(ctx, targetPool, schema, table)
+	if err != nil {
+		return fmt.Errorf("get table columns: %w", err)
+	}
+	generated := make(map[string]bool)
+	for _, c := range columns {
+		if c.Generated {
+			generated[c.Name] = true
+		}
+	}
+
 	// Build UPDATE statement
 	var setParts []string
 	var args []any
 	argIdx := 1
 
 	for col, val := range keepValue {
-		// Skip PK columns in SET clause
+		// Skip PK and generated columns in SET clause
 		isPK := slices.Contains(pkColumns, col)
-		if !isPK {
+		if !isPK && !generated[col] {
 			setParts = append(setParts, fmt.Sprintf("%s = $%d", pgx.Identifier{col}.Sanitize(), argIdx))
 			args = append(args, val)
 			argIdx++
@@ -215,12 +268,23 @@ func (m *Merger) applyResolution(ctx context.Context, schema, table string, pkCo
 		joinStrings(setParts, ", "),
 		joinStrings(whereParts, " AND "))
 
-	_, err := targetPool.Exec(ctx, query, args...)
+	_, err = targetPool.Exec(ctx, query, args...)
 	return err
 }
 
-// logMergeDecision logs a merge decision to the audit log.
+// logMergeDecision logs a merge decision to the audit log. Categories the
+// merge's LogLevel excludes are skipped. Once the merge's MaxAuditRows is
+// reached, decisions are dropped and the merge is flagged as having a
+// truncated audit.
 func (m *Merger) logMergeDecision(ctx context.Context, mergeID uuid.UUID, schema, table string, pkValue map[string]any, category OverlapCategory, resolution *string, nodeAValue, nodeBValue map[string]any, resolvedBy *string) error {
+	if !m.logLevel.Logs(category) {
+		return nil
+	}
+	if m.maxAuditRows > 0 && m.auditRows >= m.maxAuditRows {
+		m.auditTruncated = true
+		return nil
+	}
+
 	pkJSON, err := json.Marshal(pkValue)
 	if err != nil {
 		return err
@@ -238,8 +302,11 @@ func (m *Merger) logMergeDecision(ctx context.Context, mergeID uuid.UUID, schema
 		SELECT steep_repl.log_merge_decision($1, $2, $3, $4, $5, $6, $7, $8, $9)
 	`
 
-	_, err = m.localPool.Exec(ctx, query, mergeID, schema, table, pkJSON, string(category), resolution, nodeAJSON, nodeBJSON, resolvedBy)
-	return err
+	if _, err = m.localPool.Exec(ctx, query, mergeID, schema, table, pkJSON, string(category), resolution, nodeAJSON, nodeBJSON, resolvedBy); err != nil {
+		return err
+	}
+	m.auditRows++
+	return nil
 }
 
 // GenerateConflictReport generates a report of conflicts for manual resolution.
@@ -1,9 +1,13 @@
 package init_test
 
 import (
+	"context"
+	"strings"
 	"testing"
 	"time"
 
+	"github.com/jackc/pgx/v5/pgxpool"
+
 	replinit "github.com/willibrandon/steep/internal/repl/init"
 )
 
@@ -338,3 +342,73 @@ func TestResolveByLastModified_AlternateTimestampColumns(t *testing.T) {
 		t.Errorf("resolution = %q; want %q for timestamp column", resolution2, "kept_a")
 	}
 }
+
+// versionsByCall returns a version lookup that answers the local node first
+// and the remote node second.
+func versionsByCall(local, remote string) replinit.VersionLookupFunc {
+	calls := 0
+	return func(ctx context.Context, pool *pgxpool.Pool) (string, error) {
+		calls++
+		if calls%2 == 1 {
+			return local, nil
+		}
+		return remote, nil
+	}
+}
+
+func TestPreflightRefusesMajorVersionMismatch(t *testing.T) {
+	tests := []struct {
+		name   string
+		local  string
+		remote string
+	}{
+		{"major bump", "1.4.2", "2.0.0"},
+		{"v prefix", "v1.0.0", "3.1.0"},
+		{"remote older", "2.1.0", "1.9.9"},
+		{"pre-1.0 minor", "0.1.0", "0.2.0"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			m := replinit.NewMerger(nil, nil, nil)
+			m.SetVersionLookup(versionsByCall(tt.local, tt.remote))
+
+			result, err := m.RunPreflightChecks(context.Background(), nil)
+			if err != nil {
+				t.Fatalf("RunPreflightChecks error: %v", err)
+			}
+			if result.VersionCompatible {
+				t.Errorf("VersionCompatible = true; want false for %s vs %s", tt.local, tt.remote)
+			}
+			if len(result.Errors) != 1 || !strings.Contains(result.Errors[0], "major version mismatch") {
+				t.Errorf("Errors = %v; want a single major version mismatch error", result.Errors)
+			}
+		})
+	}
+}
+
+func TestMergeLogLevel_Logs(t *testing.T) {
+	categories := []replinit.OverlapCategory{
+		replinit.CategoryMatch,
+		replinit.CategoryConflict,
+		replinit.CategoryLocalOnly,
+		replinit.CategoryRemoteOnly,
+	}
+	tests := []struct {
+		level replinit.MergeLogLevel
+		want  []bool // in categories order
+	}{
+		{replinit.LogAll, []bool{true, true, true, true}},
+		{replinit.LogConflictsOnly, []bool{false, true, false, false}},
+		{replinit.LogChangesOnly, []bool{false, true, true, true}},
+		{"", []bool{false, true, true, true}},
+	}
+
+	for _, tt := range tests {
+		for i, category := range categories {
+			if got := tt.level.Logs(category); got != tt.want[i] {
+				t.Errorf("MergeLogLevel(%q).Logs(%s) = %v, want %v", tt.level, category, got, tt.want[i])
+			}
+		}
+	}
+}
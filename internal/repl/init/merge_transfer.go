@@ -35,6 +35,11 @@ func (m *Merger) TransferRows(ctx context.Context, sourcePool, targetPool *pgxpo
 
 // transferRowsInsert transfers rows using individual INSERT statements.
 func (m *Merger) transferRowsInsert(ctx context.Context, sourcePool, targetPool *pgxpool.Pool, schema, table string, pkColumns []string, pkValues []map[string]any) (int64, error) {
+	columns, err := m.getTableColumns(ctx, targetPool, schema, table)
+	if err != nil {
+		return 0, fmt.Errorf("get table columns: %w", err)
+	}
+
 	var transferred int64
 	for _, pk := range pkValues {
 		row, err := m.fetchRow(ctx, sourcePool, schema, table, pkColumns, pk)
@@ -42,22 +47,30 @@ func (m *Merger) transferRowsInsert(ctx context.Context, sourcePool, targetPool
 			return transferred, fmt.Errorf("fetch row from source: %w", err)
 		}
 
-		if err := m.insertRow(ctx, targetPool, schema, table, row); err != nil {
+		if err := m.insertRow(ctx, targetPool, schema, table, columns, row); err != nil {
 			return transferred, fmt.Errorf("insert row into target: %w", err)
 		}
 		transferred++
 	}
+
+	if err := m.syncIdentitySequences(ctx, targetPool, schema, table, columns); err != nil {
+		return transferred, fmt.Errorf("sync identity sequences: %w", err)
+	}
 	return transferred, nil
 }
 
 // transferRowsCopy transfers rows using PostgreSQL COPY protocol.
 func (m *Merger) transferRowsCopy(ctx context.Context, sourcePool, targetPool *pgxpool.Pool, schema, table string, pkColumns []string, pkValues []map[string]any) (int64, error) {
 	// Get column metadata from target table
-	columns, err := m.getTableColumns(ctx, targetPool, schema, table)
+	allColumns, err := m.getTableColumns(ctx, targetPool, schema, table)
 	if err != nil {
 		return 0, fmt.Errorf("get table columns: %w", err)
 	}
 
+	// Generated columns are recomputed by the target and can't be copied.
+	// COPY keeps supplied identity values even for GENERATED ALWAYS.
+	columns := insertableColumns(allColumns)
+
 	// Fetch all rows from source in bulk
 	rows, err := m.fetchRowsBulk(ctx, sourcePool, schema, table, columns, pkColumns, pkValues)
 	if err != nil {
@@ -85,13 +98,18 @@ func (m *Merger) transferRowsCopy(ctx context.Context, sourcePool, targetPool *p
 		return 0, fmt.Errorf("copy to target: %w", err)
 	}
 
+	if err := m.syncIdentitySequences(ctx, targetPool, schema, table, allColumns); err != nil {
+		return copyCount, fmt.Errorf("sync identity sequences: %w", err)
+	}
+
 	return copyCount, nil
 }
 
 // getTableColumns retrieves column metadata for a table in column order.
 func (m *Merger) getTableColumns(ctx context.Context, pool *pgxpool.Pool, schema, table string) ([]columnInfo, error) {
 	query := `
-		SELECT column_name, data_type, ordinal_position
+		SELECT column_name, data_type, ordinal_position,
+			COALESCE(identity_generation, ''), is_generated = 'ALWAYS'
 		FROM information_schema.columns
 		WHERE table_schema = $1 AND table_name = $2
 		ORDER BY ordinal_position`
@@ -105,7 +123,7 @@ func (m *Merger) getTableColumns(ctx context.Context, pool *pgxpool.Pool, schema
 	var columns []columnInfo
 	for rows.Next() {
 		var col columnInfo
-		if err := rows.Scan(&col.Name, &col.DataType, &col.Position); err != nil {
+		if err := rows.Scan(&col.Name, &col.DataType, &col.Position, &col.Identity, &col.Generated); err != nil {
 			return nil, err
 		}
 		columns = append(columns, col)
@@ -113,6 +131,65 @@ func (m *Merger) getTableColumns(ctx context.Context, pool *pgxpool.Pool, schema
 	return columns, rows.Err()
 }
 
+// insertableColumns returns the columns that can be written explicitly,
+// dropping generated columns.
+func insertableColumns(columns []columnInfo) []columnInfo {
+	result := make([]columnInfo, 0, len(columns))
+	for _, col := range columns {
+		if !col.Generated {
+			result = append(result, col)
+		}
+	}
+	return result
+}
+
+// describeColumnHandling reports how transfers treat each identity or
+// generated column of a table.
+func describeColumnHandling(schema, table string, columns []columnInfo) []ColumnHandling {
+	var result []ColumnHandling
+	for _, col := range columns {
+		h := ColumnHandling{TableSchema: schema, TableName: table, Column: col.Name}
+		switch {
+		case col.Generated:
+			h.Kind, h.Handling = "generated", "excluded"
+		case col.Identity == "ALWAYS":
+			h.Kind, h.Handling = "identity_always", "overriding_system_value"
+		case col.Identity == "BY DEFAULT":
+			h.Kind, h.Handling = "identity_by_default", "preserved"
+		default:
+			continue
+		}
+		result = append(result, h)
+	}
+	return result
+}
+
+// syncIdentitySequences advances identity sequences on the target past the
+// highest transferred value so later local inserts don't collide with it.
+func (m *Merger) syncIdentitySequences(ctx context.Context, pool *pgxpool.Pool, schema, table string, columns []columnInfo) error {
+	qualified := pgx.Identifier{schema}.Sanitize() + "." + pgx.Identifier{table}.Sanitize()
+	for _, col := range columns {
+		if col.Identity == "" {
+			continue
+		}
+
+		query := fmt.Sprintf(`
+			SELECT setval(seq, mx)
+			FROM (
+				SELECT pg_get_serial_sequence($1, $2) AS seq,
+					(SELECT max(%s) FROM %s) AS mx
+			) v
+			WHERE mx IS NOT NULL
+			  AND mx > COALESCE(pg_sequence_last_value(seq::regclass), 0)`,
+			pgx.Identifier{col.Name}.Sanitize(), qualified)
+
+		if _, err := pool.Exec(ctx, query, qualified, col.Name); err != nil {
+			return fmt.Errorf("%s.%s: %w", table, col.Name, err)
+		}
+	}
+	return nil
+}
+
 // fetchRowsBulk fetches multiple rows from source using a single query.
 func (m *Merger) fetchRowsBulk(ctx context.Context, pool *pgxpool.Pool, schema, table string, columns []columnInfo, pkColumns []string, pkValues []map[string]any) ([][]any, error) {
 	if len(pkValues) == 0 {
@@ -212,23 +289,39 @@ func (r *rowsCopySource) Err() error {
 }
 
 // insertRow inserts a row into a table.
-func (m *Merger) insertRow(ctx context.Context, pool *pgxpool.Pool, schema, table string, row map[string]any) error {
+// Generated columns are skipped, and OVERRIDING SYSTEM VALUE is used when the
+// table has a GENERATED ALWAYS identity so the source key is preserved.
+func (m *Merger) insertRow(ctx context.Context, pool *pgxpool.Pool, schema, table string, columns []columnInfo, row map[string]any) error {
 	var cols []string
 	var placeholders []string
 	var args []any
 
+	skip := make(map[string]bool)
+	overriding := ""
+	for _, c := range columns {
+		if c.Generated {
+			skip[c.Name] = true
+		} else if c.Identity == "ALWAYS" {
+			overriding = " OVERRIDING SYSTEM VALUE"
+		}
+	}
+
 	i := 1
 	for col, val := range row {
+		if skip[col] {
+			continue
+		}
 		cols = append(cols, pgx.Identifier{col}.Sanitize())
 		placeholders = append(placeholders, fmt.Sprintf("$%d", i))
 		args = append(args, val)
 		i++
 	}
 
-	query := fmt.Sprintf("INSERT INTO %s.%s (%s) VALUES (%s) ON CONFLICT DO NOTHING",
+	query := fmt.Sprintf("INSERT INTO %s.%s (%s)%s VALUES (%s) ON CONFLICT DO NOTHING",
 		pgx.Identifier{schema}.Sanitize(),
 		pgx.Identifier{table}.Sanitize(),
 		joinStrings(cols, ", "),
+		overriding,
 		joinStrings(placeholders, ", "))
 
 	_, err := pool.Exec(ctx, query, args...)
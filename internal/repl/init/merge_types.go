@@ -31,6 +31,28 @@ const (
 	StrategyManual       ConflictStrategy = "manual"
 )
 
+// MergeLogLevel selects which row categories a merge writes to merge_audit_log.
+type MergeLogLevel string
+
+const (
+	LogAll           MergeLogLevel = "all"            // Every row, including matches
+	LogConflictsOnly MergeLogLevel = "conflicts_only" // Conflicts only
+	LogChangesOnly   MergeLogLevel = "changes_only"   // Conflicts and transfers (default)
+)
+
+// Logs reports whether rows of the given category are written to the audit
+// log at this level. The empty level behaves as LogChangesOnly.
+func (l MergeLogLevel) Logs(category OverlapCategory) bool {
+	switch l {
+	case LogAll:
+		return true
+	case LogConflictsOnly:
+		return category == CategoryConflict
+	default:
+		return category != CategoryMatch
+	}
+}
+
 // OverlapResult represents the result of comparing a single row.
 type OverlapResult struct {
 	PKValue    map[string]interface{} `json:"pk_value"`
@@ -96,6 +118,9 @@ type MergeResult struct {
 	RowsTransferredAToB int64            `json:"rows_transferred_a_to_b"`
 	RowsTransferredBToA int64            `json:"rows_transferred_b_to_a"`
 	ConflictsResolved   int64            `json:"conflicts_resolved"`
+	ColumnHandling      []ColumnHandling `json:"column_handling,omitempty"`
+	AuditRowsWritten    int64            `json:"audit_rows_written"`
+	AuditTruncated      bool             `json:"audit_truncated"` // MaxAuditRows reached; later decisions were not logged
 	Errors              []string         `json:"errors,omitempty"`
 }
 
@@ -113,6 +138,10 @@ type MergeConfig struct {
 	RemoteServer     string
 	QuiesceTimeoutMs int
 	DryRun           bool
+	MaxAuditRows     int64         // Stop writing merge_audit_log rows after this many (0 = unlimited)
+	LogLevel         MergeLogLevel // Categories written to merge_audit_log (default changes_only)
+	MaxConflicts     int64         // Abort before applying if analysis finds more conflicts (0 = unlimited)
+	MaxConflictRatio float64       // Abort if conflicts exceed this fraction of all analysed rows (0 = unlimited)
 }
 
 // PreflightResult contains the results of pre-flight checks.
@@ -121,6 +150,7 @@ type PreflightResult struct {
 	AllTableshavePK      bool     `json:"all_tables_have_pk"`
 	NoActiveTransactions bool     `json:"no_active_transactions"`
 	TrackCommitTimestamp bool     `json:"track_commit_timestamp"`
+	VersionCompatible    bool     `json:"version_compatible"`
 	Errors               []string `json:"errors,omitempty"`
 	Warnings             []string `json:"warnings,omitempty"`
 }
@@ -135,7 +165,19 @@ type FKDependency struct {
 
 // columnInfo holds column metadata for COPY operations.
 type columnInfo struct {
-	Name     string
-	DataType string
-	Position int
+	Name      string
+	DataType  string
+	Position  int
+	Identity  string // "", "ALWAYS" or "BY DEFAULT"
+	Generated bool   // GENERATED ALWAYS AS (...) STORED
+}
+
+// ColumnHandling records how an identity or generated column was treated
+// when rows were transferred during a merge.
+type ColumnHandling struct {
+	TableSchema string `json:"table_schema"`
+	TableName   string `json:"table_name"`
+	Column      string `json:"column"`
+	Kind        string `json:"kind"`     // "identity_always", "identity_by_default", "generated"
+	Handling    string `json:"handling"` // "overriding_system_value", "preserved", "excluded"
 }
@@ -1,17 +1,23 @@
 package init
 
 import (
+	"bufio"
+	"bytes"
 	"compress/gzip"
 	"context"
+	"encoding/csv"
 	"fmt"
 	"io"
 	"os"
 	"path/filepath"
+	"slices"
 	"sort"
+	"strings"
 	"sync"
 	"sync/atomic"
 	"time"
 
+	"github.com/jackc/pgx/v5"
 	"github.com/jackc/pgx/v5/pgxpool"
 	"github.com/klauspost/compress/zstd"
 	"github.com/pierrec/lz4/v4"
@@ -28,6 +34,55 @@ type TwoPhaseApplyOptions struct {
 	ParallelWorkers    int
 	VerifyChecksums    bool
 	CreateSubscription bool // Create subscription to source after apply
+	// Transactional loads all tables in a single transaction so a failure
+	// rolls every table back, leaving the target's prior data untouched.
+	// Tables are loaded sequentially and stay locked until commit, so this
+	// trades parallelism and lock duration for atomicity. Sequence values
+	// restored afterwards are outside the transaction.
+	Transactional      bool
+	// DeferIndexes drops non-unique secondary indexes before the data phase
+	// and rebuilds them afterwards, with FK constraints dropped and
+	// revalidated around them, since building an index once is far cheaper
+	// than maintaining it per row. Unique indexes stay in place so duplicate
+	// rows are still rejected while loading. Ignored for transactional and
+	// incremental applies, which never drop objects.
+	DeferIndexes       bool
+	// Tables restricts the apply to these tables ("schema.table", or a bare
+	// name for public). Every listed table must be in the manifest. Other
+	// tables are left untouched, except that truncating a parent cascades
+	// to its children; FK relationships crossing the selection are logged.
+	Tables             []string
+	// Incremental upserts snapshot rows by primary key instead of truncating,
+	// so only rows that differ from the target are written. Every table
+	// needs a primary key. Indexes are kept in place.
+	Incremental        bool
+	// DeleteMissing removes target rows whose key is absent from the
+	// snapshot. Only used with Incremental.
+	DeleteMissing      bool
+	// SchemaOnly replays the snapshot's schema.sql (table, index, constraint
+	// and sequence DDL) into the target and loads no data. The target must
+	// not already contain the objects. Tables is ignored.
+	SchemaOnly         bool
+	// AllowSelf permits applying a snapshot onto the node it was taken from,
+	// which otherwise fails because it would overwrite that node's live data.
+	AllowSelf          bool
+	// TargetSchema loads the snapshot's tables into this schema (created if
+	// missing) instead of their own, leaving the originals untouched so the
+	// data can be inspected before a cutover. Each table is created there
+	// like the target's table of the same name, without foreign keys.
+	// Sequences are not restored, the snapshot is not marked applied, and
+	// CreateSubscription is rejected.
+	TargetSchema       string
+	// ForceFormat applies a snapshot whose manifest format_version is newer
+	// than this build understands, which otherwise fails because its files
+	// may be laid out differently.
+	ForceFormat        bool
+	// ColumnMap renames snapshot columns onto target columns, keyed by table
+	// ("schema.table", or a bare name for public) and then by source column
+	// name. Data files are always loaded by column name; unmapped columns
+	// must exist under the same name on the target, and every target column
+	// must be filled.
+	ColumnMap          map[string]map[string]string
 	SourceNodeID       string
 	SourceHost         string
 	SourcePort         int
@@ -65,6 +120,72 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 		return nil, fmt.Errorf("failed to read manifest: %w", err)
 	}
 
+	if manifest.FormatVersion > models.ManifestFormatVersion && !opts.ForceFormat {
+		return nil, fmt.Errorf("snapshot %s has manifest format version %d, but this build only understands up to %d (upgrade, or set ForceFormat to override)",
+			manifest.SnapshotID, manifest.FormatVersion, models.ManifestFormatVersion)
+	}
+
+	if targetNodeID == manifest.SourceNode && !opts.AllowSelf {
+		return nil, fmt.Errorf("snapshot %s was taken from node %s; refusing to apply it onto its own source (set AllowSelf to override)",
+			manifest.SnapshotID, manifest.SourceNode)
+	}
+
+	// A subscription replicates every published table, so it would diverge
+	// from a target that only received part of the snapshot
+	partial := len(opts.Tables) > 0
+	if partial && opts.CreateSubscription {
+		return nil, fmt.Errorf("selective apply cannot create a subscription; apply the full snapshot to subscribe")
+	}
+
+	// Tables loaded into a scratch schema are not the ones a subscription
+	// would replicate into
+	if opts.TargetSchema != "" && opts.CreateSubscription {
+		return nil, fmt.Errorf("apply into target schema %s cannot create a subscription; apply to the original schemas to subscribe", opts.TargetSchema)
+	}
+
+	if opts.SchemaOnly {
+		if err := a.applySchema(ctx, opts.InputPath, manifest); err != nil {
+			return nil, err
+		}
+		return manifest, nil
+	}
+
+	if opts.TargetSchema != "" {
+		if err := a.applyIntoSchema(ctx, opts, manifest); err != nil {
+			return nil, err
+		}
+		return manifest, nil
+	}
+
+	// Recreate partitions missing on the target so leaf data has somewhere to go
+	if err := a.ensurePartitions(ctx, manifest.Partitions); err != nil {
+		return nil, err
+	}
+
+	// Restrict to selected tables
+	if partial {
+		selected, err := selectManifestTables(manifest.Tables, opts.Tables)
+		if err != nil {
+			return nil, err
+		}
+		if allDeps, err := a.getFKDependencies(ctx, manifest.Tables); err == nil {
+			a.warnSelectiveFKRisk(selected, allDeps)
+		}
+		// Reloading truncates with CASCADE, which would empty referencing
+		// tables that are not being restored
+		if !opts.Incremental {
+			if err := a.checkUnselectedDependents(ctx, selected); err != nil {
+				return nil, err
+			}
+		}
+		sequences, err := a.selectOwnedSequences(ctx, manifest.Sequences, selected)
+		if err != nil {
+			return nil, err
+		}
+		manifest.Tables = selected
+		manifest.Sequences = sequences
+	}
+
 	// Get FK dependencies and sort tables in dependency order
 	deps, err := a.getFKDependencies(ctx, manifest.Tables)
 	if err != nil {
@@ -110,6 +231,9 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 			"sequences":        len(manifest.Sequences),
 			"parallel_workers": opts.ParallelWorkers,
 			"verify_checksums": opts.VerifyChecksums,
+			"transactional":    opts.Transactional,
+			"defer_indexes":    opts.DeferIndexes,
+			"incremental":      opts.Incremental,
 		},
 	})
 
@@ -151,6 +275,8 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 	// Strategy depends on parallel workers:
 	// - Parallel (workers > 1): Drop FK constraints → parallel import → recreate constraints
 	// - Sequential (workers = 1): Topological sort → import in dependency order
+	// With DeferIndexes, non-unique secondary indexes are dropped before import
+	// and rebuilt after, and FK constraints are dropped so they validate once.
 	a.sendProgress(opts.ProgressFn, TwoPhaseProgress{
 		SnapshotID:     manifest.SnapshotID,
 		Phase:          "importing",
@@ -161,8 +287,19 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 	workers := max(opts.ParallelWorkers, 1)
 	var totalRowsImported int64
 	var droppedConstraints []droppedConstraint
+	var droppedIndexes []droppedIndex
+	deferIndexes := opts.DeferIndexes && !opts.Transactional && !opts.Incremental
+	columnMaps, err := normalizeColumnMap(opts.ColumnMap, manifest.Tables)
+	if err != nil {
+		return nil, err
+	}
+	mode := tableLoadMode{
+		Incremental:   opts.Incremental,
+		DeleteMissing: opts.Incremental && opts.DeleteMissing,
+		ColumnMaps:    columnMaps,
+	}
 
-	if workers > 1 && len(deps) > 0 {
+	if (workers > 1 || deferIndexes) && len(deps) > 0 && !opts.Transactional {
 		// Parallel mode with FK dependencies: drop constraints first
 		a.logger.Log(InitEvent{
 			Level: "info",
@@ -178,8 +315,18 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 		}
 	}
 
+	if deferIndexes {
+		droppedIndexes, err = a.dropIndexes(ctx, manifest.Tables)
+		if err != nil {
+			// Restore anything already dropped so the target stays usable
+			_ = a.recreateIndexes(ctx, droppedIndexes)
+			_ = a.recreateFKConstraints(ctx, droppedConstraints)
+			return nil, fmt.Errorf("failed to drop indexes: %w", err)
+		}
+	}
+
 	// Import tables
-	totalRowsImported, err = a.importTablesParallel(ctx, manifest.Tables, opts.InputPath, manifest.Compression, workers, func(completed int, current string, bytes int64) {
+	importProgress := func(completed int, current string, bytes int64) {
 		percent := float32(10 + (completed * 75 / len(manifest.Tables)))
 		a.sendProgress(opts.ProgressFn, TwoPhaseProgress{
 			SnapshotID:     manifest.SnapshotID,
@@ -189,17 +336,55 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 			BytesProcessed: bytes,
 			LSN:            manifest.LSN,
 		})
-	})
+	}
+	if opts.Transactional {
+		totalRowsImported, err = a.importTablesTransactional(ctx, manifest.Tables, opts.InputPath, manifest.Compression, mode, importProgress)
+	} else {
+		totalRowsImported, err = a.importTablesParallel(ctx, manifest.Tables, opts.InputPath, manifest.Compression, mode, workers, importProgress)
+	}
 	if err != nil {
-		// Try to recreate constraints even on error
+		// Try to recreate indexes and constraints even on error
+		if len(droppedIndexes) > 0 {
+			_ = a.recreateIndexes(ctx, droppedIndexes)
+		}
 		if len(droppedConstraints) > 0 {
 			_ = a.recreateFKConstraints(ctx, droppedConstraints)
 		}
 		return nil, err
 	}
 
+	// Rebuild indexes if we dropped them
+	if len(droppedIndexes) > 0 {
+		a.sendProgress(opts.ProgressFn, TwoPhaseProgress{
+			SnapshotID:     manifest.SnapshotID,
+			Phase:          "indexes",
+			OverallPercent: 80,
+			LSN:            manifest.LSN,
+		})
+
+		a.logger.Log(InitEvent{
+			Level: "info",
+			Event: "snapshot.recreating_indexes",
+			Details: map[string]any{
+				"index_count": len(droppedIndexes),
+			},
+		})
+
+		if err := a.recreateIndexes(ctx, droppedIndexes); err != nil {
+			_ = a.recreateFKConstraints(ctx, droppedConstraints)
+			return nil, fmt.Errorf("failed to recreate indexes: %w", err)
+		}
+	}
+
 	// Recreate FK constraints if we dropped them
 	if len(droppedConstraints) > 0 {
+		a.sendProgress(opts.ProgressFn, TwoPhaseProgress{
+			SnapshotID:     manifest.SnapshotID,
+			Phase:          "constraints",
+			OverallPercent: 83,
+			LSN:            manifest.LSN,
+		})
+
 		a.logger.Log(InitEvent{
 			Level: "info",
 			Event: "snapshot.recreating_fk_constraints",
@@ -247,13 +432,17 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 		LSN:            manifest.LSN,
 	})
 
-	if err := a.markSnapshotApplied(ctx, manifest.SnapshotID, targetNodeID); err != nil {
-		a.logger.Log(InitEvent{
-			Level: "warn",
-			Event: "snapshot.mark_applied_failed",
-			Error: err.Error(),
-		})
-		// Non-fatal, continue
+	// A partial apply leaves the target short of the snapshot, so it is not
+	// recorded as applied
+	if !partial {
+		if err := a.markSnapshotApplied(ctx, manifest.SnapshotID, targetNodeID); err != nil {
+			a.logger.Log(InitEvent{
+				Level: "warn",
+				Event: "snapshot.mark_applied_failed",
+				Error: err.Error(),
+			})
+			// Non-fatal, continue
+		}
 	}
 
 	duration := time.Since(startTime)
@@ -282,8 +471,479 @@ func (a *SnapshotApplier) Apply(ctx context.Context, targetNodeID string, opts T
 	return manifest, nil
 }
 
+// applySchema replays the snapshot's schema DDL into the target. The file is
+// sent as one multi-statement query, so it applies atomically.
+func (a *SnapshotApplier) applySchema(ctx context.Context, inputPath string, manifest *models.SnapshotManifest) error {
+	if manifest.SchemaFile == "" {
+		return fmt.Errorf("snapshot %s has no schema file", manifest.SnapshotID)
+	}
+
+	ddl, err := os.ReadFile(filepath.Join(inputPath, manifest.SchemaFile))
+	if err != nil {
+		return fmt.Errorf("failed to read schema file: %w", err)
+	}
+
+	if _, err := a.pool.Exec(ctx, string(ddl)); err != nil {
+		return fmt.Errorf("failed to apply schema: %w", err)
+	}
+
+	a.logger.Log(InitEvent{
+		Level: "info",
+		Event: "snapshot.schema_applied",
+		Details: map[string]any{
+			"snapshot_id": manifest.SnapshotID,
+			"file":        manifest.SchemaFile,
+		},
+	})
+
+	return nil
+}
+
+// applyIntoSchema loads the snapshot's tables into opts.TargetSchema rather
+// than their own schemas. Tables from every source schema land side by side,
+// so their names must be unique. Existing scratch tables are reused and
+// replaced wholesale.
+func (a *SnapshotApplier) applyIntoSchema(ctx context.Context, opts TwoPhaseApplyOptions, manifest *models.SnapshotManifest) error {
+	tables := manifest.Tables
+	if len(opts.Tables) > 0 {
+		selected, err := selectManifestTables(tables, opts.Tables)
+		if err != nil {
+			return err
+		}
+		tables = selected
+	}
+	columnMaps, err := normalizeColumnMap(opts.ColumnMap, tables)
+	if err != nil {
+		return err
+	}
+
+	if _, err := a.pool.Exec(ctx, "CREATE SCHEMA IF NOT EXISTS "+pgx.Identifier{opts.TargetSchema}.Sanitize()); err != nil {
+		return fmt.Errorf("failed to create schema %s: %w", opts.TargetSchema, err)
+	}
+
+	mode := tableLoadMode{ColumnMaps: make(map[string]map[string]string, len(columnMaps))}
+	redirected := make([]models.SnapshotTableEntry, 0, len(tables))
+	sources := make(map[string]string, len(tables))
+	for _, entry := range tables {
+		if entry.Schema == opts.TargetSchema {
+			return fmt.Errorf("target schema %s holds %s itself; choose a scratch schema", opts.TargetSchema, entry.FullTableName())
+		}
+		if other, ok := sources[entry.Name]; ok {
+			return fmt.Errorf("tables %s and %s would both load into %s.%s", other, entry.FullTableName(), opts.TargetSchema, entry.Name)
+		}
+		sources[entry.Name] = entry.FullTableName()
+
+		scratch := entry
+		scratch.Schema = opts.TargetSchema
+		createSQL := fmt.Sprintf("CREATE TABLE IF NOT EXISTS %s (LIKE %s INCLUDING ALL)",
+			pgx.Identifier{scratch.Schema, scratch.Name}.Sanitize(), pgx.Identifier{entry.Schema, entry.Name}.Sanitize())
+		if _, err := a.pool.Exec(ctx, createSQL); err != nil {
+			return fmt.Errorf("failed to create %s: %w", scratch.FullTableName(), err)
+		}
+		if columns, ok := columnMaps[entry.FullTableName()]; ok {
+			mode.ColumnMaps[scratch.FullTableName()] = columns
+		}
+		redirected = append(redirected, scratch)
+	}
+
+	rows, err := a.importTablesParallel(ctx, redirected, opts.InputPath, manifest.Compression, mode, max(opts.ParallelWorkers, 1), nil)
+	if err != nil {
+		return err
+	}
+
+	a.logger.Log(InitEvent{
+		Level: "info",
+		Event: "snapshot.applied_to_schema",
+		Details: map[string]any{
+			"snapshot_id":   manifest.SnapshotID,
+			"schema":        opts.TargetSchema,
+			"tables":        len(redirected),
+			"rows_imported": rows,
+		},
+	})
+
+	return nil
+}
+
+// ensurePartitions creates partitions recorded in the manifest that do not
+// exist on the target. Partitioned roots are not created here; they come from
+// the target schema or the snapshot's schema.sql.
+func (a *SnapshotApplier) ensurePartitions(ctx context.Context, partitions []models.SnapshotPartition) error {
+	for _, p := range partitions {
+		table := pgx.Identifier{p.Schema, p.Name}.Sanitize()
+
+		var exists bool
+		if err := a.pool.QueryRow(ctx, "SELECT to_regclass($1) IS NOT NULL", table).Scan(&exists); err != nil {
+			return fmt.Errorf("check partition %s: %w", p.FullTableName(), err)
+		}
+		if exists {
+			continue
+		}
+		if p.ParentName == "" {
+			return fmt.Errorf("partitioned table %s does not exist on target; apply the snapshot schema first", p.FullTableName())
+		}
+
+		createSQL := fmt.Sprintf("CREATE TABLE %s PARTITION OF %s %s",
+			table, pgx.Identifier{p.ParentSchema, p.ParentName}.Sanitize(), p.Bound)
+		if p.PartitionKey != "" {
+			createSQL += " PARTITION BY " + p.PartitionKey
+		}
+		if _, err := a.pool.Exec(ctx, createSQL); err != nil {
+			return fmt.Errorf("create partition %s: %w", p.FullTableName(), err)
+		}
+
+		a.logger.Log(InitEvent{
+			Level: "info",
+			Event: "snapshot.partition_created",
+			Details: map[string]any{
+				"partition": p.FullTableName(),
+				"parent":    p.ParentSchema + "." + p.ParentName,
+				"bound":     p.Bound,
+			},
+		})
+	}
+
+	return nil
+}
+
+// selectManifestTables returns the manifest entries named in names, in manifest order.
+// Unqualified names are taken to be in the public schema.
+func selectManifestTables(tables []models.SnapshotTableEntry, names []string) ([]models.SnapshotTableEntry, error) {
+	wanted := make(map[string]bool, len(names))
+	for _, name := range names {
+		if !strings.Contains(name, ".") {
+			name = "public." + name
+		}
+		wanted[name] = true
+	}
+
+	var selected []models.SnapshotTableEntry
+	for _, entry := range tables {
+		if wanted[entry.FullTableName()] {
+			selected = append(selected, entry)
+			delete(wanted, entry.FullTableName())
+		}
+	}
+
+	if len(wanted) > 0 {
+		missing := make([]string, 0, len(wanted))
+		for name := range wanted {
+			missing = append(missing, name)
+		}
+		sort.Strings(missing)
+		return nil, fmt.Errorf("tables not in snapshot: %s", strings.Join(missing, ", "))
+	}
+
+	return selected, nil
+}
+
+// warnSelectiveFKRisk logs FK relationships that cross the selected table set.
+// A selected child may reference parent rows that are not restored.
+func (a *SnapshotApplier) warnSelectiveFKRisk(selected []models.SnapshotTableEntry, deps []FKDependency) {
+	inSelection := make(map[string]bool, len(selected))
+	for _, t := range selected {
+		inSelection[t.FullTableName()] = true
+	}
+
+	for _, dep := range deps {
+		child := dep.ChildSchema + "." + dep.ChildTable
+		parent := dep.ParentSchema + "." + dep.ParentTable
+		if inSelection[child] == inSelection[parent] {
+			continue
+		}
+
+		a.logger.Log(InitEvent{
+			Level: "warn",
+			Event: "snapshot.selective_fk_risk",
+			Details: map[string]any{
+				"child":           child,
+				"parent":          parent,
+				"child_selected":  inSelection[child],
+				"parent_selected": inSelection[parent],
+			},
+		})
+	}
+}
+
+// checkUnselectedDependents refuses a selective reload when a selected table
+// is referenced by a table outside the selection, since the reload's
+// TRUNCATE CASCADE would empty it.
+func (a *SnapshotApplier) checkUnselectedDependents(ctx context.Context, selected []models.SnapshotTableEntry) error {
+	names := make([]string, 0, len(selected))
+	for _, t := range selected {
+		names = append(names, t.FullTableName())
+	}
+
+	rows, err := a.pool.Query(ctx, `
+		SELECT DISTINCT
+			cn.nspname || '.' || c.relname,
+			pn.nspname || '.' || p.relname
+		FROM pg_constraint k
+		JOIN pg_class c ON c.oid = k.conrelid
+		JOIN pg_namespace cn ON cn.oid = c.relnamespace
+		JOIN pg_class p ON p.oid = k.confrelid
+		JOIN pg_namespace pn ON pn.oid = p.relnamespace
+		WHERE k.contype = 'f'
+			AND pn.nspname || '.' || p.relname = ANY($1)
+			AND NOT (cn.nspname || '.' || c.relname = ANY($1))
+		ORDER BY 1, 2
+	`, names)
+	if err != nil {
+		return fmt.Errorf("get dependent tables: %w", err)
+	}
+	defer rows.Close()
+
+	var conflicts []string
+	for rows.Next() {
+		var child, parent string
+		if err := rows.Scan(&child, &parent); err != nil {
+			return err
+		}
+		conflicts = append(conflicts, fmt.Sprintf("%s references %s", child, parent))
+	}
+	if err := rows.Err(); err != nil {
+		return err
+	}
+
+	if len(conflicts) > 0 {
+		return fmt.Errorf("selected tables have unselected dependents that reloading would truncate (%s); select them too or use Incremental",
+			strings.Join(conflicts, ", "))
+	}
+	return nil
+}
+
+// selectOwnedSequences keeps the snapshot sequences owned by the selected
+// tables (serial and identity columns), so a selective apply leaves other
+// sequences alone.
+func (a *SnapshotApplier) selectOwnedSequences(ctx context.Context, sequences []models.SnapshotSequenceEntry, selected []models.SnapshotTableEntry) ([]models.SnapshotSequenceEntry, error) {
+	if len(sequences) == 0 {
+		return nil, nil
+	}
+
+	names := make([]string, 0, len(selected))
+	for _, t := range selected {
+		names = append(names, t.FullTableName())
+	}
+
+	rows, err := a.pool.Query(ctx, `
+		SELECT sn.nspname || '.' || s.relname
+		FROM pg_depend d
+		JOIN pg_class s ON s.oid = d.objid AND s.relkind = 'S'
+		JOIN pg_namespace sn ON sn.oid = s.relnamespace
+		JOIN pg_class t ON t.oid = d.refobjid
+		JOIN pg_namespace tn ON tn.oid = t.relnamespace
+		WHERE d.classid = 'pg_class'::regclass
+			AND d.refclassid = 'pg_class'::regclass
+			AND d.deptype IN ('a', 'i')
+			AND tn.nspname || '.' || t.relname = ANY($1)
+	`, names)
+	if err != nil {
+		return nil, fmt.Errorf("get owned sequences: %w", err)
+	}
+	defer rows.Close()
+
+	owned := make(map[string]bool)
+	for rows.Next() {
+		var name string
+		if err := rows.Scan(&name); err != nil {
+			return nil, err
+		}
+		owned[name] = true
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	var kept []models.SnapshotSequenceEntry
+	for _, seq := range sequences {
+		if owned[seq.FullSequenceName()] {
+			kept = append(kept, seq)
+		}
+	}
+	return kept, nil
+}
+
+// tableLoadMode selects how snapshot rows are written into a table.
+type tableLoadMode struct {
+	Incremental   bool // upsert by primary key instead of truncate and reload
+	DeleteMissing bool // with Incremental, delete rows not in the snapshot
+	// ColumnMaps renames file columns per table, keyed by schema.table
+	ColumnMaps    map[string]map[string]string
+}
+
+// normalizeColumnMap qualifies the table keys of a column map with public
+// where needed and checks that every mapped table is in the snapshot.
+func normalizeColumnMap(columnMap map[string]map[string]string, tables []models.SnapshotTableEntry) (map[string]map[string]string, error) {
+	if len(columnMap) == 0 {
+		return nil, nil
+	}
+
+	normalized := make(map[string]map[string]string, len(columnMap))
+	for name, columns := range columnMap {
+		if !strings.Contains(name, ".") {
+			name = "public." + name
+		}
+		normalized[name] = columns
+	}
+
+	var missing []string
+	for name := range normalized {
+		if !slices.ContainsFunc(tables, func(t models.SnapshotTableEntry) bool { return t.FullTableName() == name }) {
+			missing = append(missing, name)
+		}
+	}
+	if len(missing) > 0 {
+		sort.Strings(missing)
+		return nil, fmt.Errorf("column map names tables not in snapshot: %s", strings.Join(missing, ", "))
+	}
+
+	return normalized, nil
+}
+
+// copyColumns reads the CSV header from reader and resolves each file column
+// to a target column, renaming through columnMap (source name to target
+// name). Every file column must land on a distinct target column and every
+// target column must be filled, so a renamed or added column fails the load
+// instead of shifting data positionally. The returned reader still yields
+// the header line, and the columns are in file order.
+func copyColumns(ctx context.Context, conn *pgx.Conn, entry models.SnapshotTableEntry, reader io.Reader, columnMap map[string]string) (io.Reader, []string, error) {
+	table := pgx.Identifier{entry.Schema, entry.Name}.Sanitize()
+
+	// Generated columns are never exported
+	var targetColumns []string
+	err := conn.QueryRow(ctx, `
+		SELECT array_agg(a.attname::text ORDER BY a.attnum)
+		FROM pg_attribute a
+		WHERE a.attrelid = $1::regclass
+		  AND a.attnum > 0
+		  AND NOT a.attisdropped
+		  AND a.attgenerated = ''`, table).Scan(&targetColumns)
+	if err != nil {
+		return nil, nil, fmt.Errorf("failed to read columns: %w", err)
+	}
+
+	buffered := bufio.NewReader(reader)
+	headerLine, err := buffered.ReadString('\n')
+	if err != nil && err != io.EOF {
+		return nil, nil, fmt.Errorf("failed to read CSV header: %w", err)
+	}
+	if headerLine == "" {
+		// Empty file: nothing to load, COPY just sees end of input
+		return buffered, targetColumns, nil
+	}
+	header, err := csv.NewReader(strings.NewReader(headerLine)).Read()
+	if err != nil {
+		return nil, nil, fmt.Errorf("failed to parse CSV header: %w", err)
+	}
+
+	for source := range columnMap {
+		if !slices.Contains(header, source) {
+			return nil, nil, fmt.Errorf("column map for %s names column %s, which is not in the snapshot", entry.FullTableName(), source)
+		}
+	}
+
+	columns := make([]string, len(header))
+	filled := make(map[string]bool, len(header))
+	for i, source := range header {
+		target := source
+		if mapped, ok := columnMap[source]; ok {
+			target = mapped
+		}
+		if !slices.Contains(targetColumns, target) {
+			return nil, nil, fmt.Errorf("snapshot column %s of %s has no target column %s (add it to ColumnMap)", source, entry.FullTableName(), target)
+		}
+		if filled[target] {
+			return nil, nil, fmt.Errorf("target column %s of %s is mapped more than once", target, entry.FullTableName())
+		}
+		filled[target] = true
+		columns[i] = target
+	}
+
+	for _, target := range targetColumns {
+		if !filled[target] {
+			return nil, nil, fmt.Errorf("target column %s of %s is not in the snapshot (add it to ColumnMap)", target, entry.FullTableName())
+		}
+	}
+
+	return io.MultiReader(strings.NewReader(headerLine), buffered), columns, nil
+}
+
+// quoteIdentifiers sanitizes each name and joins them with commas, adding
+// prefix (such as a table alias) before each one.
+func quoteIdentifiers(prefix string, names []string) string {
+	parts := make([]string, len(names))
+	for i, n := range names {
+		parts[i] = prefix + pgx.Identifier{n}.Sanitize()
+	}
+	return strings.Join(parts, ", ")
+}
+
 // importTable imports a single table from a CSV file using COPY.
-func (a *SnapshotApplier) importTable(ctx context.Context, entry models.SnapshotTableEntry, inputPath string, compression models.CompressionType) (int64, error) {
+func (a *SnapshotApplier) importTable(ctx context.Context, entry models.SnapshotTableEntry, inputPath string, compression models.CompressionType, mode tableLoadMode) (int64, error) {
+	conn, err := a.pool.Acquire(ctx)
+	if err != nil {
+		return 0, fmt.Errorf("failed to acquire connection: %w", err)
+	}
+	defer conn.Release()
+
+	return a.importTableOnConn(ctx, conn.Conn(), entry, inputPath, compression, mode)
+}
+
+// importTablesTransactional imports all tables sequentially inside one
+// transaction. Any failure rolls back every table imported so far.
+func (a *SnapshotApplier) importTablesTransactional(
+	ctx context.Context,
+	tables []models.SnapshotTableEntry,
+	inputPath string,
+	compression models.CompressionType,
+	mode tableLoadMode,
+	progressFn func(completed int, current string, bytes int64),
+) (int64, error) {
+	conn, err := a.pool.Acquire(ctx)
+	if err != nil {
+		return 0, fmt.Errorf("failed to acquire connection: %w", err)
+	}
+	defer conn.Release()
+
+	tx, err := conn.Begin(ctx)
+	if err != nil {
+		return 0, fmt.Errorf("failed to begin transaction: %w", err)
+	}
+	defer tx.Rollback(ctx)
+
+	var totalRows, totalBytes int64
+	for i, entry := range tables {
+		rows, err := a.importTableOnConn(ctx, tx.Conn(), entry, inputPath, compression, mode)
+		if err != nil {
+			a.logger.Log(InitEvent{
+				Level: "warn",
+				Event: "snapshot.transactional_rollback",
+				Details: map[string]any{
+					"table":           entry.FullTableName(),
+					"tables_imported": i,
+				},
+				Error: err.Error(),
+			})
+			return 0, fmt.Errorf("import %s: %w", entry.FullTableName(), err)
+		}
+		totalRows += rows
+		totalBytes += entry.SizeBytes
+		if progressFn != nil {
+			progressFn(i+1, entry.FullTableName(), totalBytes)
+		}
+	}
+
+	if err := tx.Commit(ctx); err != nil {
+		return 0, fmt.Errorf("failed to commit transaction: %w", err)
+	}
+
+	return totalRows, nil
+}
+
+// importTableOnConn truncates and loads a single table on the given
+// connection, which may be inside a transaction. In incremental mode the
+// table is upserted instead; see mergeTableOnConn.
+func (a *SnapshotApplier) importTableOnConn(ctx context.Context, conn *pgx.Conn, entry models.SnapshotTableEntry, inputPath string, compression models.CompressionType, mode tableLoadMode) (int64, error) {
 	filePath := filepath.Join(inputPath, entry.File)
 
 	// Open the file
@@ -293,6 +953,25 @@ func (a *SnapshotApplier) importTable(ctx context.Context, entry models.Snapshot
 	}
 	defer file.Close()
 
+	// Trust the file contents over the manifest: files may have been moved
+	// by hand or the manifest may omit the compression field.
+	detected, err := DetectCompression(file)
+	if err != nil {
+		return 0, fmt.Errorf("failed to detect compression of %s: %w", filePath, err)
+	}
+	if compression != "" && compression != detected {
+		a.logger.Log(InitEvent{
+			Level: "warn",
+			Event: "snapshot.compression_mismatch",
+			Details: map[string]any{
+				"file":     entry.File,
+				"manifest": string(compression),
+				"detected": string(detected),
+			},
+		})
+	}
+	compression = detected
+
 	// Set up reader based on compression type
 	var reader io.Reader = file
 	var decompressCloser io.Closer
@@ -323,24 +1002,27 @@ func (a *SnapshotApplier) importTable(ctx context.Context, entry models.Snapshot
 		defer decompressCloser.Close()
 	}
 
+	reader, columns, err := copyColumns(ctx, conn, entry, reader, mode.ColumnMaps[entry.FullTableName()])
+	if err != nil {
+		return 0, err
+	}
+
+	if mode.Incremental {
+		return a.mergeTableOnConn(ctx, conn, entry, reader, columns, mode.DeleteMissing)
+	}
+
 	// Truncate target table before import
 	truncateSQL := fmt.Sprintf("TRUNCATE %s.%s CASCADE", entry.Schema, entry.Name)
-	_, err = a.pool.Exec(ctx, truncateSQL)
+	_, err = conn.Exec(ctx, truncateSQL)
 	if err != nil {
 		return 0, fmt.Errorf("failed to truncate table: %w", err)
 	}
 
 	// Use COPY FROM to import the data
-	conn, err := a.pool.Acquire(ctx)
-	if err != nil {
-		return 0, fmt.Errorf("failed to acquire connection: %w", err)
-	}
-	defer conn.Release()
-
 	tableName := fmt.Sprintf("%s.%s", entry.Schema, entry.Name)
-	copySQL := fmt.Sprintf("COPY %s FROM STDIN WITH (FORMAT csv, HEADER true)", tableName)
+	copySQL := fmt.Sprintf("COPY %s (%s) FROM STDIN WITH (FORMAT csv, HEADER true)", tableName, quoteIdentifiers("", columns))
 
-	tag, err := conn.Conn().PgConn().CopyFrom(ctx, reader, copySQL)
+	tag, err := conn.PgConn().CopyFrom(ctx, reader, copySQL)
 	if err != nil {
 		return 0, fmt.Errorf("COPY FROM failed: %w", err)
 	}
@@ -358,6 +1040,132 @@ func (a *SnapshotApplier) importTable(ctx context.Context, entry models.Snapshot
 	return tag.RowsAffected(), nil
 }
 
+// mergeTableOnConn upserts the snapshot rows from reader into the table by
+// primary key, writing only rows that are new or differ. columns are the
+// target columns in file order. With deleteMissing, target rows absent from
+// the snapshot are removed. Returns rows changed.
+func (a *SnapshotApplier) mergeTableOnConn(ctx context.Context, conn *pgx.Conn, entry models.SnapshotTableEntry, reader io.Reader, columns []string, deleteMissing bool) (int64, error) {
+	table := pgx.Identifier{entry.Schema, entry.Name}.Sanitize()
+
+	var pkColumns []string
+	err := conn.QueryRow(ctx, `
+		SELECT array_agg(a.attname::text ORDER BY a.attnum)
+		FROM pg_attribute a
+		JOIN pg_index i ON i.indrelid = a.attrelid AND i.indisprimary
+		WHERE a.attrelid = $1::regclass
+		  AND a.attnum = ANY(i.indkey)`, table).Scan(&pkColumns)
+	if err != nil {
+		return 0, fmt.Errorf("failed to read primary key: %w", err)
+	}
+	if len(pkColumns) == 0 {
+		return 0, fmt.Errorf("incremental apply requires a primary key on %s", entry.FullTableName())
+	}
+
+	colList := quoteIdentifiers("", columns)
+
+	// Stage the snapshot rows in a session temp table
+	if _, err := conn.Exec(ctx, "DROP TABLE IF EXISTS pg_temp.steep_incremental"); err != nil {
+		return 0, fmt.Errorf("failed to drop staging table: %w", err)
+	}
+	if _, err := conn.Exec(ctx, fmt.Sprintf(
+		"CREATE TEMP TABLE steep_incremental AS SELECT %s FROM %s WITH NO DATA", colList, table)); err != nil {
+		return 0, fmt.Errorf("failed to create staging table: %w", err)
+	}
+	defer conn.Exec(context.WithoutCancel(ctx), "DROP TABLE IF EXISTS pg_temp.steep_incremental")
+
+	staged, err := conn.PgConn().CopyFrom(ctx, reader, "COPY steep_incremental FROM STDIN WITH (FORMAT csv, HEADER true)")
+	if err != nil {
+		return 0, fmt.Errorf("COPY FROM failed: %w", err)
+	}
+
+	// Upsert, skipping rows that are already identical
+	var nonKey []string
+	for _, c := range columns {
+		if !slices.Contains(pkColumns, c) {
+			nonKey = append(nonKey, c)
+		}
+	}
+	onConflict := "DO NOTHING"
+	if len(nonKey) > 0 {
+		sets := make([]string, len(nonKey))
+		for i, c := range nonKey {
+			ident := pgx.Identifier{c}.Sanitize()
+			sets[i] = ident + " = EXCLUDED." + ident
+		}
+		onConflict = fmt.Sprintf("DO UPDATE SET %s WHERE (%s) IS DISTINCT FROM (%s)",
+			strings.Join(sets, ", "), quoteIdentifiers("t.", nonKey), quoteIdentifiers("EXCLUDED.", nonKey))
+	}
+	tag, err := conn.Exec(ctx, fmt.Sprintf(
+		"INSERT INTO %s AS t (%s) OVERRIDING SYSTEM VALUE SELECT %s FROM steep_incremental ON CONFLICT (%s) %s",
+		table, colList, colList, quoteIdentifiers("", pkColumns), onConflict))
+	if err != nil {
+		return 0, fmt.Errorf("failed to upsert rows: %w", err)
+	}
+	upserted := tag.RowsAffected()
+
+	var deleted int64
+	if deleteMissing {
+		match := make([]string, len(pkColumns))
+		for i, c := range pkColumns {
+			ident := pgx.Identifier{c}.Sanitize()
+			match[i] = "s." + ident + " = t." + ident
+		}
+		tag, err := conn.Exec(ctx, fmt.Sprintf(
+			"DELETE FROM %s t WHERE NOT EXISTS (SELECT 1 FROM steep_incremental s WHERE %s)",
+			table, strings.Join(match, " AND ")))
+		if err != nil {
+			return 0, fmt.Errorf("failed to delete missing rows: %w", err)
+		}
+		deleted = tag.RowsAffected()
+	}
+
+	a.logger.Log(InitEvent{
+		Level: "debug",
+		Event: "snapshot.table_merged",
+		Details: map[string]any{
+			"table":    entry.FullTableName(),
+			"staged":   staged.RowsAffected(),
+			"upserted": upserted,
+			"deleted":  deleted,
+		},
+	})
+
+	return upserted + deleted, nil
+}
+
+// Magic bytes identifying compressed snapshot data files.
+var (
+	gzipMagic = []byte{0x1f, 0x8b}
+	lz4Magic  = []byte{0x04, 0x22, 0x4d, 0x18}
+	zstdMagic = []byte{0x28, 0xb5, 0x2f, 0xfd}
+)
+
+// DetectCompression identifies the codec of a snapshot data file from its
+// magic bytes, returning CompressionNone for plain CSV. The reader is
+// rewound to the start before returning.
+func DetectCompression(r io.ReadSeeker) (models.CompressionType, error) {
+	header := make([]byte, 4)
+	n, err := io.ReadFull(r, header)
+	if err != nil && err != io.ErrUnexpectedEOF && err != io.EOF {
+		return "", err
+	}
+	if _, err := r.Seek(0, io.SeekStart); err != nil {
+		return "", err
+	}
+	header = header[:n]
+
+	switch {
+	case bytes.HasPrefix(header, gzipMagic):
+		return models.CompressionGzip, nil
+	case bytes.HasPrefix(header, lz4Magic):
+		return models.CompressionLZ4, nil
+	case bytes.HasPrefix(header, zstdMagic):
+		return models.CompressionZstd, nil
+	default:
+		return models.CompressionNone, nil
+	}
+}
+
 // importTableResult holds the result of importing a single table.
 type importTableResult struct {
 	tableName    string
@@ -372,6 +1180,7 @@ func (a *SnapshotApplier) importTablesParallel(
 	tables []models.SnapshotTableEntry,
 	inputPath string,
 	compression models.CompressionType,
+	mode tableLoadMode,
 	workers int,
 	progressFn func(completed int, current string, bytes int64),
 ) (int64, error) {
@@ -409,7 +1218,7 @@ func (a *SnapshotApplier) importTablesParallel(
 				default:
 				}
 
-				rowsImported, err := a.importTable(ctx, table, inputPath, compression)
+				rowsImported, err := a.importTable(ctx, table, inputPath, compression, mode)
 				if err != nil {
 					resultChan <- importTableResult{
 						tableName: table.FullTableName(),
@@ -589,6 +1398,8 @@ func (a *SnapshotApplier) dropFKConstraints(ctx context.Context, tables []models
 		JOIN pg_namespace ns ON ns.oid = pgc.connamespace AND ns.nspname = tc.constraint_schema
 		WHERE tc.constraint_type = 'FOREIGN KEY'
 			AND tc.table_schema || '.' || tc.table_name = ANY($1)
+			AND pgc.conrelid = format('%I.%I', tc.table_schema, tc.table_name)::regclass
+			AND pgc.conparentid = 0 -- inherited from a partitioned parent; can't be dropped alone
 		ORDER BY tc.table_schema, tc.table_name, tc.constraint_name
 	`
 
@@ -660,6 +1471,108 @@ func (a *SnapshotApplier) recreateFKConstraints(ctx context.Context, constraints
 	return nil
 }
 
+// droppedIndex stores a secondary index definition for rebuilding after import.
+type droppedIndex struct {
+	Schema     string
+	Table      string
+	IndexName  string
+	Definition string // Full CREATE INDEX statement
+}
+
+// dropIndexes drops secondary indexes on the given tables, returning info to recreate them.
+// Unique indexes and indexes backing PK, unique, or exclusion constraints are kept.
+func (a *SnapshotApplier) dropIndexes(ctx context.Context, tables []models.SnapshotTableEntry) ([]droppedIndex, error) {
+	query := `
+		SELECT
+			i.schemaname,
+			i.tablename,
+			i.indexname,
+			i.indexdef
+		FROM pg_indexes i
+		WHERE i.schemaname || '.' || i.tablename = ANY($1)
+			AND NOT EXISTS (
+				SELECT 1 FROM pg_constraint con
+				WHERE con.conindid = format('%I.%I', i.schemaname, i.indexname)::regclass
+			)
+			AND NOT EXISTS (
+				-- unique indexes must reject duplicates during the load
+				SELECT 1 FROM pg_index x
+				WHERE x.indexrelid = format('%I.%I', i.schemaname, i.indexname)::regclass
+					AND x.indisunique
+			)
+			AND NOT EXISTS (
+				-- partitions of a parent index can't be dropped alone
+				SELECT 1 FROM pg_inherits inh
+				WHERE inh.inhrelid = format('%I.%I', i.schemaname, i.indexname)::regclass
+			)
+		ORDER BY i.schemaname, i.tablename, i.indexname
+	`
+
+	var fullNames []string
+	for _, t := range tables {
+		fullNames = append(fullNames, fmt.Sprintf("%s.%s", t.Schema, t.Name))
+	}
+
+	rows, err := a.pool.Query(ctx, query, fullNames)
+	if err != nil {
+		return nil, fmt.Errorf("query indexes: %w", err)
+	}
+	defer rows.Close()
+
+	var indexes []droppedIndex
+	for rows.Next() {
+		var idx droppedIndex
+		if err := rows.Scan(&idx.Schema, &idx.Table, &idx.IndexName, &idx.Definition); err != nil {
+			return nil, err
+		}
+		indexes = append(indexes, idx)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	// Drop each index
+	for i, idx := range indexes {
+		dropSQL := fmt.Sprintf("DROP INDEX %s", pgx.Identifier{idx.Schema, idx.IndexName}.Sanitize())
+		if _, err := a.pool.Exec(ctx, dropSQL); err != nil {
+			return indexes[:i], fmt.Errorf("drop index %s.%s: %w", idx.Schema, idx.IndexName, err)
+		}
+
+		a.logger.Log(InitEvent{
+			Level: "debug",
+			Event: "snapshot.index_dropped",
+			Details: map[string]any{
+				"schema": idx.Schema,
+				"table":  idx.Table,
+				"index":  idx.IndexName,
+			},
+		})
+	}
+
+	return indexes, nil
+}
+
+// recreateIndexes rebuilds previously dropped indexes.
+func (a *SnapshotApplier) recreateIndexes(ctx context.Context, indexes []droppedIndex) error {
+	for _, idx := range indexes {
+		if _, err := a.pool.Exec(ctx, idx.Definition); err != nil {
+			return fmt.Errorf("recreate index %s.%s: %w", idx.Schema, idx.IndexName, err)
+		}
+
+		a.logger.Log(InitEvent{
+			Level: "debug",
+			Event: "snapshot.index_recreated",
+			Details: map[string]any{
+				"schema": idx.Schema,
+				"table":  idx.Table,
+				"index":  idx.IndexName,
+			},
+		})
+	}
+
+	return nil
+}
+
 // getFKDependencies retrieves foreign key dependencies for the given tables.
 func (a *SnapshotApplier) getFKDependencies(ctx context.Context, tables []models.SnapshotTableEntry) ([]FKDependency, error) {
 	query := `
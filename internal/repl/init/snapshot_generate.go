@@ -9,6 +9,8 @@ import (
 	"io"
 	"os"
 	"path/filepath"
+	"slices"
+	"strings"
 	"sync"
 	"sync/atomic"
 	"time"
@@ -29,6 +31,8 @@ type TwoPhaseSnapshotOptions struct {
 	OutputPath      string
 	Compression     models.CompressionType
 	ParallelWorkers int
+	Tags            []string            // Labels for grouping and retention (steep_repl.snapshots.tags)
+	ChecksumAlgo    models.ChecksumAlgo // Data file checksum algorithm (default sha256)
 	ProgressFn      func(progress TwoPhaseProgress)
 }
 
@@ -69,8 +73,20 @@ func NewSnapshotGenerator(pool *pgxpool.Pool, manager *Manager) *SnapshotGenerat
 func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, opts TwoPhaseSnapshotOptions) (*models.SnapshotManifest, error) {
 	startTime := time.Now()
 
-	// Generate unique snapshot ID
-	snapshotID := fmt.Sprintf("snap_%s_%s", time.Now().Format("20060102_150405"), sourceNodeID[:8])
+	checksumAlgo := opts.ChecksumAlgo
+	if checksumAlgo == "" {
+		checksumAlgo = models.ChecksumSHA256
+	}
+	if !checksumAlgo.IsValid() {
+		return nil, fmt.Errorf("unsupported checksum algorithm: %s", checksumAlgo)
+	}
+
+	// Generate unique snapshot ID from the extension's sequence so rapid
+	// or concurrent generations never collide
+	var snapshotID string
+	if err := g.pool.QueryRow(ctx, "SELECT steep_repl.next_snapshot_id($1)", sourceNodeID).Scan(&snapshotID); err != nil {
+		return nil, fmt.Errorf("failed to generate snapshot ID: %w", err)
+	}
 
 	g.logger.Log(InitEvent{
 		Level:  "info",
@@ -81,6 +97,7 @@ func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, o
 			"output_path":      opts.OutputPath,
 			"compression":      opts.Compression,
 			"parallel_workers": opts.ParallelWorkers,
+			"checksum_algo":    checksumAlgo,
 		},
 	})
 
@@ -121,6 +138,26 @@ func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, o
 		return nil, fmt.Errorf("failed to get tables: %w", err)
 	}
 
+	// Record the schema allowlist the table list was filtered by
+	var includedSchemas []string
+	if err := g.pool.QueryRow(ctx, "SELECT steep_repl.included_schemas()").Scan(&includedSchemas); err != nil {
+		g.dropSlot(ctx, slotName)
+		return nil, fmt.Errorf("failed to get included schemas: %w", err)
+	}
+
+	partitions, err := g.getPartitions(ctx)
+	if err != nil {
+		g.dropSlot(ctx, slotName)
+		return nil, fmt.Errorf("failed to get partitions: %w", err)
+	}
+
+	// Capture table definitions so the snapshot can seed an empty target
+	schemaFile, err := g.exportSchema(ctx, opts.OutputPath, tables)
+	if err != nil {
+		g.dropSlot(ctx, slotName)
+		return nil, fmt.Errorf("failed to export schema: %w", err)
+	}
+
 	g.sendProgress(opts.ProgressFn, TwoPhaseProgress{
 		SnapshotID:     snapshotID,
 		Phase:          "data",
@@ -134,7 +171,7 @@ func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, o
 		workers = len(tables)
 	}
 
-	tableEntries, totalBytes, err := g.exportTablesParallel(ctx, tables, dataDir, opts.Compression, workers, func(completed int, current string, bytes int64) {
+	tableEntries, totalBytes, err := g.exportTablesParallel(ctx, tables, dataDir, opts.Compression, checksumAlgo, workers, func(completed int, current string, bytes int64) {
 		percent := float32(5 + (completed * 85 / len(tables)))
 		g.sendProgress(opts.ProgressFn, TwoPhaseProgress{
 			SnapshotID:     snapshotID,
@@ -166,6 +203,7 @@ func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, o
 
 	// Create manifest (T082)
 	manifest := &models.SnapshotManifest{
+		FormatVersion:   models.ManifestFormatVersion,
 		SnapshotID:      snapshotID,
 		SourceNode:      sourceNodeID,
 		LSN:             lsn,
@@ -175,6 +213,10 @@ func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, o
 		TotalSizeBytes:  totalBytes,
 		Compression:     opts.Compression,
 		ParallelWorkers: opts.ParallelWorkers,
+		ChecksumAlgo:    checksumAlgo,
+		SchemaFile:      schemaFile,
+		Partitions:      partitions,
+		IncludedSchemas: includedSchemas,
 	}
 
 	// Write manifest to file
@@ -191,7 +233,7 @@ func (g *SnapshotGenerator) Generate(ctx context.Context, sourceNodeID string, o
 	}
 
 	// Record snapshot in database
-	if err := g.recordSnapshot(ctx, manifest, opts.OutputPath); err != nil {
+	if err := g.recordSnapshot(ctx, manifest, opts.OutputPath, opts.Tags); err != nil {
 		g.logger.Log(InitEvent{
 			Level: "warn",
 			Event: "snapshot.record_failed",
@@ -256,15 +298,21 @@ func (g *SnapshotGenerator) dropSlot(ctx context.Context, slotName string) {
 
 // getTablesForExport returns tables that should be included in the snapshot.
 func (g *SnapshotGenerator) getTablesForExport(ctx context.Context) ([]TableInfo, error) {
+	// Partitioned tables hold no rows of their own; their leaf partitions
+	// (relkind 'r') are exported instead so every row is copied once.
+	// steep_repl.eligible_tables() applies steep_repl.included_schemas.
 	rows, err := g.pool.Query(ctx, `
 		SELECT
-			schemaname,
-			tablename,
-			schemaname || '.' || tablename as full_name,
-			pg_table_size(schemaname || '.' || tablename) as size_bytes
-		FROM pg_tables
-		WHERE schemaname NOT IN ('pg_catalog', 'information_schema', 'steep_repl')
-		ORDER BY schemaname, tablename
+			n.nspname,
+			c.relname,
+			n.nspname || '.' || c.relname as full_name,
+			pg_table_size(c.oid) as size_bytes
+		FROM pg_class c
+		JOIN pg_namespace n ON n.oid = c.relnamespace
+		JOIN steep_repl.eligible_tables() e
+			ON e.table_schema = n.nspname AND e.table_name = c.relname
+		WHERE c.relkind = 'r'
+		ORDER BY n.nspname, c.relname
 	`)
 	if err != nil {
 		return nil, err
@@ -283,8 +331,93 @@ func (g *SnapshotGenerator) getTablesForExport(ctx context.Context) ([]TableInfo
 	return tables, rows.Err()
 }
 
+// getPartitions returns the partition hierarchies of the exported schemas,
+// parents before children, so apply can recreate missing partitions.
+func (g *SnapshotGenerator) getPartitions(ctx context.Context) ([]models.SnapshotPartition, error) {
+	rows, err := g.pool.Query(ctx, `
+		SELECT
+			n.nspname,
+			c.relname,
+			COALESCE(pn.nspname, ''),
+			COALESCE(p.relname, ''),
+			CASE WHEN c.relispartition THEN pg_get_expr(c.relpartbound, c.oid) ELSE '' END,
+			CASE WHEN c.relkind = 'p' THEN pg_get_partkeydef(c.oid) ELSE '' END
+		FROM pg_class c
+		JOIN pg_namespace n ON n.oid = c.relnamespace
+		LEFT JOIN pg_inherits i ON c.relispartition AND i.inhrelid = c.oid
+		LEFT JOIN pg_class p ON p.oid = i.inhparent
+		LEFT JOIN pg_namespace pn ON pn.oid = p.relnamespace
+		WHERE (c.relkind = 'p' OR c.relispartition)
+			AND c.relkind IN ('r', 'p')
+			AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'steep_repl')
+			AND (steep_repl.included_schemas() IS NULL
+				OR n.nspname = ANY(steep_repl.included_schemas()))
+		ORDER BY (SELECT count(*) FROM pg_partition_ancestors(c.oid)), n.nspname, c.relname
+	`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var partitions []models.SnapshotPartition
+	for rows.Next() {
+		var p models.SnapshotPartition
+		if err := rows.Scan(&p.Schema, &p.Name, &p.ParentSchema, &p.ParentName, &p.Bound, &p.PartitionKey); err != nil {
+			return nil, err
+		}
+		partitions = append(partitions, p)
+	}
+
+	return partitions, rows.Err()
+}
+
+// schemaFileName is the snapshot-relative path of the schema DDL.
+const schemaFileName = "schema.sql"
+
+// exportSchema writes the DDL for the schemas of the exported tables, as
+// produced by steep_repl.dump_schema_ddl, and returns the snapshot-relative path.
+func (g *SnapshotGenerator) exportSchema(ctx context.Context, outputPath string, tables []TableInfo) (string, error) {
+	var schemas []string
+	for _, t := range tables {
+		if !slices.Contains(schemas, t.SchemaName) {
+			schemas = append(schemas, t.SchemaName)
+		}
+	}
+
+	rows, err := g.pool.Query(ctx, "SELECT ddl FROM steep_repl.dump_schema_ddl($1) ORDER BY ordinal", schemas)
+	if err != nil {
+		return "", err
+	}
+	ddl, err := pgx.CollectRows(rows, pgx.RowTo[string])
+	if err != nil {
+		return "", err
+	}
+
+	var buf strings.Builder
+	for _, stmt := range ddl {
+		buf.WriteString(stmt)
+		buf.WriteString("\n\n")
+	}
+	if err := os.WriteFile(filepath.Join(outputPath, schemaFileName), []byte(buf.String()), 0644); err != nil {
+		return "", err
+	}
+
+	g.logger.Log(InitEvent{
+		Level: "debug",
+		Event: "snapshot.schema_exported",
+		Details: map[string]any{
+			"schemas":    schemas,
+			"statements": len(ddl),
+		},
+	})
+
+	return schemaFileName, nil
+}
+
 // exportTable exports a single table to a file using COPY.
-func (g *SnapshotGenerator) exportTable(ctx context.Context, table TableInfo, dataDir string, compression models.CompressionType) (*models.SnapshotTableEntry, error) {
+func (g *SnapshotGenerator) exportTable(ctx context.Context, table TableInfo, dataDir string, compression models.CompressionType, checksumAlgo models.ChecksumAlgo) (*models.SnapshotTableEntry, error) {
+	startTime := time.Now()
+
 	// Determine output filename based on compression type
 	filename := fmt.Sprintf("%s.%s.csv", table.SchemaName, table.TableName)
 	switch compression {
@@ -364,18 +497,19 @@ func (g *SnapshotGenerator) exportTable(ctx context.Context, table TableInfo, da
 	}
 
 	// Calculate checksum of the output file
-	checksum, err := g.calculateFileChecksum(outputPath)
+	checksum, err := FileChecksum(outputPath, checksumAlgo)
 	if err != nil {
 		return nil, fmt.Errorf("failed to calculate checksum: %w", err)
 	}
 
 	entry := &models.SnapshotTableEntry{
-		Schema:    table.SchemaName,
-		Name:      table.TableName,
-		RowCount:  tag.RowsAffected(),
-		SizeBytes: fileInfo.Size(),
-		Checksum:  checksum,
-		File:      filepath.Join("data", filename),
+		Schema:     table.SchemaName,
+		Name:       table.TableName,
+		RowCount:   tag.RowsAffected(),
+		SizeBytes:  fileInfo.Size(),
+		Checksum:   checksum,
+		File:       filepath.Join("data", filename),
+		DurationMs: time.Since(startTime).Milliseconds(),
 	}
 
 	g.logger.Log(InitEvent{
@@ -387,6 +521,7 @@ func (g *SnapshotGenerator) exportTable(ctx context.Context, table TableInfo, da
 			"size":        entry.SizeBytes,
 			"file":        entry.File,
 			"compression": string(compression),
+			"duration_ms": entry.DurationMs,
 		},
 	})
 
@@ -405,6 +540,7 @@ func (g *SnapshotGenerator) exportTablesParallel(
 	tables []TableInfo,
 	dataDir string,
 	compression models.CompressionType,
+	checksumAlgo models.ChecksumAlgo,
 	workers int,
 	progressFn func(completed int, current string, bytes int64),
 ) ([]models.SnapshotTableEntry, int64, error) {
@@ -442,7 +578,7 @@ func (g *SnapshotGenerator) exportTablesParallel(
 				default:
 				}
 
-				entry, err := g.exportTable(ctx, table, dataDir, compression)
+				entry, err := g.exportTable(ctx, table, dataDir, compression, checksumAlgo)
 				if err != nil {
 					resultChan <- exportTableResult{err: fmt.Errorf("failed to export table %s: %w", table.FullName, err)}
 					cancel() // Cancel other workers
@@ -502,22 +638,6 @@ func (c *countingWriter) Write(p []byte) (int, error) {
 	return n, err
 }
 
-// calculateFileChecksum calculates SHA256 checksum of a file.
-func (g *SnapshotGenerator) calculateFileChecksum(filePath string) (string, error) {
-	file, err := os.Open(filePath)
-	if err != nil {
-		return "", err
-	}
-	defer file.Close()
-
-	hasher := sha256.New()
-	if _, err := io.Copy(hasher, file); err != nil {
-		return "", err
-	}
-
-	return "sha256:" + hex.EncodeToString(hasher.Sum(nil)), nil
-}
-
 // getSequences returns all sequence values for the snapshot.
 func (g *SnapshotGenerator) getSequences(ctx context.Context) ([]models.SnapshotSequenceEntry, error) {
 	rows, err := g.pool.Query(ctx, `
@@ -581,7 +701,7 @@ func (g *SnapshotGenerator) writeManifest(outputPath string, manifest *models.Sn
 }
 
 // recordSnapshot records the snapshot in the database.
-func (g *SnapshotGenerator) recordSnapshot(ctx context.Context, manifest *models.SnapshotManifest, storagePath string) error {
+func (g *SnapshotGenerator) recordSnapshot(ctx context.Context, manifest *models.SnapshotManifest, storagePath string, tags []string) error {
 	// Calculate manifest checksum
 	data, err := manifest.ToJSON()
 	if err != nil {
@@ -594,8 +714,10 @@ func (g *SnapshotGenerator) recordSnapshot(ctx context.Context, manifest *models
 		INSERT INTO steep_repl.snapshots (
 			snapshot_id, source_node_id, lsn, storage_path, size_bytes,
 			table_count, compression, checksum, status, phase,
-			overall_percent, tables_completed, completed_at
-		) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, now())
+			overall_percent, tables_completed, completed_at, tags,
+			rows_total, rows_written, bytes_written
+		) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, now(), COALESCE($13::text[], '{}'),
+			$14, $14, $5)
 		ON CONFLICT (snapshot_id) DO UPDATE SET
 			lsn = EXCLUDED.lsn,
 			storage_path = EXCLUDED.storage_path,
@@ -606,7 +728,11 @@ func (g *SnapshotGenerator) recordSnapshot(ctx context.Context, manifest *models
 			phase = EXCLUDED.phase,
 			overall_percent = EXCLUDED.overall_percent,
 			tables_completed = EXCLUDED.tables_completed,
-			completed_at = EXCLUDED.completed_at
+			completed_at = EXCLUDED.completed_at,
+			tags = EXCLUDED.tags,
+			rows_total = EXCLUDED.rows_total,
+			rows_written = EXCLUDED.rows_written,
+			bytes_written = EXCLUDED.bytes_written
 	`
 
 	_, err = g.pool.Exec(ctx, query,
@@ -622,6 +748,8 @@ func (g *SnapshotGenerator) recordSnapshot(ctx context.Context, manifest *models
 		string(models.PhaseIdle),
 		100.0,                // overall_percent
 		len(manifest.Tables), // tables_completed
+		tags,
+		manifest.TotalRows(), // rows_total and rows_written
 	)
 
 	return err
@@ -650,6 +778,7 @@ func ReadManifest(manifestPath string) (*models.SnapshotManifest, error) {
 }
 
 // VerifySnapshot verifies the integrity of a snapshot by checking checksums.
+// Files are hashed with the algorithm recorded in the manifest.
 func VerifySnapshot(snapshotPath string) ([]string, error) {
 	manifestPath := filepath.Join(snapshotPath, "manifest.json")
 	manifest, err := ReadManifest(manifestPath)
@@ -665,22 +794,12 @@ func VerifySnapshot(snapshotPath string) ([]string, error) {
 			continue
 		}
 
-		// Calculate checksum
-		file, err := os.Open(filePath)
+		actualChecksum, err := FileChecksum(filePath, manifest.ChecksumAlgo)
 		if err != nil {
-			errors = append(errors, fmt.Sprintf("cannot open %s: %v", table.File, err))
-			continue
-		}
-
-		hasher := sha256.New()
-		if _, err := io.Copy(hasher, file); err != nil {
-			file.Close()
-			errors = append(errors, fmt.Sprintf("cannot read %s: %v", table.File, err))
+			errors = append(errors, fmt.Sprintf("cannot checksum %s: %v", table.File, err))
 			continue
 		}
-		file.Close()
 
-		actualChecksum := "sha256:" + hex.EncodeToString(hasher.Sum(nil))
 		if actualChecksum != table.Checksum {
 			errors = append(errors, fmt.Sprintf("checksum mismatch for %s: expected %s, got %s",
 				table.File, table.Checksum, actualChecksum))
@@ -4,11 +4,17 @@ import (
 	"compress/gzip"
 	"crypto/sha256"
 	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
 	"os"
 	"path/filepath"
+	"strings"
 	"testing"
 	"time"
 
+	"github.com/klauspost/compress/zstd"
+	"github.com/pierrec/lz4/v4"
 	replinit "github.com/willibrandon/steep/internal/repl/init"
 	"github.com/willibrandon/steep/internal/repl/models"
 )
@@ -440,6 +446,264 @@ func TestVerifySnapshot_EmptySnapshot(t *testing.T) {
 	}
 }
 
+// =============================================================================
+// Checksum Algorithm Tests
+// =============================================================================
+
+func TestFileChecksum_XXHash64KnownVectors(t *testing.T) {
+	tmpDir := t.TempDir()
+
+	tests := []struct {
+		input string
+		want  string
+	}{
+		{"", "xxhash64:ef46db3751d8e999"},
+		{"a", "xxhash64:d24ec4f1a98c6e5b"},
+		{"abc", "xxhash64:44bc2cf5ad770999"},
+		{"Nobody inspects the spammish repetition", "xxhash64:fbcea83c8a378bf1"},
+	}
+
+	for i, tt := range tests {
+		path := filepath.Join(tmpDir, fmt.Sprintf("vector%d", i))
+		if err := os.WriteFile(path, []byte(tt.input), 0644); err != nil {
+			t.Fatalf("Failed to write file: %v", err)
+		}
+		got, err := replinit.FileChecksum(path, models.ChecksumXXHash64)
+		if err != nil {
+			t.Fatalf("FileChecksum(%q) error = %v", tt.input, err)
+		}
+		if got != tt.want {
+			t.Errorf("FileChecksum(%q) = %s; want %s", tt.input, got, tt.want)
+		}
+	}
+}
+
+func TestFileChecksum_DefaultsToSHA256(t *testing.T) {
+	tmpDir := t.TempDir()
+	testData := []byte("id,name\n1,alice\n")
+	path := filepath.Join(tmpDir, "data.csv")
+	if err := os.WriteFile(path, testData, 0644); err != nil {
+		t.Fatalf("Failed to write file: %v", err)
+	}
+
+	sum := sha256.Sum256(testData)
+	want := "sha256:" + hex.EncodeToString(sum[:])
+
+	got, err := replinit.FileChecksum(path, "")
+	if err != nil {
+		t.Fatalf("FileChecksum() error = %v", err)
+	}
+	if got != want {
+		t.Errorf("FileChecksum() = %s; want %s", got, want)
+	}
+}
+
+func TestFileChecksum_UnsupportedAlgo(t *testing.T) {
+	tmpDir := t.TempDir()
+	path := filepath.Join(tmpDir, "data.csv")
+	if err := os.WriteFile(path, []byte("x"), 0644); err != nil {
+		t.Fatalf("Failed to write file: %v", err)
+	}
+
+	if _, err := replinit.FileChecksum(path, "md5"); err == nil {
+		t.Error("FileChecksum(md5) expected error, got nil")
+	}
+}
+
+// writeChecksumSnapshot writes a single-table snapshot whose manifest records
+// algo and whose table checksum was computed with checksumWith.
+func writeChecksumSnapshot(t *testing.T, algo, checksumWith models.ChecksumAlgo) string {
+	t.Helper()
+	tmpDir := t.TempDir()
+
+	dataDir := filepath.Join(tmpDir, "data")
+	if err := os.MkdirAll(dataDir, 0755); err != nil {
+		t.Fatalf("Failed to create data directory: %v", err)
+	}
+
+	// Larger than one 32-byte block so the streaming path is exercised
+	testData := []byte(strings.Repeat("1,alice,alice@example.com\n", 100))
+	dataFile := filepath.Join(dataDir, "public.users.csv")
+	if err := os.WriteFile(dataFile, testData, 0644); err != nil {
+		t.Fatalf("Failed to write data file: %v", err)
+	}
+
+	checksum, err := replinit.FileChecksum(dataFile, checksumWith)
+	if err != nil {
+		t.Fatalf("FileChecksum() error = %v", err)
+	}
+
+	manifest := &models.SnapshotManifest{
+		SnapshotID:   "snap_test",
+		SourceNode:   "node1",
+		LSN:          "0/1234567",
+		CreatedAt:    time.Now(),
+		Compression:  models.CompressionNone,
+		ChecksumAlgo: algo,
+		Tables: []models.SnapshotTableEntry{
+			{
+				Schema:    "public",
+				Name:      "users",
+				RowCount:  100,
+				SizeBytes: int64(len(testData)),
+				Checksum:  checksum,
+				File:      "data/public.users.csv",
+			},
+		},
+	}
+
+	data, _ := manifest.ToJSON()
+	if err := os.WriteFile(filepath.Join(tmpDir, "manifest.json"), data, 0644); err != nil {
+		t.Fatalf("Failed to write manifest: %v", err)
+	}
+
+	return tmpDir
+}
+
+func TestVerifySnapshot_ChecksumAlgoRoundTrip(t *testing.T) {
+	for _, algo := range models.AllChecksumAlgos() {
+		t.Run(string(algo), func(t *testing.T) {
+			dir := writeChecksumSnapshot(t, algo, algo)
+
+			manifest, err := replinit.ReadManifest(filepath.Join(dir, "manifest.json"))
+			if err != nil {
+				t.Fatalf("ReadManifest() error = %v", err)
+			}
+			if manifest.ChecksumAlgo != algo {
+				t.Errorf("ChecksumAlgo = %q; want %q", manifest.ChecksumAlgo, algo)
+			}
+			if !strings.HasPrefix(manifest.Tables[0].Checksum, string(algo)+":") {
+				t.Errorf("Checksum = %s; want %s: prefix", manifest.Tables[0].Checksum, algo)
+			}
+
+			errors, err := replinit.VerifySnapshot(dir)
+			if err != nil {
+				t.Fatalf("VerifySnapshot() error = %v", err)
+			}
+			if len(errors) != 0 {
+				t.Errorf("VerifySnapshot() errors = %v; want empty", errors)
+			}
+		})
+	}
+}
+
+func TestVerifySnapshot_UsesManifestChecksumAlgo(t *testing.T) {
+	// Manifest says xxhash64 but the recorded checksum is sha256: verify must
+	// hash with xxhash64 and report the mismatch rather than guessing.
+	dir := writeChecksumSnapshot(t, models.ChecksumXXHash64, models.ChecksumSHA256)
+
+	errors, err := replinit.VerifySnapshot(dir)
+	if err != nil {
+		t.Fatalf("VerifySnapshot() error = %v", err)
+	}
+	if len(errors) != 1 {
+		t.Fatalf("VerifySnapshot() errors = %v; want 1 mismatch", errors)
+	}
+	if !strings.Contains(errors[0], "got xxhash64:") {
+		t.Errorf("error = %q; want actual checksum computed with xxhash64", errors[0])
+	}
+}
+
+// =============================================================================
+// DetectCompression Tests
+// =============================================================================
+
+func TestDetectCompression(t *testing.T) {
+	testData := []byte("id,name\n1,alice\n2,bob\n")
+
+	tests := []struct {
+		name  string
+		want  models.CompressionType
+		write func(w io.Writer) error
+	}{
+		{"plain", models.CompressionNone, func(w io.Writer) error {
+			_, err := w.Write(testData)
+			return err
+		}},
+		{"gzip", models.CompressionGzip, func(w io.Writer) error {
+			gz := gzip.NewWriter(w)
+			if _, err := gz.Write(testData); err != nil {
+				return err
+			}
+			return gz.Close()
+		}},
+		{"lz4", models.CompressionLZ4, func(w io.Writer) error {
+			lw := lz4.NewWriter(w)
+			if _, err := lw.Write(testData); err != nil {
+				return err
+			}
+			return lw.Close()
+		}},
+		{"zstd", models.CompressionZstd, func(w io.Writer) error {
+			zw, err := zstd.NewWriter(w)
+			if err != nil {
+				return err
+			}
+			if _, err := zw.Write(testData); err != nil {
+				return err
+			}
+			return zw.Close()
+		}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			path := filepath.Join(t.TempDir(), "public.users.csv")
+			file, err := os.Create(path)
+			if err != nil {
+				t.Fatalf("Failed to create file: %v", err)
+			}
+			if err := tt.write(file); err != nil {
+				t.Fatalf("Failed to write %s data: %v", tt.name, err)
+			}
+			file.Close()
+
+			file, err = os.Open(path)
+			if err != nil {
+				t.Fatalf("Failed to open file: %v", err)
+			}
+			defer file.Close()
+
+			got, err := replinit.DetectCompression(file)
+			if err != nil {
+				t.Fatalf("DetectCompression() error = %v", err)
+			}
+			if got != tt.want {
+				t.Errorf("DetectCompression() = %q; want %q", got, tt.want)
+			}
+
+			// Reader must be rewound so the data can still be imported
+			header := make([]byte, 1)
+			if _, err := file.Read(header); err != nil {
+				t.Fatalf("Read after detection error = %v", err)
+			}
+			if offset, _ := file.Seek(0, io.SeekCurrent); offset != 1 {
+				t.Errorf("offset after one-byte read = %d; want 1", offset)
+			}
+		})
+	}
+}
+
+func TestDetectCompression_EmptyFile(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "empty.csv")
+	if err := os.WriteFile(path, nil, 0644); err != nil {
+		t.Fatalf("Failed to write file: %v", err)
+	}
+	file, err := os.Open(path)
+	if err != nil {
+		t.Fatalf("Failed to open file: %v", err)
+	}
+	defer file.Close()
+
+	got, err := replinit.DetectCompression(file)
+	if err != nil {
+		t.Fatalf("DetectCompression() error = %v", err)
+	}
+	if got != models.CompressionNone {
+		t.Errorf("DetectCompression() = %q; want %q", got, models.CompressionNone)
+	}
+}
+
 // =============================================================================
 // TwoPhaseProgress Tests
 // =============================================================================
@@ -624,6 +888,18 @@ func TestSnapshotTableEntry_FullTableName(t *testing.T) {
 	}
 }
 
+func TestSnapshotTableEntry_ZeroDurationSerialized(t *testing.T) {
+	data, err := json.Marshal(models.SnapshotTableEntry{Schema: "public", Name: "tiny"})
+	if err != nil {
+		t.Fatalf("Marshal() error = %v", err)
+	}
+
+	// A sub-millisecond export must still be distinguishable from an untimed one
+	if !strings.Contains(string(data), `"duration_ms":0`) {
+		t.Errorf("Marshal() = %s; want duration_ms present", data)
+	}
+}
+
 func TestSnapshotSequenceEntry_FullSequenceName(t *testing.T) {
 	entry := models.SnapshotSequenceEntry{
 		Schema: "public",
@@ -656,6 +932,24 @@ func TestSnapshotManifest_TableCount(t *testing.T) {
 	}
 }
 
+func TestSnapshotManifest_TotalRows(t *testing.T) {
+	manifest := models.SnapshotManifest{
+		Tables: []models.SnapshotTableEntry{
+			{Schema: "public", Name: "users", RowCount: 1000},
+			{Schema: "public", Name: "orders", RowCount: 250},
+			{Schema: "public", Name: "empty", RowCount: 0},
+		},
+	}
+
+	if got := manifest.TotalRows(); got != 1250 {
+		t.Errorf("TotalRows() = %d; want 1250", got)
+	}
+
+	if got := (&models.SnapshotManifest{}).TotalRows(); got != 0 {
+		t.Errorf("TotalRows() on empty manifest = %d; want 0", got)
+	}
+}
+
 func TestSnapshotManifest_SequenceCount(t *testing.T) {
 	manifest := models.SnapshotManifest{
 		Sequences: []models.SnapshotSequenceEntry{
@@ -731,3 +1025,48 @@ func TestSnapshotManifest_RoundTrip(t *testing.T) {
 		t.Errorf("len(Sequences) = %d; want %d", len(parsed.Sequences), len(original.Sequences))
 	}
 }
+
+func TestRollingThroughput_ETASuppressedUntilWarm(t *testing.T) {
+	rt := models.NewRollingThroughput(10)
+	start := time.Now()
+
+	var total int64
+	for i := 0; i < 10; i++ {
+		rt.AddAt(start.Add(time.Duration(i)*time.Second), total, 0)
+		total += 10 * 1024 * 1024
+
+		if eta := rt.EstimateETA(100 * 1024 * 1024); eta != 0 {
+			t.Fatalf("EstimateETA after %d samples = %d; want 0 (suppressed)", i+1, eta)
+		}
+	}
+}
+
+func TestRollingThroughput_ETASmoothed(t *testing.T) {
+	rt := models.NewRollingThroughput(10)
+	start := time.Now()
+	remaining := int64(100 * 1000 * 1000)
+
+	// Alternate 5 MB/s and 15 MB/s; instantaneous ETAs would swing 20s ↔ 6s
+	var total int64
+	var etas []int
+	for i := 0; i < 30; i++ {
+		rt.AddAt(start.Add(time.Duration(i)*time.Second), total, 0)
+		if i%2 == 0 {
+			total += 5 * 1000 * 1000
+		} else {
+			total += 15 * 1000 * 1000
+		}
+		if eta := rt.EstimateETA(remaining); eta > 0 {
+			etas = append(etas, eta)
+		}
+	}
+
+	if len(etas) == 0 {
+		t.Fatal("EstimateETA never produced an estimate")
+	}
+	for i, eta := range etas {
+		if eta < 8 || eta > 12 {
+			t.Errorf("smoothed ETA[%d] = %d; want within 8..12 around the 10s mean", i, eta)
+		}
+	}
+}
@@ -4,6 +4,8 @@ package models
 import (
 	"encoding/json"
 	"time"
+
+	"github.com/VividCortex/ewma"
 )
 
 // SnapshotStatus represents the status of a snapshot.
@@ -26,6 +28,8 @@ const (
 	SnapshotStatusCancelled SnapshotStatus = "cancelled"
 	// SnapshotStatusExpired means snapshot has expired and should be cleaned up.
 	SnapshotStatusExpired SnapshotStatus = "expired"
+	// SnapshotStatusFilesMissing means storage verification found missing or corrupt files.
+	SnapshotStatusFilesMissing SnapshotStatus = "files_missing"
 )
 
 // AllSnapshotStatuses returns all valid snapshot statuses.
@@ -39,6 +43,7 @@ func AllSnapshotStatuses() []SnapshotStatus {
 		SnapshotStatusFailed,
 		SnapshotStatusCancelled,
 		SnapshotStatusExpired,
+		SnapshotStatusFilesMissing,
 	}
 }
 
@@ -123,6 +128,32 @@ func (c CompressionType) String() string {
 	return string(c)
 }
 
+// ChecksumAlgo represents the checksum algorithm used for snapshot data files.
+type ChecksumAlgo string
+
+const (
+	ChecksumSHA256   ChecksumAlgo = "sha256"
+	ChecksumXXHash64 ChecksumAlgo = "xxhash64"
+)
+
+// AllChecksumAlgos returns all valid checksum algorithms.
+func AllChecksumAlgos() []ChecksumAlgo {
+	return []ChecksumAlgo{
+		ChecksumSHA256,
+		ChecksumXXHash64,
+	}
+}
+
+// IsValid returns true if the checksum algorithm is recognized.
+func (a ChecksumAlgo) IsValid() bool {
+	for _, valid := range AllChecksumAlgos() {
+		if a == valid {
+			return true
+		}
+	}
+	return false
+}
+
 // Snapshot represents a generated snapshot manifest with progress tracking.
 // This maps to the steep_repl.snapshots table.
 type Snapshot struct {
@@ -136,6 +167,7 @@ type Snapshot struct {
 	StoragePath *string         `db:"storage_path" json:"storage_path,omitempty"`
 	Compression CompressionType `db:"compression" json:"compression"`
 	Checksum    *string         `db:"checksum" json:"checksum,omitempty"`
+	Tags        []string        `db:"tags" json:"tags,omitempty"`
 
 	// Status tracking
 	Status       SnapshotStatus `db:"status" json:"status"`
@@ -218,12 +250,13 @@ func (s *Snapshot) SizeGB() float64 {
 
 // SnapshotTableEntry represents a single table in a snapshot manifest.
 type SnapshotTableEntry struct {
-	Schema    string `json:"schema"`
-	Name      string `json:"name"`
-	RowCount  int64  `json:"row_count"`
-	SizeBytes int64  `json:"size_bytes"`
-	Checksum  string `json:"checksum"`
-	File      string `json:"file"`
+	Schema     string `json:"schema"`
+	Name       string `json:"name"`
+	RowCount   int64  `json:"row_count"`
+	SizeBytes  int64  `json:"size_bytes"`
+	Checksum   string `json:"checksum"`
+	File       string `json:"file"`
+	DurationMs int64  `json:"duration_ms"` // Export time; absent in older manifests
 }
 
 // FullTableName returns the fully qualified table name (schema.name).
@@ -243,8 +276,14 @@ func (e *SnapshotSequenceEntry) FullSequenceName() string {
 	return e.Schema + "." + e.Name
 }
 
+// ManifestFormatVersion is the manifest and file layout version written by
+// this build. Manifests without a format_version predate versioning and use
+// the version 1 layout.
+const ManifestFormatVersion = 1
+
 // SnapshotManifest represents the manifest.json file in a snapshot.
 type SnapshotManifest struct {
+	FormatVersion   int                     `json:"format_version,omitempty"`
 	SnapshotID      string                  `json:"snapshot_id"`
 	SourceNode      string                  `json:"source_node"`
 	LSN             string                  `json:"lsn"`
@@ -254,6 +293,34 @@ type SnapshotManifest struct {
 	TotalSizeBytes  int64                   `json:"total_size_bytes"`
 	Compression     CompressionType         `json:"compression"`
 	ParallelWorkers int                     `json:"parallel_workers"`
+	// ChecksumAlgo is empty for manifests written before it was recorded,
+	// which always used sha256.
+	ChecksumAlgo    ChecksumAlgo            `json:"checksum_algo,omitempty"`
+	// SchemaFile is the snapshot-relative path of the table DDL
+	// (steep_repl.dump_schema_ddl output), empty for older snapshots.
+	SchemaFile      string                  `json:"schema_file,omitempty"`
+	// Partitions records declarative partition hierarchies, parents before
+	// children. Only leaf partitions appear in Tables and carry data.
+	Partitions      []SnapshotPartition     `json:"partitions,omitempty"`
+	// IncludedSchemas is the steep_repl.included_schemas allowlist in effect
+	// when the snapshot was taken; null means all user schemas.
+	IncludedSchemas []string                `json:"included_schemas"`
+}
+
+// SnapshotPartition describes a partitioned table or a partition in a
+// snapshot manifest. Roots have no parent.
+type SnapshotPartition struct {
+	Schema       string `json:"schema"`
+	Name         string `json:"name"`
+	ParentSchema string `json:"parent_schema,omitempty"`
+	ParentName   string `json:"parent_name,omitempty"`
+	Bound        string `json:"bound,omitempty"`         // e.g. FOR VALUES FROM (1) TO (100)
+	PartitionKey string `json:"partition_key,omitempty"` // set on partitioned tables, e.g. RANGE (id)
+}
+
+// FullTableName returns the fully qualified table name (schema.name).
+func (p *SnapshotPartition) FullTableName() string {
+	return p.Schema + "." + p.Name
 }
 
 // TableCount returns the number of tables in the manifest.
@@ -261,6 +328,15 @@ func (m *SnapshotManifest) TableCount() int {
 	return len(m.Tables)
 }
 
+// TotalRows returns the sum of exported row counts across all tables.
+func (m *SnapshotManifest) TotalRows() int64 {
+	var total int64
+	for _, t := range m.Tables {
+		total += t.RowCount
+	}
+	return total
+}
+
 // SequenceCount returns the number of sequences in the manifest.
 func (m *SnapshotManifest) SequenceCount() int {
 	return len(m.Sequences)
@@ -326,9 +402,13 @@ type SnapshotProgress struct {
 }
 
 // RollingThroughput tracks throughput over a rolling time window.
+// ETA uses an EWMA of the rate between consecutive samples, so it stays
+// stable instead of following every burst or stall.
 type RollingThroughput struct {
-	windowSize int           // Number of samples to keep
+	windowSize int                // Number of samples to keep
 	samples    []throughputSample
+	rate       ewma.MovingAverage // Smoothed bytes/sec between consecutive samples
+	last       *throughputSample
 }
 
 type throughputSample struct {
@@ -342,12 +422,27 @@ func NewRollingThroughput(windowSizeSeconds int) *RollingThroughput {
 	return &RollingThroughput{
 		windowSize: windowSizeSeconds,
 		samples:    make([]throughputSample, 0, windowSizeSeconds),
+		// Age of 5 gives ~33% weight to new samples; the average reads 0
+		// until it has warmed up, which suppresses early ETAs
+		rate: ewma.NewMovingAverage(5),
 	}
 }
 
 // Add records a sample of bytes and rows processed.
 func (r *RollingThroughput) Add(bytes, rows int64) {
-	now := time.Now()
+	r.AddAt(time.Now(), bytes, rows)
+}
+
+// AddAt records a sample taken at the given time. Bytes are cumulative
+// totals; the rate since the previous sample feeds the ETA average.
+func (r *RollingThroughput) AddAt(now time.Time, bytes, rows int64) {
+	if r.last != nil {
+		if elapsed := now.Sub(r.last.timestamp).Seconds(); elapsed > 0 && bytes >= r.last.bytes {
+			r.rate.Add(float64(bytes-r.last.bytes) / elapsed)
+		}
+	}
+	r.last = &throughputSample{timestamp: now, bytes: bytes, rows: rows}
+
 	r.samples = append(r.samples, throughputSample{
 		timestamp: now,
 		bytes:     bytes,
@@ -403,9 +498,10 @@ func (r *RollingThroughput) RowsPerSec() float64 {
 	return float64(totalRows) / duration
 }
 
-// EstimateETA estimates the remaining time in seconds based on current throughput.
+// EstimateETA estimates the remaining time in seconds from the smoothed
+// throughput. Returns 0 (unknown) until enough samples have accumulated.
 func (r *RollingThroughput) EstimateETA(remainingBytes int64) int {
-	bytesPerSec := r.BytesPerSec()
+	bytesPerSec := r.rate.Value()
 	if bytesPerSec <= 0 {
 		return 0
 	}
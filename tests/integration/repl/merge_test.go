@@ -2,6 +2,7 @@ package repl_test
 
 import (
 	"context"
+	"encoding/json"
 	"fmt"
 	"log"
 	"net"
@@ -104,6 +105,8 @@ func (s *MergeTestSuite) SetupTest() {
 		DROP SERVER IF EXISTS bad_server CASCADE;
 		DROP TABLE IF EXISTS no_pk CASCADE;
 		DROP TABLE IF EXISTS schema_test CASCADE;
+		DROP TABLE IF EXISTS identity_items CASCADE;
+		DROP TABLE IF EXISTS changed_events CASCADE;
 		DROP PUBLICATION IF EXISTS test_pub_origin CASCADE;
 	`
 
@@ -1228,6 +1231,123 @@ func (s *MergeTestSuite) TestConflictResolution_LastModified() {
 	s.Assert().Equal("A", row2Version, "Row 2: A was modified later")
 }
 
+// TestConflictResolution_LastModifiedCustomColumn tests that last-modified uses
+// the column detected from steep_repl.modified_columns, not a fixed name list.
+func (s *MergeTestSuite) TestConflictResolution_LastModifiedCustomColumn() {
+	ctx := s.ctx
+
+	ddl := `CREATE TABLE changed_events (
+		id INT PRIMARY KEY,
+		version TEXT NOT NULL,
+		changed_at TIMESTAMPTZ
+	)`
+	_, err := s.env.nodeAPool.Exec(ctx, ddl)
+	s.Require().NoError(err)
+	_, err = s.env.nodeBPool.Exec(ctx, ddl)
+	s.Require().NoError(err)
+
+	// Row 1: B is newer; row 2: A is newer
+	_, err = s.env.nodeAPool.Exec(ctx, `
+		INSERT INTO changed_events (id, version, changed_at) VALUES
+			(1, 'A', '2024-01-01 10:00:00+00'),
+			(2, 'A', '2024-01-15 10:00:00+00')
+	`)
+	s.Require().NoError(err)
+	_, err = s.env.nodeBPool.Exec(ctx, `
+		INSERT INTO changed_events (id, version, changed_at) VALUES
+			(1, 'B', '2024-01-10 10:00:00+00'),
+			(2, 'B', '2024-01-05 10:00:00+00')
+	`)
+	s.Require().NoError(err)
+
+	// Node A sessions configured with the non-standard column name
+	poolConfig := s.env.nodeAPool.Config()
+	poolConfig.ConnConfig.RuntimeParams["steep_repl.modified_columns"] = "changed_at"
+	localPool, err := pgxpool.NewWithConfig(ctx, poolConfig)
+	s.Require().NoError(err)
+	defer localPool.Close()
+
+	merger := replinit.NewMerger(localPool, s.env.nodeBPool, nil)
+	s.setupForeignServer()
+
+	_, err = merger.ExecuteMerge(ctx, replinit.MergeConfig{
+		Tables: []replinit.MergeTableInfo{
+			{Schema: "public", Name: "changed_events", PKColumns: []string{"id"}},
+		},
+		Strategy:     replinit.StrategyLastModified,
+		RemoteServer: "node_b_server",
+	})
+	s.Require().NoError(err)
+
+	var row1Version, row2Version string
+	err = s.env.nodeAPool.QueryRow(ctx, "SELECT version FROM changed_events WHERE id = 1").Scan(&row1Version)
+	s.Require().NoError(err)
+	s.Assert().Equal("B", row1Version, "Row 1: B was modified later")
+
+	err = s.env.nodeAPool.QueryRow(ctx, "SELECT version FROM changed_events WHERE id = 2").Scan(&row2Version)
+	s.Require().NoError(err)
+	s.Assert().Equal("A", row2Version, "Row 2: A was modified later")
+
+	var resolvedBy string
+	err = s.env.nodeAPool.QueryRow(ctx, `
+		SELECT DISTINCT resolved_by FROM steep_repl.merge_audit_log
+		WHERE table_name = 'changed_events' AND category = 'conflict'
+	`).Scan(&resolvedBy)
+	s.Require().NoError(err)
+	s.Assert().Equal("strategy:last-modified", resolvedBy)
+}
+
+// TestConflictResolution_GoMatchesSQLResolver runs the same row pairs through
+// the daemon's last-modified resolver and steep_repl.preview_resolution and
+// asserts they reach the same decision.
+func (s *MergeTestSuite) TestConflictResolution_GoMatchesSQLResolver() {
+	ctx := s.ctx
+
+	tests := []struct {
+		name string
+		a    string
+		b    string
+		want string
+	}{
+		{"b newer", `{"updated_at": "2024-01-01T10:00:00Z"}`, `{"updated_at": "2024-01-02T10:00:00Z"}`, "kept_b"},
+		{"a newer", `{"updated_at": "2024-01-02T10:00:00Z"}`, `{"updated_at": "2024-01-01T10:00:00Z"}`, "kept_a"},
+		{"tie", `{"updated_at": "2024-01-01T10:00:00Z"}`, `{"updated_at": "2024-01-01T10:00:00Z"}`, "kept_a"},
+		{"a null", `{"updated_at": null}`, `{"updated_at": "2024-01-01T10:00:00Z"}`, "kept_b"},
+		{"b null", `{"updated_at": "2024-01-01T10:00:00Z"}`, `{"updated_at": null}`, "kept_a"},
+		{"both null", `{"updated_at": null}`, `{"updated_at": null}`, "kept_a"},
+		{"no column", `{"id": 1}`, `{"id": 1}`, "kept_a"},
+		{"unparseable a", `{"updated_at": "soon"}`, `{"updated_at": "2024-01-01T10:00:00Z"}`, "kept_b"},
+		{"numeric ignored", `{"updated_at": 1700000000}`, `{"updated_at": "2024-01-01T10:00:00Z"}`, "kept_b"},
+		{"without time zone", `{"updated_at": "2024-01-01T11:00:00"}`, `{"updated_at": "2024-01-01T10:00:00"}`, "kept_a"},
+		{"postgres text offset", `{"updated_at": "2024-01-01 09:00:00+00"}`, `{"updated_at": "2024-01-01 10:00:00+00"}`, "kept_b"},
+		{"date only", `{"updated_at": "2024-01-02"}`, `{"updated_at": "2024-01-01"}`, "kept_a"},
+		{"fractional seconds", `{"updated_at": "2024-01-01T10:00:00.5Z"}`, `{"updated_at": "2024-01-01T10:00:00.25Z"}`, "kept_a"},
+		{"candidate priority", `{"updated_at": "2024-01-01T10:00:00Z", "modified_at": "2024-03-01T10:00:00Z"}`, `{"updated_at": "2024-02-01T10:00:00Z"}`, "kept_b"},
+		{"later candidate", `{"modified_at": "2024-01-01T10:00:00Z"}`, `{"last_modified": "2024-01-02T10:00:00Z"}`, "kept_b"},
+	}
+
+	merger := replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+
+	for _, tt := range tests {
+		s.Run(tt.name, func() {
+			var a, b map[string]interface{}
+			s.Require().NoError(json.Unmarshal([]byte(tt.a), &a))
+			s.Require().NoError(json.Unmarshal([]byte(tt.b), &b))
+
+			goResolution, _ := merger.ResolveByLastModified(replinit.ConflictDetail{NodeAValue: a, NodeBValue: b})
+
+			var sqlResolution string
+			err := s.env.nodeAPool.QueryRow(ctx,
+				"SELECT steep_repl.preview_resolution('last-modified', $1::jsonb, $2::jsonb)", tt.a, tt.b,
+			).Scan(&sqlResolution)
+			s.Require().NoError(err)
+
+			s.Assert().Equal(tt.want, sqlResolution, "SQL resolver")
+			s.Assert().Equal(sqlResolution, goResolution, "Go resolver should agree with SQL")
+		})
+	}
+}
+
 // TestConflictResolution_Manual tests the manual strategy generates a report.
 // T067-11: Resolution Strategy - manual (Generates Report)
 func (s *MergeTestSuite) TestConflictResolution_Manual() {
@@ -1521,6 +1641,68 @@ func (s *MergeTestSuite) TestDataMovement_Bidirectional() {
 	s.Assert().Equal(4, countB, "Node B should have 4 users")
 }
 
+// TestDataMovement_IdentityAndGeneratedColumns tests that remote-only rows in a
+// table with a GENERATED ALWAYS identity and a stored generated column transfer
+// with their keys intact and the identity sequence advanced past them.
+func (s *MergeTestSuite) TestDataMovement_IdentityAndGeneratedColumns() {
+	ctx := s.ctx
+
+	ddl := `CREATE TABLE identity_items (
+		id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+		name TEXT NOT NULL,
+		name_upper TEXT GENERATED ALWAYS AS (upper(name)) STORED
+	)`
+	_, err := s.env.nodeAPool.Exec(ctx, ddl)
+	s.Require().NoError(err)
+	_, err = s.env.nodeBPool.Exec(ctx, ddl)
+	s.Require().NoError(err)
+
+	// Node A: id 1; Node B: ids 1, 50, 51
+	_, err = s.env.nodeAPool.Exec(ctx, `INSERT INTO identity_items (name) VALUES ('alice')`)
+	s.Require().NoError(err)
+	_, err = s.env.nodeBPool.Exec(ctx, `
+		INSERT INTO identity_items (id, name) OVERRIDING SYSTEM VALUE VALUES
+			(1, 'alice'),
+			(50, 'bob'),
+			(51, 'charlie')
+	`)
+	s.Require().NoError(err)
+
+	merger := replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+	s.setupForeignServer()
+
+	result, err := merger.ExecuteMerge(ctx, replinit.MergeConfig{
+		Tables: []replinit.MergeTableInfo{
+			{Schema: "public", Name: "identity_items", PKColumns: []string{"id"}},
+		},
+		Strategy:     replinit.StrategyPreferNodeA,
+		RemoteServer: "node_b_server",
+	})
+	s.Require().NoError(err)
+	s.Require().Empty(result.Errors)
+	s.Assert().Equal(int64(2), result.RowsTransferredBToA)
+
+	// ASSERT: source keys preserved and generated column recomputed
+	var upper string
+	err = s.env.nodeAPool.QueryRow(ctx, "SELECT name_upper FROM identity_items WHERE id = 50").Scan(&upper)
+	s.Require().NoError(err)
+	s.Assert().Equal("BOB", upper)
+
+	// ASSERT: next local insert doesn't collide with transferred keys
+	var nextID int64
+	err = s.env.nodeAPool.QueryRow(ctx, "INSERT INTO identity_items (name) VALUES ('diana') RETURNING id").Scan(&nextID)
+	s.Require().NoError(err)
+	s.Assert().Greater(nextID, int64(51))
+
+	// ASSERT: handling is recorded for each special column
+	handling := make(map[string]string)
+	for _, h := range result.ColumnHandling {
+		handling[h.Column] = h.Handling
+	}
+	s.Assert().Equal("overriding_system_value", handling["id"])
+	s.Assert().Equal("excluded", handling["name_upper"])
+}
+
 // =============================================================================
 // Category 7: Pre-flight Checks (T067-24 through T067-26)
 // =============================================================================
@@ -1550,6 +1732,31 @@ func (s *MergeTestSuite) TestPreflight_MissingPK_Fails() {
 	s.Assert().True(len(preflight.Errors) > 0, "Should have errors")
 }
 
+// TestPreflight_ExcludedSchema_Fails tests that tables outside
+// steep_repl.included_schemas are rejected.
+func (s *MergeTestSuite) TestPreflight_ExcludedSchema_Fails() {
+	ctx := s.ctx
+
+	poolConfig := s.env.nodeAPool.Config()
+	poolConfig.ConnConfig.RuntimeParams["steep_repl.included_schemas"] = "sales"
+	localPool, err := pgxpool.NewWithConfig(ctx, poolConfig)
+	s.Require().NoError(err)
+	defer localPool.Close()
+
+	merger := replinit.NewMerger(localPool, s.env.nodeBPool, nil)
+
+	tables := []replinit.MergeTableInfo{
+		{Schema: "public", Name: "users", PKColumns: []string{"id"}},
+	}
+
+	preflight, err := merger.RunPreflightChecks(ctx, tables)
+	s.Require().NoError(err)
+
+	s.Require().Len(preflight.Errors, 1)
+	s.Assert().Contains(preflight.Errors[0], "public.users")
+	s.Assert().Contains(preflight.Errors[0], "included_schemas")
+}
+
 // =============================================================================
 // Category 8: Dry-Run Mode Tests (T067-27 through T067-28)
 // =============================================================================
@@ -1665,6 +1872,198 @@ func (s *MergeTestSuite) TestAuditLog_RecordsDecisions() {
 	s.Assert().Greater(auditCount, 0, "Should have audit log entries")
 }
 
+// TestAuditLog_MaxAuditRows tests that the audit log stops at the cap while the
+// merge counters stay accurate and the result is flagged as truncated.
+func (s *MergeTestSuite) TestAuditLog_MaxAuditRows() {
+	ctx := s.ctx
+
+	// SETUP: 5 conflicting rows
+	_, err := s.env.nodeAPool.Exec(ctx, `
+		INSERT INTO users (id, name, version)
+		SELECT i, 'user_' || i, 'A' FROM generate_series(1, 5) AS i
+	`)
+	s.Require().NoError(err)
+
+	_, err = s.env.nodeBPool.Exec(ctx, `
+		INSERT INTO users (id, name, version)
+		SELECT i, 'user_' || i, 'B' FROM generate_series(1, 5) AS i
+	`)
+	s.Require().NoError(err)
+
+	merger := replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+	s.setupForeignServer()
+
+	result, err := merger.ExecuteMerge(ctx, replinit.MergeConfig{
+		Tables: []replinit.MergeTableInfo{
+			{Schema: "public", Name: "users", PKColumns: []string{"id"}},
+		},
+		Strategy:     replinit.StrategyPreferNodeA,
+		RemoteServer: "node_b_server",
+		MaxAuditRows: 2,
+	})
+	s.Require().NoError(err)
+
+	var auditCount int
+	err = s.env.nodeAPool.QueryRow(ctx, `
+		SELECT COUNT(*) FROM steep_repl.merge_audit_log
+		WHERE merge_id = $1
+	`, result.MergeID).Scan(&auditCount)
+	s.Require().NoError(err)
+
+	s.Assert().Equal(2, auditCount, "audit logging should stop at the cap")
+	s.Assert().Equal(int64(2), result.AuditRowsWritten)
+	s.Assert().True(result.AuditTruncated, "result should be flagged as truncated")
+	s.Assert().Equal(int64(5), result.TotalConflicts, "counters should stay accurate")
+	s.Assert().Equal(int64(5), result.ConflictsResolved, "every conflict should still be resolved")
+}
+
+// TestAuditLog_LogLevels tests which categories each log level writes while
+// the merge counters stay complete.
+func (s *MergeTestSuite) TestAuditLog_LogLevels() {
+	ctx := s.ctx
+
+	cases := []struct {
+		level replinit.MergeLogLevel
+		want  map[string]int
+	}{
+		{replinit.LogAll, map[string]int{"match": 2, "conflict": 1, "local_only": 1, "remote_only": 1}},
+		{replinit.LogConflictsOnly, map[string]int{"conflict": 1}},
+		{replinit.LogChangesOnly, map[string]int{"conflict": 1, "local_only": 1, "remote_only": 1}},
+	}
+
+	merger := replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+	s.setupForeignServer()
+
+	for _, tc := range cases {
+		// SETUP: 2 matches, 1 conflict, 1 row unique to each node
+		for _, pool := range []*pgxpool.Pool{s.env.nodeAPool, s.env.nodeBPool} {
+			_, err := pool.Exec(ctx, "TRUNCATE users CASCADE")
+			s.Require().NoError(err)
+			_, err = pool.Exec(ctx, "INSERT INTO users (id, name, version) VALUES (1, 'alice', 'v1'), (2, 'bob', 'v1')")
+			s.Require().NoError(err)
+		}
+		_, err := s.env.nodeAPool.Exec(ctx, "INSERT INTO users (id, name, version) VALUES (3, 'carol', 'A'), (4, 'dave', 'v1')")
+		s.Require().NoError(err)
+		_, err = s.env.nodeBPool.Exec(ctx, "INSERT INTO users (id, name, version) VALUES (3, 'carol', 'B'), (5, 'erin', 'v1')")
+		s.Require().NoError(err)
+
+		result, err := merger.ExecuteMerge(ctx, replinit.MergeConfig{
+			Tables: []replinit.MergeTableInfo{
+				{Schema: "public", Name: "users", PKColumns: []string{"id"}},
+			},
+			Strategy:     replinit.StrategyPreferNodeA,
+			RemoteServer: "node_b_server",
+			LogLevel:     tc.level,
+		})
+		s.Require().NoError(err, "level %s", tc.level)
+
+		rows, err := s.env.nodeAPool.Query(ctx, `
+			SELECT category, COUNT(*) FROM steep_repl.merge_audit_log
+			WHERE merge_id = $1 GROUP BY category
+		`, result.MergeID)
+		s.Require().NoError(err)
+		got := map[string]int{}
+		for rows.Next() {
+			var category string
+			var count int
+			s.Require().NoError(rows.Scan(&category, &count))
+			got[category] = count
+		}
+		s.Require().NoError(rows.Err())
+
+		s.Assert().Equal(tc.want, got, "audit rows for level %s", tc.level)
+		s.Assert().Equal(int64(2), result.TotalMatches, "counters should stay complete at level %s", tc.level)
+		s.Assert().Equal(int64(1), result.TotalConflicts)
+		s.Assert().Equal(int64(1), result.TotalLocalOnly)
+		s.Assert().Equal(int64(1), result.TotalRemoteOnly)
+	}
+}
+
+// TestMerge_ConflictThresholdAborts tests that a merge finding more conflicts
+// than allowed aborts before changing either node and audits what it found,
+// and that a dry run reports the same breach.
+func (s *MergeTestSuite) TestMerge_ConflictThresholdAborts() {
+	ctx := s.ctx
+
+	// SETUP: 5 conflicts plus a row unique to each node
+	_, err := s.env.nodeAPool.Exec(ctx, `
+		INSERT INTO users (id, name, version)
+		SELECT i, 'user_' || i, 'A' FROM generate_series(1, 5) AS i;
+		INSERT INTO users (id, name, version) VALUES (10, 'only_a', 'A');
+	`)
+	s.Require().NoError(err)
+	_, err = s.env.nodeBPool.Exec(ctx, `
+		INSERT INTO users (id, name, version)
+		SELECT i, 'user_' || i, 'B' FROM generate_series(1, 5) AS i;
+		INSERT INTO users (id, name, version) VALUES (20, 'only_b', 'B');
+	`)
+	s.Require().NoError(err)
+
+	state := func(pool *pgxpool.Pool) string {
+		var rows string
+		s.Require().NoError(pool.QueryRow(ctx,
+			"SELECT string_agg(id || ':' || version, ',' ORDER BY id) FROM users").Scan(&rows))
+		return rows
+	}
+	beforeA, beforeB := state(s.env.nodeAPool), state(s.env.nodeBPool)
+
+	merger := replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+	s.setupForeignServer()
+
+	tables := []replinit.MergeTableInfo{{Schema: "public", Name: "users", PKColumns: []string{"id"}}}
+
+	// A dry run reports the breach without auditing anything
+	preview, err := merger.ExecuteMerge(ctx, replinit.MergeConfig{
+		Tables:       tables,
+		Strategy:     replinit.StrategyPreferNodeA,
+		RemoteServer: "node_b_server",
+		MaxConflicts: 3,
+		DryRun:       true,
+	})
+	s.Require().Error(err)
+	s.Assert().ErrorIs(err, replinit.ErrConflictThresholdExceeded)
+	s.Assert().Equal(int64(5), preview.TotalConflicts)
+	s.Assert().Contains(preview.Errors, err.Error(), "the breach should be recorded in the result")
+
+	var previewAudited int
+	s.Require().NoError(s.env.nodeAPool.QueryRow(ctx,
+		"SELECT COUNT(*) FROM steep_repl.merge_audit_log WHERE merge_id = $1", preview.MergeID).Scan(&previewAudited))
+	s.Assert().Zero(previewAudited, "a dry run should not write the audit log")
+
+	result, err := merger.ExecuteMerge(ctx, replinit.MergeConfig{
+		Tables:       tables,
+		Strategy:     replinit.StrategyPreferNodeA,
+		RemoteServer: "node_b_server",
+		MaxConflicts: 3,
+	})
+	s.Require().Error(err)
+	s.Assert().ErrorIs(err, replinit.ErrConflictThresholdExceeded)
+	s.Assert().Equal(int64(5), result.TotalConflicts)
+	s.Assert().Zero(result.ConflictsResolved)
+	s.Assert().Zero(result.RowsTransferredAToB + result.RowsTransferredBToA)
+
+	s.Assert().Equal(beforeA, state(s.env.nodeAPool), "node A should be unchanged")
+	s.Assert().Equal(beforeB, state(s.env.nodeBPool), "node B should be unchanged")
+
+	var audited int
+	s.Require().NoError(s.env.nodeAPool.QueryRow(ctx, `
+		SELECT COUNT(*) FROM steep_repl.merge_audit_log
+		WHERE merge_id = $1 AND category = 'conflict' AND resolution = 'skipped'
+	`, result.MergeID).Scan(&audited))
+	s.Assert().Equal(5, audited, "conflicts found should be left in the audit log")
+
+	// 5 of 7 rows conflict
+	_, err = merger.ExecuteMerge(ctx, replinit.MergeConfig{
+		Tables:           tables,
+		Strategy:         replinit.StrategyPreferNodeA,
+		RemoteServer:     "node_b_server",
+		MaxConflictRatio: 0.5,
+	})
+	s.Assert().ErrorIs(err, replinit.ErrConflictThresholdExceeded)
+	s.Assert().Equal(beforeA, state(s.env.nodeAPool), "node A should be unchanged")
+	s.Assert().Equal(beforeB, state(s.env.nodeBPool), "node B should be unchanged")
+}
+
 // =============================================================================
 // Category 5: Atomicity Tests (T067-19 through T067-21)
 // =============================================================================
@@ -1927,6 +2326,47 @@ func (s *MergeTestSuite) TestPreflight_ActiveTransactions_Warning() {
 		"Should detect active transaction (NoActiveTransactions=false)")
 }
 
+// TestPreflight_VersionMismatch tests that a major steep_repl version mismatch
+// between the nodes blocks the merge unless explicitly allowed.
+func (s *MergeTestSuite) TestPreflight_VersionMismatch() {
+	ctx := s.ctx
+
+	tables := []replinit.MergeTableInfo{
+		{Schema: "public", Name: "users", PKColumns: []string{"id"}},
+	}
+
+	// Peer reports a different major version
+	mockLookup := func(ctx context.Context, pool *pgxpool.Pool) (string, error) {
+		if pool == s.env.nodeBPool {
+			return "2.0.0", nil
+		}
+		return "1.4.0", nil
+	}
+
+	merger := replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+	merger.SetVersionLookup(mockLookup)
+
+	preflight, err := merger.RunPreflightChecks(ctx, tables)
+	s.Require().NoError(err)
+	s.Assert().False(preflight.VersionCompatible, "Should detect major version mismatch")
+	s.Require().Len(preflight.Errors, 1, "Mismatch should refuse the merge")
+	s.Assert().Contains(preflight.Errors[0], "major version mismatch")
+
+	// Allowing the mismatch turns it into a warning
+	merger.SetAllowVersionMismatch(true)
+	preflight, err = merger.RunPreflightChecks(ctx, tables)
+	s.Require().NoError(err)
+	s.Assert().False(preflight.VersionCompatible)
+	s.Assert().Empty(preflight.Errors, "Allowed mismatch should not block the merge")
+	s.Assert().Contains(strings.Join(preflight.Warnings, "\n"), "major version mismatch")
+
+	// The real extension versions match
+	merger = replinit.NewMerger(s.env.nodeAPool, s.env.nodeBPool, nil)
+	preflight, err = merger.RunPreflightChecks(ctx, tables)
+	s.Require().NoError(err)
+	s.Assert().True(preflight.VersionCompatible, "Same extension build should be compatible")
+}
+
 // =============================================================================
 // Category 9: PG18 Feature Integration Tests (T067-29 through T067-31)
 // =============================================================================
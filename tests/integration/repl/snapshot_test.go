@@ -3,9 +3,11 @@ package repl_test
 import (
 	"context"
 	"fmt"
+	"log/slog"
 	"os"
 	"path/filepath"
 	"slices"
+	"strings"
 	"sync/atomic"
 	"testing"
 	"time"
@@ -21,6 +23,8 @@ import (
 	pb "github.com/willibrandon/steep/internal/repl/grpc/proto"
 	replinit "github.com/willibrandon/steep/internal/repl/init"
 	"github.com/willibrandon/steep/internal/repl/models"
+	"google.golang.org/grpc/codes"
+	"google.golang.org/grpc/status"
 )
 
 // =============================================================================
@@ -332,7 +336,7 @@ func (s *SnapshotTestSuite) SetupTest() {
 	testTables := []string{
 		"snapshot_test", "users", "orders", "products",
 		"large_table", "small_table", "compressed_test",
-		"lz4_test", "zstd_test",
+		"lz4_test", "zstd_test", "partitioned_events",
 	}
 	for _, table := range testTables {
 		s.env.sourcePool.Exec(ctx, fmt.Sprintf("DROP TABLE IF EXISTS %s CASCADE", table))
@@ -414,6 +418,59 @@ func tempSocketPathSnapshot() string {
 	return fmt.Sprintf("/tmp/steep-snapshot-%d-%d.sock", time.Now().UnixNano(), n)
 }
 
+// writeSnapshotFixture writes a hand-built uncompressed snapshot to dir under
+// the snapshot directory and returns its path. files maps snapshot-relative
+// paths to contents; each data/<schema>.<table>.csv file gets a manifest
+// table entry counting its rows after the header. mutate, if non-nil,
+// adjusts the manifest before it is written.
+func (s *SnapshotTestSuite) writeSnapshotFixture(dir string, files map[string]string, mutate func(*models.SnapshotManifest)) string {
+	inputPath := filepath.Join(s.env.snapshotDir, dir)
+	s.Require().NoError(os.MkdirAll(filepath.Join(inputPath, "data"), 0755))
+
+	manifest := &models.SnapshotManifest{
+		FormatVersion: models.ManifestFormatVersion,
+		SnapshotID:    "snap_" + strings.ReplaceAll(dir, "-", "_"),
+		SourceNode:    "snapshot-source",
+		CreatedAt:     time.Now(),
+		Compression:   models.CompressionNone,
+	}
+
+	paths := make([]string, 0, len(files))
+	for path := range files {
+		paths = append(paths, path)
+	}
+	slices.Sort(paths)
+	for _, path := range paths {
+		contents := files[path]
+		s.Require().NoError(os.WriteFile(filepath.Join(inputPath, path), []byte(contents), 0644))
+
+		table, ok := strings.CutPrefix(path, "data/")
+		if !ok || !strings.HasSuffix(table, ".csv") {
+			continue
+		}
+		schema, name, _ := strings.Cut(strings.TrimSuffix(table, ".csv"), ".")
+		manifest.Tables = append(manifest.Tables, models.SnapshotTableEntry{
+			Schema:   schema,
+			Name:     name,
+			RowCount: int64(strings.Count(contents, "\n") - 1),
+			File:     path,
+		})
+	}
+
+	if mutate != nil {
+		mutate(manifest)
+	}
+	data, err := manifest.ToJSON()
+	s.Require().NoError(err)
+	s.Require().NoError(os.WriteFile(filepath.Join(inputPath, "manifest.json"), data, 0644))
+	return inputPath
+}
+
+// targetApplier returns a snapshot applier for the target database.
+func (s *SnapshotTestSuite) targetApplier() *replinit.SnapshotApplier {
+	return replinit.NewManager(s.env.targetPool, &config.InitConfig{}, &config.PostgreSQLConfig{}, nil, slog.Default()).SnapshotApplier()
+}
+
 // =============================================================================
 // GenerateSnapshot RPC Tests
 // =============================================================================
@@ -1046,6 +1103,48 @@ func (s *SnapshotTestSuite) TestSnapshot_GenerateMissingOutputPath() {
 	s.Require().Error(err, "Should fail with missing output path")
 }
 
+// TestSnapshot_GenerateRemotePreferredSourceRejected tests that an implicit
+// source resolving to another node is refused rather than exported locally.
+func (s *SnapshotTestSuite) TestSnapshot_GenerateRemotePreferredSourceRejected() {
+	ctx := s.ctx
+	env := s.env
+
+	_, err := env.sourcePool.Exec(ctx, `
+		INSERT INTO steep_repl.nodes (node_id, node_name, host, port, priority, status)
+		VALUES ('snapshot-remote', 'Remote Node', 'remote.example', 5432, 90, 'healthy')
+		ON CONFLICT (node_id) DO UPDATE SET status = 'healthy'
+	`)
+	s.Require().NoError(err)
+	_, err = env.sourcePool.Exec(ctx, "SELECT steep_repl.set_preferred_source('snapshot-remote')")
+	s.Require().NoError(err)
+	defer func() {
+		_, _ = env.sourcePool.Exec(ctx, "SELECT steep_repl.set_preferred_source(NULL)")
+		_, _ = env.sourcePool.Exec(ctx, "DELETE FROM steep_repl.nodes WHERE node_id = 'snapshot-remote'")
+	}()
+
+	conn, err := replgrpc.Dial(ctx, fmt.Sprintf("localhost:%d", env.sourceGRPCPort), "", "", "")
+	s.Require().NoError(err)
+	defer conn.Close()
+
+	initClient := pb.NewInitServiceClient(conn)
+
+	outputPath := filepath.Join(env.snapshotDir, "remote-preferred")
+	stream, err := initClient.GenerateSnapshot(ctx, &pb.GenerateSnapshotRequest{
+		OutputPath:  outputPath,
+		Compression: "none",
+	})
+	if err == nil {
+		_, err = stream.Recv()
+	}
+
+	s.Require().Error(err, "Should refuse a preferred source that is not this node")
+	s.Assert().Equal(codes.FailedPrecondition, status.Code(err))
+	s.Assert().Contains(err.Error(), "snapshot-remote")
+
+	_, statErr := os.Stat(outputPath)
+	s.Assert().True(os.IsNotExist(statErr), "No snapshot should be written")
+}
+
 // TestSnapshot_GenerateInvalidCompression tests validation of compression type.
 func (s *SnapshotTestSuite) TestSnapshot_GenerateInvalidCompression() {
 	ctx := s.ctx
@@ -1152,6 +1251,54 @@ func (s *SnapshotTestSuite) TestSnapshot_RecordedInDatabase() {
 		dbSnapshotID, dbStatus, dbTableCount)
 }
 
+// TestSnapshot_ManifestRowCountsMatchSource tests that the manifest's
+// per-table row counts and the recorded rows_total match the source tables.
+func (s *SnapshotTestSuite) TestSnapshot_ManifestRowCountsMatchSource() {
+	ctx := s.ctx
+	env := s.env
+
+	_, err := env.sourcePool.Exec(ctx, `
+		CREATE TABLE users (id SERIAL PRIMARY KEY, name TEXT);
+		CREATE TABLE orders (id SERIAL PRIMARY KEY, user_id INTEGER REFERENCES users(id));
+		CREATE TABLE products (id SERIAL PRIMARY KEY);
+		INSERT INTO users (name) SELECT 'user_' || i FROM generate_series(1, 137) AS i;
+		INSERT INTO orders (user_id) SELECT 1 + i % 137 FROM generate_series(1, 1021) AS i;
+	`)
+	s.Require().NoError(err)
+
+	outputPath := filepath.Join(env.snapshotDir, "row_counts")
+	generator := replinit.NewManager(env.sourcePool, &config.InitConfig{}, &config.PostgreSQLConfig{}, nil, slog.Default()).SnapshotGenerator()
+	_, err = generator.Generate(ctx, "snapshot-source", replinit.TwoPhaseSnapshotOptions{
+		OutputPath:  outputPath,
+		Compression: models.CompressionNone,
+	})
+	s.Require().NoError(err)
+
+	manifest, err := replinit.ReadManifest(filepath.Join(outputPath, "manifest.json"))
+	s.Require().NoError(err)
+
+	counts := make(map[string]int64)
+	for _, t := range manifest.Tables {
+		counts[t.Schema+"."+t.Name] = t.RowCount
+	}
+	for _, table := range []string{"users", "orders", "products"} {
+		var expected int64
+		s.Require().NoError(env.sourcePool.QueryRow(ctx, fmt.Sprintf("SELECT count(*) FROM %s", table)).Scan(&expected))
+		got, ok := counts["public."+table]
+		s.Require().True(ok, "manifest should include public.%s", table)
+		s.Assert().Equal(expected, got, "manifest row count for public.%s", table)
+	}
+
+	var rowsTotal, rowsWritten int64
+	err = env.sourcePool.QueryRow(ctx, `
+		SELECT rows_total, rows_written FROM steep_repl.snapshots WHERE snapshot_id = $1
+	`, manifest.SnapshotID).Scan(&rowsTotal, &rowsWritten)
+	s.Require().NoError(err)
+	s.Assert().Equal(manifest.TotalRows(), rowsTotal)
+	s.Assert().Equal(manifest.TotalRows(), rowsWritten)
+	s.Assert().GreaterOrEqual(rowsTotal, int64(137+1021))
+}
+
 // =============================================================================
 // Two-Phase Snapshot Round-Trip Tests (T079)
 // =============================================================================
@@ -1815,3 +1962,684 @@ func (s *SnapshotTestSuite) TestSnapshot_ApplyWithParallelWorkers() {
 		s.Assert().Equal(100, count, "Table %s should have 100 rows", t)
 	}
 }
+
+// TestSnapshot_TransactionalApplyRollsBack tests that a transactional apply
+// failing on a later table leaves earlier tables' prior data intact.
+func (s *SnapshotTestSuite) TestSnapshot_TransactionalApplyRollsBack() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP TABLE IF EXISTS txn_a, txn_b",
+		"CREATE TABLE txn_a (id INT PRIMARY KEY, data TEXT)",
+		"CREATE TABLE txn_b (id INT PRIMARY KEY, n INT)",
+		"INSERT INTO txn_a VALUES (1, 'old')",
+		"INSERT INTO txn_b VALUES (1, 1)",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+
+	// Hand-built snapshot: txn_a loads cleanly, txn_b has a bad value
+	inputPath := s.writeSnapshotFixture("transactional", map[string]string{
+		"data/public.txn_a.csv": "id,data\n2,new\n",
+		"data/public.txn_b.csv": "id,n\n2,not-a-number\n",
+	}, nil)
+
+	applier := s.targetApplier()
+
+	_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:     inputPath,
+		Transactional: true,
+	})
+	s.Require().Error(err, "apply should fail on the malformed txn_b row")
+
+	var txnAData string
+	var count int
+	err = env.targetPool.QueryRow(ctx, "SELECT count(*), max(data) FROM txn_a").Scan(&count, &txnAData)
+	s.Require().NoError(err)
+	s.Assert().Equal(1, count, "txn_a should keep its prior row")
+	s.Assert().Equal("old", txnAData, "txn_a should not contain snapshot data")
+
+	// With valid data the same transactional apply commits both tables
+	s.writeSnapshotFixture("transactional", map[string]string{
+		"data/public.txn_a.csv": "id,data\n2,new\n",
+		"data/public.txn_b.csv": "id,n\n2,2\n",
+	}, nil)
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:     inputPath,
+		Transactional: true,
+	})
+	s.Require().NoError(err)
+
+	var ids string
+	err = env.targetPool.QueryRow(ctx,
+		"SELECT (SELECT string_agg(id::text, ',') FROM txn_a) || '|' || (SELECT string_agg(id::text, ',') FROM txn_b)").Scan(&ids)
+	s.Require().NoError(err)
+	s.Assert().Equal("2|2", ids, "both tables should be replaced by snapshot data")
+}
+
+// TestSnapshot_DeferIndexesApply tests that applying with deferred indexes
+// loads the same rows as an in-place load, rebuilds the dropped indexes, and
+// keeps unique indexes in place so duplicates are still rejected.
+func (s *SnapshotTestSuite) TestSnapshot_DeferIndexesApply() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP TABLE IF EXISTS defer_items",
+		"CREATE TABLE defer_items (id INT PRIMARY KEY, sku TEXT NOT NULL, qty INT)",
+		"CREATE INDEX defer_items_sku_idx ON defer_items (sku)",
+		"CREATE UNIQUE INDEX defer_items_sku_qty_idx ON defer_items (sku, qty)",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+
+	csv := "id,sku,qty\n"
+	for i := 1; i <= 500; i++ {
+		csv += fmt.Sprintf("%d,sku-%d,%d\n", i, i%50, i)
+	}
+	inputPath := s.writeSnapshotFixture("defer-indexes", map[string]string{
+		"data/public.defer_items.csv": csv,
+	}, nil)
+
+	applier := s.targetApplier()
+
+	var uniqueIndexOID uint32
+	s.Require().NoError(env.targetPool.QueryRow(ctx,
+		"SELECT 'defer_items_sku_qty_idx'::regclass::oid").Scan(&uniqueIndexOID))
+
+	var checksums []string
+	for _, deferIndexes := range []bool{true, false} {
+		var phases []string
+		_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+			InputPath:    inputPath,
+			DeferIndexes: deferIndexes,
+			ProgressFn: func(p replinit.TwoPhaseProgress) {
+				phases = append(phases, p.Phase)
+			},
+		})
+		s.Require().NoError(err, "apply with DeferIndexes=%v", deferIndexes)
+
+		var checksum string
+		err = env.targetPool.QueryRow(ctx,
+			"SELECT count(*) || ':' || md5(string_agg(id || sku || qty, ',' ORDER BY id)) FROM defer_items").Scan(&checksum)
+		s.Require().NoError(err)
+		checksums = append(checksums, checksum)
+
+		var indexes int
+		err = env.targetPool.QueryRow(ctx, `
+			SELECT count(*) FROM pg_index i
+			JOIN pg_class c ON c.oid = i.indexrelid
+			WHERE i.indrelid = 'defer_items'::regclass AND i.indisvalid
+			  AND c.relname IN ('defer_items_pkey', 'defer_items_sku_idx', 'defer_items_sku_qty_idx')`).Scan(&indexes)
+		s.Require().NoError(err)
+		s.Assert().Equal(3, indexes, "all indexes should exist after apply with DeferIndexes=%v", deferIndexes)
+
+		var oid uint32
+		s.Require().NoError(env.targetPool.QueryRow(ctx,
+			"SELECT 'defer_items_sku_qty_idx'::regclass::oid").Scan(&oid))
+		s.Assert().Equal(uniqueIndexOID, oid, "unique index should never be dropped")
+
+		if deferIndexes {
+			s.Assert().Contains(phases, "indexes", "deferred apply should report the index rebuild phase")
+		} else {
+			s.Assert().NotContains(phases, "indexes", "in-place apply should skip the index rebuild phase")
+		}
+	}
+
+	s.Assert().True(strings.HasPrefix(checksums[0], "500:"), "all rows should load: %s", checksums[0])
+	s.Assert().Equal(checksums[0], checksums[1], "deferred and in-place index loads should produce identical rows")
+
+	// A duplicate (sku, qty) pair must fail the data phase, not the rebuild
+	duplicatePath := s.writeSnapshotFixture("defer-indexes-duplicate", map[string]string{
+		"data/public.defer_items.csv": "id,sku,qty\n1,sku-dup,1\n2,sku-dup,1\n",
+	}, nil)
+	var phases []string
+	_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:    duplicatePath,
+		DeferIndexes: true,
+		ProgressFn: func(p replinit.TwoPhaseProgress) {
+			phases = append(phases, p.Phase)
+		},
+	})
+	s.Require().Error(err, "duplicate rows should be rejected by the unique index")
+	s.Assert().NotContains(phases, "indexes", "the load should fail before the index rebuild phase")
+
+	var valid bool
+	s.Require().NoError(env.targetPool.QueryRow(ctx, `
+		SELECT indisvalid FROM pg_index WHERE indexrelid = 'defer_items_sku_qty_idx'::regclass`).Scan(&valid))
+	s.Assert().True(valid, "unique index should remain after the failed apply")
+}
+
+// TestSnapshot_SelectiveApply tests applying one table from a multi-table
+// snapshot leaves the other tables untouched.
+func (s *SnapshotTestSuite) TestSnapshot_SelectiveApply() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP TABLE IF EXISTS sel_a, sel_b",
+		"CREATE TABLE sel_a (id INT PRIMARY KEY, data TEXT)",
+		"CREATE TABLE sel_b (id INT PRIMARY KEY, data TEXT)",
+		"INSERT INTO sel_a VALUES (1, 'target')",
+		"INSERT INTO sel_b VALUES (1, 'corrupted')",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+
+	inputPath := s.writeSnapshotFixture("selective", map[string]string{
+		"data/public.sel_a.csv": "id,data\n1,snapshot\n2,snapshot\n",
+		"data/public.sel_b.csv": "id,data\n1,restored\n",
+	}, nil)
+
+	applier := s.targetApplier()
+
+	// Unknown tables are rejected before anything is touched
+	_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath: inputPath,
+		Tables:    []string{"sel_b", "public.sel_missing"},
+	})
+	s.Require().Error(err)
+	s.Assert().Contains(err.Error(), "public.sel_missing")
+
+	applied, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath: inputPath,
+		Tables:    []string{"sel_b"},
+	})
+	s.Require().NoError(err)
+	s.Assert().Len(applied.Tables, 1, "only the selected table should be applied")
+
+	var a, b string
+	err = env.targetPool.QueryRow(ctx,
+		"SELECT (SELECT string_agg(id || '=' || data, ',' ORDER BY id) FROM sel_a), (SELECT string_agg(id || '=' || data, ',' ORDER BY id) FROM sel_b)").Scan(&a, &b)
+	s.Require().NoError(err)
+	s.Assert().Equal("1=target", a, "unselected table should be unchanged")
+	s.Assert().Equal("1=restored", b, "selected table should be restored from the snapshot")
+}
+
+// TestSnapshot_SelectiveApplyGuards tests that a selective apply restores
+// only the selected tables' sequences and refuses what it cannot do safely.
+func (s *SnapshotTestSuite) TestSnapshot_SelectiveApplyGuards() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP TABLE IF EXISTS sel_child, sel_parent, sel_other",
+		"CREATE TABLE sel_parent (id SERIAL PRIMARY KEY, data TEXT)",
+		"CREATE TABLE sel_child (id INT PRIMARY KEY, parent_id INT REFERENCES sel_parent (id))",
+		"CREATE TABLE sel_other (id SERIAL PRIMARY KEY, data TEXT)",
+		"INSERT INTO sel_parent (data) VALUES ('target')",
+		"INSERT INTO sel_child VALUES (1, 1)",
+		"INSERT INTO sel_other (data) VALUES ('target')",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+	defer func() {
+		_, _ = env.targetPool.Exec(ctx, "DROP TABLE IF EXISTS sel_child, sel_parent, sel_other")
+	}()
+
+	inputPath := s.writeSnapshotFixture("selective-guards", map[string]string{
+		"data/public.sel_parent.csv": "id,data\n1,snapshot\n",
+		"data/public.sel_child.csv":  "id,parent_id\n1,1\n",
+		"data/public.sel_other.csv":  "id,data\n1,snapshot\n",
+	}, func(m *models.SnapshotManifest) {
+		m.Sequences = []models.SnapshotSequenceEntry{
+			{Schema: "public", Name: "sel_parent_id_seq", Value: 100},
+			{Schema: "public", Name: "sel_other_id_seq", Value: 500},
+		}
+	})
+
+	applier := s.targetApplier()
+
+	// A subscription would replicate tables the target never received
+	_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:          inputPath,
+		Tables:             []string{"sel_other"},
+		CreateSubscription: true,
+	})
+	s.Require().Error(err)
+	s.Assert().Contains(err.Error(), "subscription")
+
+	// Reloading the parent alone would truncate the unselected child
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath: inputPath,
+		Tables:    []string{"sel_parent"},
+	})
+	s.Require().Error(err)
+	s.Assert().Contains(err.Error(), "public.sel_child references public.sel_parent")
+
+	var childRows int
+	s.Require().NoError(env.targetPool.QueryRow(ctx, "SELECT count(*) FROM sel_child").Scan(&childRows))
+	s.Assert().Equal(1, childRows, "unselected child should be untouched")
+
+	// Selecting parent and child restores only the parent's sequence
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath: inputPath,
+		Tables:    []string{"sel_parent", "sel_child"},
+	})
+	s.Require().NoError(err)
+
+	var parentSeq, otherSeq int64
+	err = env.targetPool.QueryRow(ctx,
+		"SELECT (SELECT last_value FROM sel_parent_id_seq), (SELECT last_value FROM sel_other_id_seq)").Scan(&parentSeq, &otherSeq)
+	s.Require().NoError(err)
+	s.Assert().Equal(int64(100), parentSeq, "selected table's sequence should be restored")
+	s.Assert().Equal(int64(1), otherSeq, "unselected table's sequence should be unchanged")
+}
+
+// TestSnapshot_IncrementalApply verifies that an incremental apply over a
+// mostly-identical target writes only the rows that differ.
+func (s *SnapshotTestSuite) TestSnapshot_IncrementalApply() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP TABLE IF EXISTS inc_items",
+		"CREATE TABLE inc_items (id INT PRIMARY KEY, data TEXT)",
+		"INSERT INTO inc_items SELECT g, 'row ' || g FROM generate_series(1, 100) g",
+		"UPDATE inc_items SET data = 'drifted' WHERE id = 10",
+		"INSERT INTO inc_items VALUES (500, 'target only')",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+
+	// Snapshot: rows 1-100 as generated, plus new row 101
+	var csv strings.Builder
+	csv.WriteString("id,data\n")
+	for i := 1; i <= 101; i++ {
+		fmt.Fprintf(&csv, "%d,row %d\n", i, i)
+	}
+
+	inputPath := s.writeSnapshotFixture("incremental", map[string]string{
+		"data/public.inc_items.csv": csv.String(),
+	}, nil)
+
+	// Remember row versions so we can tell which rows were rewritten
+	var xminBefore string
+	err := env.targetPool.QueryRow(ctx,
+		"SELECT string_agg(xmin::text, ',' ORDER BY id) FROM inc_items WHERE id <> 10 AND id <= 100").Scan(&xminBefore)
+	s.Require().NoError(err)
+
+	applier := s.targetApplier()
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:     inputPath,
+		Incremental:   true,
+		DeleteMissing: true,
+	})
+	s.Require().NoError(err)
+
+	// Unchanged rows keep their original tuple versions
+	var xminAfter string
+	err = env.targetPool.QueryRow(ctx,
+		"SELECT string_agg(xmin::text, ',' ORDER BY id) FROM inc_items WHERE id <> 10 AND id <= 100").Scan(&xminAfter)
+	s.Require().NoError(err)
+	s.Assert().Equal(xminBefore, xminAfter, "identical rows should not be rewritten")
+
+	var drifted, added string
+	var missing, total int
+	err = env.targetPool.QueryRow(ctx, `
+		SELECT
+			(SELECT data FROM inc_items WHERE id = 10),
+			(SELECT data FROM inc_items WHERE id = 101),
+			(SELECT count(*) FROM inc_items WHERE id = 500),
+			(SELECT count(*) FROM inc_items)`).Scan(&drifted, &added, &missing, &total)
+	s.Require().NoError(err)
+	s.Assert().Equal("row 10", drifted, "changed row should be restored")
+	s.Assert().Equal("row 101", added, "new row should be inserted")
+	s.Assert().Equal(0, missing, "row absent from the snapshot should be deleted")
+	s.Assert().Equal(101, total)
+}
+
+// TestSnapshot_SchemaOnlyApply verifies that a schema-only apply replays the
+// snapshot's schema.sql and loads no data.
+func (s *SnapshotTestSuite) TestSnapshot_SchemaOnlyApply() {
+	ctx := s.ctx
+	env := s.env
+
+	_, err := env.targetPool.Exec(ctx, "DROP TABLE IF EXISTS schema_only_child, schema_only_parent")
+	s.Require().NoError(err)
+
+	ddl := `CREATE TABLE public.schema_only_child (
+    id integer NOT NULL,
+    parent_id integer
+);
+
+CREATE TABLE public.schema_only_parent (
+    id integer NOT NULL
+);
+
+ALTER TABLE public.schema_only_parent ADD CONSTRAINT schema_only_parent_pkey PRIMARY KEY (id);
+
+ALTER TABLE public.schema_only_child ADD CONSTRAINT schema_only_child_parent_id_fkey FOREIGN KEY (parent_id) REFERENCES public.schema_only_parent(id);
+`
+	inputPath := s.writeSnapshotFixture("schema-only", map[string]string{
+		"schema.sql":                         ddl,
+		"data/public.schema_only_parent.csv": "id\n1\n",
+	}, func(m *models.SnapshotManifest) {
+		m.SchemaFile = "schema.sql"
+	})
+
+	applier := s.targetApplier()
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:  inputPath,
+		SchemaOnly: true,
+	})
+	s.Require().NoError(err)
+
+	var fks, rows int
+	err = env.targetPool.QueryRow(ctx, `
+		SELECT
+			(SELECT count(*) FROM pg_constraint WHERE conname = 'schema_only_child_parent_id_fkey'),
+			(SELECT count(*) FROM schema_only_parent)`).Scan(&fks, &rows)
+	s.Require().NoError(err)
+	s.Assert().Equal(1, fks, "constraints from schema.sql should exist")
+	s.Assert().Equal(0, rows, "schema-only apply should not load data")
+
+	_, err = env.targetPool.Exec(ctx, "DROP TABLE schema_only_child, schema_only_parent")
+	s.Require().NoError(err)
+}
+
+// TestSnapshot_PartitionedTable tests that a range-partitioned table is
+// exported through its leaf partitions and that apply recreates them.
+func (s *SnapshotTestSuite) TestSnapshot_PartitionedTable() {
+	ctx := s.ctx
+	env := s.env
+
+	partitionDDL := `
+		CREATE TABLE partitioned_events (
+			id INTEGER NOT NULL,
+			happened_on DATE NOT NULL,
+			PRIMARY KEY (id, happened_on)
+		) PARTITION BY RANGE (happened_on);
+		CREATE INDEX partitioned_events_day_idx ON partitioned_events (happened_on);
+	`
+	_, err := env.sourcePool.Exec(ctx, partitionDDL+`
+		CREATE TABLE partitioned_events_q1 PARTITION OF partitioned_events FOR VALUES FROM ('2024-01-01') TO ('2024-04-01');
+		CREATE TABLE partitioned_events_q2 PARTITION OF partitioned_events FOR VALUES FROM ('2024-04-01') TO ('2024-07-01');
+		CREATE TABLE partitioned_events_rest PARTITION OF partitioned_events DEFAULT;
+		INSERT INTO partitioned_events
+		SELECT i, DATE '2024-01-01' + (i % 365) FROM generate_series(1, 300) AS i;
+	`)
+	s.Require().NoError(err)
+
+	outputPath := filepath.Join(env.snapshotDir, "partitioned")
+	generator := replinit.NewManager(env.sourcePool, &config.InitConfig{}, &config.PostgreSQLConfig{}, nil, slog.Default()).SnapshotGenerator()
+	manifest, err := generator.Generate(ctx, "snapshot-source", replinit.TwoPhaseSnapshotOptions{
+		OutputPath:      outputPath,
+		Compression:     models.CompressionNone,
+		ParallelWorkers: 2,
+	})
+	s.Require().NoError(err)
+
+	// Only leaf partitions carry data, so every row is captured exactly once
+	var leaves []string
+	var exportedRows int64
+	for _, t := range manifest.Tables {
+		s.Assert().NotEqual("partitioned_events", t.Name, "partitioned parent should not be exported")
+		if strings.HasPrefix(t.Name, "partitioned_events_") {
+			leaves = append(leaves, t.Name)
+			exportedRows += t.RowCount
+		}
+	}
+	s.Assert().ElementsMatch([]string{"partitioned_events_q1", "partitioned_events_q2", "partitioned_events_rest"}, leaves)
+	s.Assert().Equal(int64(300), exportedRows)
+
+	s.Require().Len(manifest.Partitions, 4)
+	s.Assert().Equal("partitioned_events", manifest.Partitions[0].Name)
+	s.Assert().Equal("RANGE (happened_on)", manifest.Partitions[0].PartitionKey)
+	for _, p := range manifest.Partitions[1:] {
+		s.Assert().Equal("partitioned_events", p.ParentName)
+		s.Assert().NotEmpty(p.Bound)
+	}
+
+	// Target has only the parent; apply recreates the partitions
+	_, err = env.targetPool.Exec(ctx, "DROP TABLE IF EXISTS partitioned_events CASCADE")
+	s.Require().NoError(err)
+	_, err = env.targetPool.Exec(ctx, partitionDDL)
+	s.Require().NoError(err)
+
+	applier := s.targetApplier()
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:       outputPath,
+		ParallelWorkers: 2,
+		Tables:          leaves,
+	})
+	s.Require().NoError(err)
+
+	var partitions, rows int
+	var bound string
+	err = env.targetPool.QueryRow(ctx, `
+		SELECT
+			(SELECT count(*) FROM pg_inherits WHERE inhparent = 'partitioned_events'::regclass),
+			(SELECT count(*) FROM partitioned_events),
+			(SELECT pg_get_expr(relpartbound, oid) FROM pg_class WHERE relname = 'partitioned_events_q2')`).Scan(&partitions, &rows, &bound)
+	s.Require().NoError(err)
+	s.Assert().Equal(3, partitions, "apply should recreate every partition")
+	s.Assert().Equal(300, rows)
+	s.Assert().Equal("FOR VALUES FROM ('2024-04-01') TO ('2024-07-01')", bound)
+
+	var sourceQ1, targetQ1 int
+	s.Require().NoError(env.sourcePool.QueryRow(ctx, "SELECT count(*) FROM partitioned_events_q1").Scan(&sourceQ1))
+	s.Require().NoError(env.targetPool.QueryRow(ctx, "SELECT count(*) FROM partitioned_events_q1").Scan(&targetQ1))
+	s.Assert().Equal(sourceQ1, targetQ1, "rows should land in the same partition")
+
+	_, err = env.targetPool.Exec(ctx, "DROP TABLE partitioned_events CASCADE")
+	s.Require().NoError(err)
+}
+
+// TestSnapshot_GenerateRespectsIncludedSchemas tests that tables outside
+// steep_repl.included_schemas are left out of the snapshot.
+func (s *SnapshotTestSuite) TestSnapshot_GenerateRespectsIncludedSchemas() {
+	ctx := s.ctx
+	env := s.env
+
+	_, err := env.sourcePool.Exec(ctx, `
+		CREATE SCHEMA excluded_schema;
+		CREATE TABLE excluded_schema.hidden (id INTEGER PRIMARY KEY);
+		INSERT INTO excluded_schema.hidden SELECT generate_series(1, 10);
+	`)
+	s.Require().NoError(err)
+	defer func() {
+		_, _ = env.sourcePool.Exec(ctx, "DROP SCHEMA excluded_schema CASCADE")
+	}()
+
+	poolConfig := env.sourcePool.Config()
+	poolConfig.ConnConfig.RuntimeParams["steep_repl.included_schemas"] = "public"
+	pool, err := pgxpool.NewWithConfig(ctx, poolConfig)
+	s.Require().NoError(err)
+	defer pool.Close()
+
+	generator := replinit.NewManager(pool, &config.InitConfig{}, &config.PostgreSQLConfig{}, nil, slog.Default()).SnapshotGenerator()
+	manifest, err := generator.Generate(ctx, "snapshot-source", replinit.TwoPhaseSnapshotOptions{
+		OutputPath:  filepath.Join(env.snapshotDir, "included-schemas"),
+		Compression: models.CompressionNone,
+	})
+	s.Require().NoError(err)
+
+	s.Require().NotEmpty(manifest.Tables)
+	for _, t := range manifest.Tables {
+		s.Assert().Equal("public", t.Schema, "%s.%s should have been excluded", t.Schema, t.Name)
+	}
+	s.Assert().Equal([]string{"public"}, manifest.IncludedSchemas)
+
+	// The allowlist is also persisted in manifest.json
+	data, err := os.ReadFile(filepath.Join(env.snapshotDir, "included-schemas", "manifest.json"))
+	s.Require().NoError(err)
+	written, err := models.ParseManifest(data)
+	s.Require().NoError(err)
+	s.Assert().Equal([]string{"public"}, written.IncludedSchemas)
+}
+
+// TestSnapshot_ApplyOntoSourceRejected tests that a snapshot is not applied
+// onto the node it came from unless explicitly allowed.
+func (s *SnapshotTestSuite) TestSnapshot_ApplyOntoSourceRejected() {
+	ctx := s.ctx
+	env := s.env
+
+	_, err := env.targetPool.Exec(ctx, "DROP TABLE IF EXISTS self_apply; CREATE TABLE self_apply (id INTEGER PRIMARY KEY)")
+	s.Require().NoError(err)
+
+	inputPath := s.writeSnapshotFixture("self-apply", map[string]string{
+		"data/public.self_apply.csv": "id\n1\n2\n",
+	}, func(m *models.SnapshotManifest) {
+		m.SourceNode = "snapshot-target"
+	})
+
+	applier := s.targetApplier()
+
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{InputPath: inputPath})
+	s.Require().Error(err, "applying onto the source node should be refused")
+	s.Assert().Contains(err.Error(), "refusing to apply it onto its own source")
+
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{InputPath: inputPath, AllowSelf: true})
+	s.Require().NoError(err, "AllowSelf should permit the apply")
+
+	var rows int
+	s.Require().NoError(env.targetPool.QueryRow(ctx, "SELECT count(*) FROM self_apply").Scan(&rows))
+	s.Assert().Equal(2, rows)
+
+	_, err = env.targetPool.Exec(ctx, "DROP TABLE self_apply")
+	s.Require().NoError(err)
+}
+
+// TestSnapshot_ApplyWithColumnMap verifies that a snapshot whose column was
+// renamed on the target only applies when the rename is mapped.
+func (s *SnapshotTestSuite) TestSnapshot_ApplyWithColumnMap() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP TABLE IF EXISTS remap_items",
+		"CREATE TABLE remap_items (id INT PRIMARY KEY, display_name TEXT, qty INT)",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+
+	// The snapshot predates the rename of full_name to display_name, and its
+	// column order differs from the target's
+	inputPath := s.writeSnapshotFixture("column-map", map[string]string{
+		"data/public.remap_items.csv": "id,qty,full_name\n1,5,alpha\n2,7,beta\n",
+	}, nil)
+
+	applier := s.targetApplier()
+
+	_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{InputPath: inputPath})
+	s.Require().Error(err, "renamed column should fail without a map")
+	s.Assert().Contains(err.Error(), "full_name")
+
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath: inputPath,
+		ColumnMap: map[string]map[string]string{
+			"remap_items": {"full_name": "display_name"},
+		},
+	})
+	s.Require().NoError(err, "mapped rename should apply")
+
+	var names string
+	var total int
+	s.Require().NoError(env.targetPool.QueryRow(ctx,
+		"SELECT string_agg(display_name, ',' ORDER BY id), sum(qty) FROM remap_items").Scan(&names, &total))
+	s.Assert().Equal("alpha,beta", names)
+	s.Assert().Equal(12, total)
+
+	_, err = env.targetPool.Exec(ctx, "DROP TABLE remap_items")
+	s.Require().NoError(err)
+}
+
+// TestSnapshot_ManifestFormatVersion verifies that apply accepts the current
+// manifest format and refuses a newer one unless forced.
+func (s *SnapshotTestSuite) TestSnapshot_ManifestFormatVersion() {
+	ctx := s.ctx
+	env := s.env
+
+	_, err := env.targetPool.Exec(ctx, "DROP TABLE IF EXISTS format_items; CREATE TABLE format_items (id INTEGER PRIMARY KEY)")
+	s.Require().NoError(err)
+
+	files := map[string]string{"data/public.format_items.csv": "id\n1\n2\n"}
+
+	applier := s.targetApplier()
+
+	current := s.writeSnapshotFixture("format-current", files, nil)
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{InputPath: current})
+	s.Require().NoError(err, "current format version should apply")
+
+	newer := s.writeSnapshotFixture("format-newer", files, func(m *models.SnapshotManifest) {
+		m.FormatVersion = models.ManifestFormatVersion + 1
+	})
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{InputPath: newer})
+	s.Require().Error(err, "newer format version should be refused")
+	s.Assert().Contains(err.Error(), "manifest format version")
+
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{InputPath: newer, ForceFormat: true})
+	s.Require().NoError(err, "ForceFormat should permit the apply")
+
+	_, err = env.targetPool.Exec(ctx, "DROP TABLE format_items")
+	s.Require().NoError(err)
+}
+
+// TestSnapshot_ApplyIntoTargetSchema verifies that a snapshot can be loaded
+// into a scratch schema while the original tables keep their data.
+func (s *SnapshotTestSuite) TestSnapshot_ApplyIntoTargetSchema() {
+	ctx := s.ctx
+	env := s.env
+
+	for _, ddl := range []string{
+		"DROP SCHEMA IF EXISTS snapshot_scratch CASCADE",
+		"DROP TABLE IF EXISTS scratch_items",
+		"CREATE TABLE scratch_items (id INT PRIMARY KEY, data TEXT)",
+		"INSERT INTO scratch_items VALUES (1, 'production')",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+
+	inputPath := s.writeSnapshotFixture("target-schema", map[string]string{
+		"data/public.scratch_items.csv": "id,data\n1,snapshot\n2,snapshot\n3,snapshot\n",
+	}, nil)
+
+	applier := s.targetApplier()
+
+	// A subscription would replicate into the original tables instead
+	_, err := applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:          inputPath,
+		TargetSchema:       "snapshot_scratch",
+		CreateSubscription: true,
+	})
+	s.Require().Error(err)
+	s.Assert().Contains(err.Error(), "subscription")
+
+	var schemas int
+	s.Require().NoError(env.targetPool.QueryRow(ctx,
+		"SELECT count(*) FROM pg_namespace WHERE nspname = 'snapshot_scratch'").Scan(&schemas))
+	s.Assert().Equal(0, schemas, "rejected apply should not create the scratch schema")
+
+	_, err = applier.Apply(ctx, "snapshot-target", replinit.TwoPhaseApplyOptions{
+		InputPath:    inputPath,
+		TargetSchema: "snapshot_scratch",
+	})
+	s.Require().NoError(err)
+
+	var scratchRows int
+	s.Require().NoError(env.targetPool.QueryRow(ctx,
+		"SELECT count(*) FROM snapshot_scratch.scratch_items WHERE data = 'snapshot'").Scan(&scratchRows))
+	s.Assert().Equal(3, scratchRows, "scratch schema should hold the snapshot data")
+
+	var original string
+	s.Require().NoError(env.targetPool.QueryRow(ctx,
+		"SELECT string_agg(id || ':' || data, ',' ORDER BY id) FROM public.scratch_items").Scan(&original))
+	s.Assert().Equal("1:production", original, "original table should be untouched")
+
+	for _, ddl := range []string{
+		"DROP SCHEMA snapshot_scratch CASCADE",
+		"DROP TABLE scratch_items",
+	} {
+		_, err := env.targetPool.Exec(ctx, ddl)
+		s.Require().NoError(err)
+	}
+}